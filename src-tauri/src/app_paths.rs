@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Bundle identifier from tauri.conf.json. Tauri derives the app data and
+/// config directories from it, so the headless CLI does the same.
+pub const APP_IDENTIFIER: &str = "com.cli-config-editor.app";
+
+/// Event carrying a warning message from background work
+pub const WARNING_EVENT: &str = "app-warning";
+
+/// Locations of the app's own state (MCP config, versions, tool registry).
+/// Implemented by the Tauri `AppHandle` for the GUI and by `StandalonePaths`
+/// for the CLI, which reads and writes the same files without a running app.
+pub trait AppPaths {
+    fn app_data_dir(&self) -> Option<PathBuf>;
+    fn app_config_dir(&self) -> Option<PathBuf>;
+
+    /// Report a failure that has no caller to return it to, such as a
+    /// history commit or snapshot after a write that already landed, or work
+    /// triggered by the file watcher.
+    fn warn(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+impl AppPaths for AppHandle {
+    fn app_data_dir(&self) -> Option<PathBuf> {
+        self.path().app_data_dir().ok()
+    }
+
+    fn app_config_dir(&self) -> Option<PathBuf> {
+        self.path().app_config_dir().ok()
+    }
+
+    /// Logged, and shown in the frontend as a toast.
+    fn warn(&self, message: &str) {
+        eprintln!("{}", message);
+        let _ = self.emit(WARNING_EVENT, message);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StandalonePaths {
+    pub data_dir: Option<PathBuf>,
+    pub config_dir: Option<PathBuf>,
+}
+
+impl StandalonePaths {
+    /// The same directories Tauri resolves for this bundle identifier.
+    pub fn new() -> Self {
+        StandalonePaths {
+            data_dir: dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER)),
+            config_dir: dirs::config_dir().map(|dir| dir.join(APP_IDENTIFIER)),
+        }
+    }
+}
+
+impl Default for StandalonePaths {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppPaths for StandalonePaths {
+    fn app_data_dir(&self) -> Option<PathBuf> {
+        self.data_dir.clone()
+    }
+
+    fn app_config_dir(&self) -> Option<PathBuf> {
+        self.config_dir.clone()
+    }
+}
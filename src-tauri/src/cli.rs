@@ -0,0 +1,851 @@
+//! Headless command line mode. `cli-config-editor <subcommand>` runs the same
+//! operations the GUI exposes through `invoke`, prints JSON on stdout and
+//! reports failures as `{ "error_type", "message" }` on stderr.
+
+use crate::app_paths::{AppPaths, StandalonePaths};
+use crate::commands::{
+    expand_path, list_backups, read_file, read_json_path, restore_backup, write_file_internal,
+    write_json_path_internal, BackupSettings, CommandError,
+};
+use crate::config::ConfigFormat;
+use crate::formats::{resolve_format, ConfigDocument};
+use crate::mcp::{
+    get_mcp_tool_statuses_internal, preview_mcp_config_content_internal,
+    preview_mcp_sync_all_internal, preview_mcp_sync_internal, sync_mcp_to_all_internal,
+    sync_mcp_to_tool_internal, McpError,
+};
+use crate::secrets::SecretError;
+use crate::ssh::{backup_remote_config, read_remote_config, write_remote_config};
+use crate::transaction::recover_transactions;
+use crate::validation::SchemaTarget;
+use crate::versions::{
+    list_config_ids_internal, list_versions_internal, load_version_internal, save_version_internal,
+    VersionError,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Process exit codes. Scripts can rely on these staying stable.
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// I/O, SSH or any other operational failure
+    pub const FAILURE: i32 = 1;
+    /// Invalid arguments (also used by clap)
+    pub const USAGE: i32 = 2;
+    /// File, JSON path, version, backup or tool not found
+    pub const NOT_FOUND: i32 = 3;
+    /// Content failed to parse or validate
+    pub const INVALID: i32 = 4;
+    /// MCP sync stopped on conflicts or failed for some tools
+    pub const INCOMPLETE: i32 = 5;
+    /// The file changed on disk since `--expected-token` was taken
+    pub const CONFLICT: i32 = 6;
+}
+
+const SUBCOMMANDS: &[&str] = &[
+    "get",
+    "set",
+    "mcp",
+    "versions",
+    "backups",
+    "remote",
+    "help",
+    "--help",
+    "-h",
+    "--version",
+    "-V",
+];
+
+#[derive(Parser)]
+#[command(
+    name = "cli-config-editor",
+    version,
+    about = "Scriptable access to CLI Config Editor operations"
+)]
+struct Cli {
+    /// Override the app data directory (MCP config, versions)
+    #[arg(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+    /// Override the app config directory (user tool definitions)
+    #[arg(long, global = true, value_name = "DIR")]
+    config_dir: Option<PathBuf>,
+    /// Pretty-print JSON output
+    #[arg(long, global = true)]
+    pretty: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a config file, or the value at a dotted path, as JSON
+    Get {
+        path: String,
+        json_path: Option<String>,
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+    },
+    /// Set the value at a dotted path. VALUE is parsed as JSON, falling back to a plain string
+    Set {
+        path: String,
+        json_path: String,
+        value: String,
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+        #[command(flatten)]
+        write: WriteArgs,
+        /// Refuse the write on schema errors, e.g. `claude-code:Settings`
+        #[arg(long, value_name = "TOOL_ID:LABEL")]
+        validate_as: Option<String>,
+    },
+    /// MCP server sync
+    #[command(subcommand)]
+    Mcp(McpCommand),
+    /// Saved config versions
+    #[command(subcommand)]
+    Versions(VersionsCommand),
+    /// Backup files next to a config
+    #[command(subcommand)]
+    Backups(BackupsCommand),
+    /// Config files on SSH hosts (`user@host:path`)
+    #[command(subcommand)]
+    Remote(RemoteCommand),
+}
+
+#[derive(Subcommand)]
+enum McpCommand {
+    /// Sync status of every MCP-capable tool
+    Status,
+    /// Show what a sync would change without writing
+    Preview {
+        /// Tool to preview; omit with --all
+        tool_id: Option<String>,
+        #[arg(long, conflicts_with = "tool_id")]
+        all: bool,
+        /// Print the resulting config file instead of the merge summary
+        #[arg(long, requires = "tool_id")]
+        content: bool,
+    },
+    /// Write source servers into tool configs
+    Sync {
+        tool_id: Option<String>,
+        /// Sync every enabled tool
+        #[arg(long, conflicts_with = "tool_id")]
+        all: bool,
+        /// Resolve conflicts by taking the source definition
+        #[arg(long, requires = "tool_id")]
+        accept_source: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VersionsCommand {
+    /// Config ids that have saved versions
+    Configs,
+    /// Versions of a config, newest first
+    List { config_id: String },
+    /// Save the current content of a file as a version
+    Save {
+        config_id: String,
+        #[arg(long, value_name = "PATH")]
+        from: String,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Write a saved version back to a file
+    Restore {
+        config_id: String,
+        version_id: String,
+        #[arg(long, value_name = "PATH")]
+        to: String,
+        #[command(flatten)]
+        write: WriteArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupsCommand {
+    /// Backups of a config file
+    List { path: String },
+    /// Restore a backup (the latest one when BACKUP_PATH is omitted)
+    Restore {
+        path: String,
+        backup_path: Option<String>,
+        /// Don't back up the current file before restoring
+        #[arg(long)]
+        no_backup: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RemoteCommand {
+    /// Read a remote file
+    Read { ssh_path: String },
+    /// Write a remote file from --file or stdin
+    Write {
+        ssh_path: String,
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// Back up the remote file first
+        #[arg(long)]
+        backup: bool,
+    },
+}
+
+#[derive(Args)]
+struct WriteArgs {
+    /// Don't create a backup of the file being replaced
+    #[arg(long)]
+    no_backup: bool,
+    /// Number of rotating backups to keep
+    #[arg(long, default_value_t = 1)]
+    max_backups: u32,
+    /// Fail with a conflict unless the file still has this token
+    #[arg(long)]
+    expected_token: Option<String>,
+}
+
+impl WriteArgs {
+    fn backup_settings(&self) -> BackupSettings {
+        BackupSettings {
+            enabled: !self.no_backup,
+            max_backups: self.max_backups,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Json,
+    Yaml,
+    Toml,
+    Ini,
+}
+
+impl From<FormatArg> for ConfigFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Json => ConfigFormat::Json,
+            FormatArg::Yaml => ConfigFormat::Yaml,
+            FormatArg::Toml => ConfigFormat::Toml,
+            FormatArg::Ini => ConfigFormat::Ini,
+        }
+    }
+}
+
+/// A failed CLI operation, already classified for its exit code.
+#[derive(Debug)]
+struct CliError {
+    code: i32,
+    error_type: String,
+    message: String,
+    details: Option<Value>,
+}
+
+impl CliError {
+    fn new(code: i32, error_type: &str, message: impl Into<String>) -> Self {
+        CliError {
+            code,
+            error_type: error_type.to_string(),
+            message: message.into(),
+            details: None,
+        }
+    }
+}
+
+impl From<CommandError> for CliError {
+    fn from(e: CommandError) -> Self {
+        let code = match e {
+            CommandError::ConfigNotFound(_) | CommandError::JsonPathNotFound(_) => {
+                exit_code::NOT_FOUND
+            }
+            CommandError::JsonParse(_)
+            | CommandError::ConfigParse(_)
+            | CommandError::UnsupportedFormat(_)
+            | CommandError::Validation(_) => exit_code::INVALID,
+            CommandError::PathResolution(_) | CommandError::InvalidArgument(_) => exit_code::USAGE,
+            CommandError::Io(_) | CommandError::Transaction(_) => exit_code::FAILURE,
+            CommandError::Conflict { .. } => exit_code::CONFLICT,
+        };
+        // Reuse the IPC error shape so scripts and the frontend see the same fields
+        let serialized = serde_json::to_value(&e).unwrap_or_default();
+        CliError {
+            code,
+            error_type: serialized["error_type"]
+                .as_str()
+                .unwrap_or("Io")
+                .to_string(),
+            message: serialized["message"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| e.to_string()),
+            details: serialized.get("details").cloned(),
+        }
+    }
+}
+
+impl From<McpError> for CliError {
+    fn from(e: McpError) -> Self {
+        let (code, error_type) = match e {
+            McpError::NotFound(_) => (exit_code::NOT_FOUND, "NotFound"),
+            McpError::ToolNotSupported(_) => (exit_code::NOT_FOUND, "ToolNotSupported"),
+            McpError::Json(_) | McpError::InvalidFormat(_) | McpError::Format(_) => {
+                (exit_code::INVALID, "InvalidFormat")
+            }
+            McpError::Io(_) | McpError::AppDataDir => (exit_code::FAILURE, "Io"),
+            McpError::Ssh(_) => (exit_code::FAILURE, "Ssh"),
+            McpError::Secret(SecretError::NotFound(_)) => (exit_code::NOT_FOUND, "SecretNotFound"),
+            McpError::Secret(_) => (exit_code::FAILURE, "Secret"),
+            McpError::Transaction(_) => (exit_code::FAILURE, "Transaction"),
+        };
+        CliError::new(code, error_type, e.to_string())
+    }
+}
+
+impl From<VersionError> for CliError {
+    fn from(e: VersionError) -> Self {
+        let (code, error_type) = match e {
+            VersionError::NotFound(_) => (exit_code::NOT_FOUND, "NotFound"),
+            VersionError::Json(_) => (exit_code::INVALID, "JsonParse"),
+            VersionError::Corrupt(_) => (exit_code::INVALID, "Corrupt"),
+            VersionError::Io(_) | VersionError::AppDataDir => (exit_code::FAILURE, "Io"),
+        };
+        CliError::new(code, error_type, e.to_string())
+    }
+}
+
+type CliResult = Result<(Value, i32), CliError>;
+
+fn ok(value: impl Serialize) -> CliResult {
+    Ok((
+        serde_json::to_value(value).unwrap_or(Value::Null),
+        exit_code::SUCCESS,
+    ))
+}
+
+/// True when the arguments ask for CLI mode rather than the GUI. Only known
+/// subcommands count, so launcher-supplied arguments still open the window.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--pretty" => continue,
+            "--data-dir" | "--config-dir" => {
+                rest.next();
+            }
+            arg if arg.starts_with("--data-dir=") || arg.starts_with("--config-dir=") => continue,
+            arg => return SUBCOMMANDS.contains(&arg),
+        }
+    }
+    false
+}
+
+/// Run a CLI invocation and return the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    let cli = match Cli::try_parse_from(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let code = if e.use_stderr() {
+                exit_code::USAGE
+            } else {
+                exit_code::SUCCESS
+            };
+            let _ = e.print();
+            return code;
+        }
+    };
+
+    let defaults = StandalonePaths::new();
+    let paths = StandalonePaths {
+        data_dir: cli.data_dir.clone().or(defaults.data_dir),
+        config_dir: cli.config_dir.clone().or(defaults.config_dir),
+    };
+
+    let print = |value: &Value| {
+        let text = if cli.pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        };
+        text.unwrap_or_default()
+    };
+
+    if let Err(e) = recover_transactions(&paths) {
+        eprintln!("Failed to recover interrupted writes: {}", e);
+    }
+
+    match execute(&paths, cli.command) {
+        Ok((value, code)) => {
+            println!("{}", print(&value));
+            code
+        }
+        Err(e) => {
+            let mut error = json!({ "error_type": e.error_type, "message": e.message });
+            if let Some(details) = e.details {
+                error["details"] = details;
+            }
+            eprintln!("{}", print(&error));
+            e.code
+        }
+    }
+}
+
+fn execute(paths: &impl AppPaths, command: Command) -> CliResult {
+    match command {
+        Command::Get {
+            path,
+            json_path,
+            format,
+        } => get(path, json_path, format.map(Into::into)),
+        Command::Set {
+            path,
+            json_path,
+            value,
+            format,
+            write,
+            validate_as,
+        } => {
+            let validate = validate_as
+                .as_deref()
+                .map(parse_schema_target)
+                .transpose()?;
+            // Accept bare strings so `set settings.json model opus` does what it looks like
+            let value = serde_json::from_str::<Value>(&value).unwrap_or(Value::String(value));
+            let content = serde_json::to_string(&value).unwrap_or_default();
+            let token = write_json_path_internal(
+                paths,
+                path.clone(),
+                json_path.clone(),
+                content,
+                Some(write.backup_settings()),
+                format.map(Into::into),
+                validate,
+                write.expected_token.clone(),
+                None,
+            )?;
+            ok(json!({ "path": path, "jsonPath": json_path, "value": value, "token": token }))
+        }
+        Command::Mcp(command) => mcp(paths, command),
+        Command::Versions(command) => versions(paths, command),
+        Command::Backups(command) => backups(command),
+        Command::Remote(command) => remote(command),
+    }
+}
+
+fn parse_schema_target(arg: &str) -> Result<SchemaTarget, CliError> {
+    match arg.split_once(':') {
+        Some((tool_id, label)) if !tool_id.is_empty() && !label.is_empty() => Ok(SchemaTarget {
+            tool_id: tool_id.to_string(),
+            label: label.to_string(),
+        }),
+        _ => Err(CliError::new(
+            exit_code::USAGE,
+            "Usage",
+            format!("--validate-as expects TOOL_ID:LABEL, got '{}'", arg),
+        )),
+    }
+}
+
+fn get(path: String, json_path: Option<String>, format: Option<ConfigFormat>) -> CliResult {
+    let value = match json_path {
+        Some(json_path) => {
+            let content = read_json_path(path, json_path, format)?.content;
+            serde_json::from_str(&content).map_err(|e| CommandError::JsonParse(e.to_string()))?
+        }
+        None => {
+            let content = read_file(path.clone())?.content;
+            let expanded =
+                expand_path(&path).ok_or_else(|| CommandError::PathResolution(path.clone()))?;
+            let format = resolve_format(format, &expanded);
+            ConfigDocument::parse(&content, &format)
+                .map_err(CommandError::from)?
+                .to_value()
+        }
+    };
+    ok(value)
+}
+
+fn mcp(paths: &impl AppPaths, command: McpCommand) -> CliResult {
+    match command {
+        McpCommand::Status => ok(get_mcp_tool_statuses_internal(paths)?),
+        McpCommand::Preview {
+            tool_id: Some(tool_id),
+            content: true,
+            ..
+        } => ok(preview_mcp_config_content_internal(paths, tool_id, None)?),
+        McpCommand::Preview {
+            tool_id: Some(tool_id),
+            ..
+        } => ok(preview_mcp_sync_internal(paths, tool_id)?),
+        McpCommand::Preview { all: true, .. } => ok(preview_mcp_sync_all_internal(paths)?),
+        McpCommand::Sync {
+            tool_id: Some(tool_id),
+            accept_source,
+            ..
+        } => {
+            let resolved = if accept_source {
+                let preview = preview_mcp_sync_internal(paths, tool_id.clone())?;
+                Some(
+                    preview
+                        .merge_result
+                        .conflicts
+                        .into_iter()
+                        .map(|c| c.source_server)
+                        .collect(),
+                )
+            } else {
+                None
+            };
+            let result = sync_mcp_to_tool_internal(paths, tool_id, resolved, None)?;
+            let code = if result.success {
+                exit_code::SUCCESS
+            } else {
+                exit_code::INCOMPLETE
+            };
+            Ok((serde_json::to_value(result).unwrap_or_default(), code))
+        }
+        McpCommand::Sync { all: true, .. } => {
+            let results = sync_mcp_to_all_internal(paths, None, None)?;
+            let code = if results.iter().all(|r| r.success) {
+                exit_code::SUCCESS
+            } else {
+                exit_code::INCOMPLETE
+            };
+            Ok((serde_json::to_value(results).unwrap_or_default(), code))
+        }
+        McpCommand::Preview { .. } | McpCommand::Sync { .. } => Err(CliError::new(
+            exit_code::USAGE,
+            "Usage",
+            "pass a TOOL_ID or --all",
+        )),
+    }
+}
+
+fn versions(paths: &impl AppPaths, command: VersionsCommand) -> CliResult {
+    match command {
+        VersionsCommand::Configs => ok(list_config_ids_internal(paths)?),
+        VersionsCommand::List { config_id } => ok(list_versions_internal(paths, config_id)?),
+        VersionsCommand::Save {
+            config_id,
+            from,
+            name,
+            description,
+        } => {
+            let content = read_file(from)?.content;
+            let version = save_version_internal(
+                paths,
+                config_id,
+                name,
+                content,
+                description,
+                "manual".to_string(),
+            )?;
+            ok(crate::versions::VersionMetadata::from(&version))
+        }
+        VersionsCommand::Restore {
+            config_id,
+            version_id,
+            to,
+            write,
+        } => {
+            let version = load_version_internal(paths, config_id.clone(), version_id)?;
+            let token = write_file_internal(
+                paths,
+                to.clone(),
+                version.content.clone(),
+                Some(write.backup_settings()),
+                None,
+                write.expected_token.clone(),
+                Some(config_id),
+            )?;
+            ok(json!({
+                "path": to,
+                "token": token,
+                "version": crate::versions::VersionMetadata::from(&version),
+            }))
+        }
+    }
+}
+
+fn backups(command: BackupsCommand) -> CliResult {
+    match command {
+        BackupsCommand::List { path } => ok(list_backups(path)?),
+        BackupsCommand::Restore {
+            path,
+            backup_path,
+            no_backup,
+        } => {
+            let backup_path = match backup_path {
+                Some(backup_path) => backup_path,
+                None => list_backups(path.clone())?
+                    .into_iter()
+                    .max_by_key(|b| b.modified_at)
+                    .map(|b| b.path)
+                    .ok_or_else(|| {
+                        CliError::new(
+                            exit_code::NOT_FOUND,
+                            "ConfigNotFound",
+                            format!("No backups for {}", path),
+                        )
+                    })?,
+            };
+            restore_backup(path.clone(), backup_path.clone(), !no_backup)?;
+            ok(json!({ "path": path, "restoredFrom": backup_path }))
+        }
+    }
+}
+
+fn remote(command: RemoteCommand) -> CliResult {
+    let ssh_error = |message: String| CliError::new(exit_code::FAILURE, "Ssh", message);
+    match command {
+        RemoteCommand::Read { ssh_path } => {
+            let content = read_remote_config(ssh_path.clone()).map_err(ssh_error)?;
+            ok(json!({ "sshPath": ssh_path, "content": content }))
+        }
+        RemoteCommand::Write {
+            ssh_path,
+            file,
+            backup,
+        } => {
+            let content = match file {
+                Some(file) => std::fs::read_to_string(&file)
+                    .map_err(|e| CliError::from(CommandError::Io(e)))?,
+                None => {
+                    let mut content = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut content)
+                        .map_err(|e| CliError::from(CommandError::Io(e)))?;
+                    content
+                }
+            };
+            let backup_path = if backup {
+                Some(backup_remote_config(ssh_path.clone()).map_err(ssh_error)?)
+            } else {
+                None
+            };
+            write_remote_config(ssh_path.clone(), content).map_err(ssh_error)?;
+            ok(json!({ "sshPath": ssh_path, "backupPath": backup_path }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cli-test-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn run_in(dir: &std::path::Path, command: &[&str]) -> CliResult {
+        let paths = StandalonePaths {
+            data_dir: Some(dir.join("data")),
+            config_dir: Some(dir.join("config")),
+        };
+        let mut full = vec!["cli-config-editor"];
+        full.extend_from_slice(command);
+        let cli = Cli::try_parse_from(full).unwrap();
+        execute(&paths, cli.command)
+    }
+
+    #[test]
+    fn test_is_cli_invocation() {
+        assert!(is_cli_invocation(&args(&["app", "get", "x"])));
+        assert!(is_cli_invocation(&args(&["app", "--help"])));
+        assert!(!is_cli_invocation(&args(&["app"])));
+        assert!(!is_cli_invocation(&args(&["app", "-psn_0_12345"])));
+        assert!(is_cli_invocation(&args(&[
+            "app",
+            "--pretty",
+            "--data-dir",
+            "/tmp/x",
+            "mcp",
+            "status"
+        ])));
+        assert!(!is_cli_invocation(&args(&["app", "--pretty"])));
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let dir = temp_dir("get-set");
+        let file = dir.join("settings.json");
+        fs::write(&file, "{\n  // keep me\n  \"model\": \"sonnet\"\n}\n").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let (value, code) = run_in(&dir, &["set", &file, "model", "opus"]).unwrap();
+        assert_eq!(code, exit_code::SUCCESS);
+        assert_eq!(value["value"], "opus");
+
+        run_in(
+            &dir,
+            &["set", &file, "permissions.allow", r#"["Bash(ls)"]"#],
+        )
+        .unwrap();
+
+        let (value, _) = run_in(&dir, &["get", &file]).unwrap();
+        assert_eq!(value["model"], "opus");
+        assert_eq!(value["permissions"]["allow"][0], "Bash(ls)");
+        assert!(fs::read_to_string(&file).unwrap().contains("// keep me"));
+
+        let (value, _) = run_in(&dir, &["get", &file, "model"]).unwrap();
+        assert_eq!(value, "opus");
+
+        let err = run_in(&dir, &["get", &file, "missing"]).unwrap_err();
+        assert_eq!(err.code, exit_code::NOT_FOUND);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_set_with_stale_token_conflicts() {
+        let dir = temp_dir("conflict");
+        let file = dir.join("settings.json");
+        fs::write(&file, r#"{"model": "sonnet"}"#).unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let token = read_file(file.clone()).unwrap().token;
+        let (value, _) = run_in(
+            &dir,
+            &["set", &file, "model", "opus", "--expected-token", &token],
+        )
+        .unwrap();
+        let token = value["token"].as_str().unwrap().to_string();
+
+        // Someone else edits the file after we took the token
+        fs::write(&file, r#"{"model": "haiku"}"#).unwrap();
+
+        let err = run_in(
+            &dir,
+            &["set", &file, "model", "sonnet", "--expected-token", &token],
+        )
+        .unwrap_err();
+        assert_eq!(err.code, exit_code::CONFLICT);
+        assert_eq!(err.error_type, "Conflict");
+        let details = err.details.unwrap();
+        assert_eq!(details["currentContent"], r#"{"model": "haiku"}"#);
+        assert_eq!(details["token"], read_file(file.clone()).unwrap().token);
+        assert_eq!(fs::read_to_string(&file).unwrap(), r#"{"model": "haiku"}"#);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_set_refuses_invalid_content() {
+        let dir = temp_dir("validate");
+        let file = dir.join("settings.json").to_string_lossy().to_string();
+
+        let err = run_in(
+            &dir,
+            &[
+                "set",
+                &file,
+                "permissions.allow",
+                "Bash(ls)",
+                "--validate-as",
+                "claude-code:Settings",
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err.code, exit_code::INVALID);
+        assert_eq!(err.error_type, "Validation");
+        assert!(!std::path::Path::new(&file).exists());
+
+        run_in(
+            &dir,
+            &[
+                "set",
+                &file,
+                "permissions.allow",
+                r#"["Bash(ls)"]"#,
+                "--validate-as",
+                "claude-code:Settings",
+            ],
+        )
+        .unwrap();
+
+        let err = run_in(&dir, &["set", &file, "a", "1", "--validate-as", "nolabel"]).unwrap_err();
+        assert_eq!(err.code, exit_code::USAGE);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_versions_save_list_restore() {
+        let dir = temp_dir("versions");
+        let file = dir.join("config.yaml");
+        fs::write(&file, "model: one\n").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let (saved, _) = run_in(
+            &dir,
+            &[
+                "versions", "save", "cfg-1", "--from", &file, "--name", "first",
+            ],
+        )
+        .unwrap();
+        fs::write(&file, "model: two\n").unwrap();
+
+        let (list, _) = run_in(&dir, &["versions", "list", "cfg-1"]).unwrap();
+        assert_eq!(list.as_array().unwrap().len(), 1);
+        let (configs, _) = run_in(&dir, &["versions", "configs"]).unwrap();
+        assert_eq!(configs, json!(["cfg-1"]));
+
+        let version_id = saved["id"].as_str().unwrap();
+        run_in(
+            &dir,
+            &["versions", "restore", "cfg-1", version_id, "--to", &file],
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "model: one\n");
+
+        let err = run_in(
+            &dir,
+            &["versions", "restore", "cfg-1", "nope", "--to", &file],
+        )
+        .unwrap_err();
+        assert_eq!(err.code, exit_code::NOT_FOUND);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_backups_restore_latest() {
+        let dir = temp_dir("backups");
+        let file = dir.join("config.json");
+        fs::write(&file, "{\"v\": 1}").unwrap();
+        fs::write(dir.join("config.bak"), "{\"v\": 0}").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let (list, _) = run_in(&dir, &["backups", "list", &file]).unwrap();
+        assert_eq!(list.as_array().unwrap().len(), 1);
+
+        run_in(&dir, &["backups", "restore", &file, "--no-backup"]).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "{\"v\": 0}");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_mcp_requires_target() {
+        let dir = temp_dir("mcp");
+        let err = run_in(&dir, &["mcp", "sync"]).unwrap_err();
+        assert_eq!(err.code, exit_code::USAGE);
+
+        let err = run_in(&dir, &["mcp", "preview", "no-such-tool"]).unwrap_err();
+        assert_eq!(err.code, exit_code::NOT_FOUND);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
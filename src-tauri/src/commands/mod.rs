@@ -1,8 +1,15 @@
-use crate::config::{get_cli_tools, CliTool};
+use crate::app_paths::AppPaths;
+use crate::config::{tool_registry, CliTool, ConfigFormat};
+use crate::formats::{resolve_format, ConfigDocument, FormatError};
+use crate::history::{history_enabled, record_history, HistoryEntry};
+use crate::transaction::{fingerprint, FileTarget, Transaction, TransactionError};
+use crate::validation::{validate_content, validate_document, ConfigDiagnostic, SchemaTarget};
+use crate::versions::record_write;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
+use tauri::AppHandle;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,12 +24,52 @@ pub enum CommandError {
     JsonParse(String),
     #[error("JSON path not found: {0}")]
     JsonPathNotFound(String),
+    #[error("Config parse error: {0}")]
+    ConfigParse(String),
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Validation failed with {} error(s)", .0.len())]
+    Validation(Vec<ConfigDiagnostic>),
+    #[error("{0}")]
+    Transaction(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("{path} was changed on disk since it was read")]
+    Conflict {
+        path: String,
+        /// What the file holds now, `None` if it was deleted
+        current_content: Option<String>,
+        token: String,
+    },
+}
+
+impl From<TransactionError> for CommandError {
+    fn from(e: TransactionError) -> Self {
+        match e {
+            TransactionError::PathResolution(path) => CommandError::PathResolution(path),
+            TransactionError::Io(e) => CommandError::Io(e),
+            _ => CommandError::Transaction(e.to_string()),
+        }
+    }
+}
+
+impl From<FormatError> for CommandError {
+    fn from(e: FormatError) -> Self {
+        match e {
+            // Keep the existing error type for JSON so the editor banner is unchanged
+            FormatError::Parse { format: "JSON", .. } => CommandError::JsonParse(e.to_string()),
+            FormatError::Unsupported(_) => CommandError::UnsupportedFormat(e.to_string()),
+            _ => CommandError::ConfigParse(e.to_string()),
+        }
+    }
 }
 
 #[derive(Serialize)]
 struct ErrorResponse {
     error_type: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
 }
 
 impl Serialize for CommandError {
@@ -30,6 +77,7 @@ impl Serialize for CommandError {
     where
         S: serde::Serializer,
     {
+        let mut details = None;
         let (error_type, message) = match self {
             CommandError::ConfigNotFound(msg) => ("ConfigNotFound", msg.clone()),
             CommandError::Io(e) => {
@@ -42,11 +90,32 @@ impl Serialize for CommandError {
             CommandError::PathResolution(msg) => ("PathResolution", msg.clone()),
             CommandError::JsonParse(msg) => ("JsonParse", msg.clone()),
             CommandError::JsonPathNotFound(msg) => ("JsonPathNotFound", msg.clone()),
+            CommandError::ConfigParse(msg) => ("ConfigParse", msg.clone()),
+            CommandError::UnsupportedFormat(msg) => ("UnsupportedFormat", msg.clone()),
+            CommandError::Validation(diagnostics) => {
+                details = serde_json::to_value(diagnostics).ok();
+                ("Validation", self.to_string())
+            }
+            CommandError::Transaction(msg) => ("Transaction", msg.clone()),
+            CommandError::InvalidArgument(msg) => ("InvalidArgument", msg.clone()),
+            CommandError::Conflict {
+                path,
+                current_content,
+                token,
+            } => {
+                details = Some(serde_json::json!({
+                    "path": path,
+                    "currentContent": current_content,
+                    "token": token,
+                }));
+                ("Conflict", self.to_string())
+            }
         };
         
         let response = ErrorResponse {
             error_type: error_type.to_string(),
             message,
+            details,
         };
         response.serialize(serializer)
     }
@@ -60,6 +129,16 @@ pub struct FileExistsResult {
     pub resolved_path: String,
 }
 
+/// Content returned by the read commands, with a token for the version of
+/// the file it came from. Passing the token back as `expected_token` makes
+/// the write fail with `Conflict` if the file changed in between.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContent {
+    pub content: String,
+    pub token: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BackupSettings {
@@ -67,6 +146,16 @@ pub struct BackupSettings {
     pub max_backups: u32,
 }
 
+impl Default for BackupSettings {
+    /// A single `.bak`, as the settings dialog starts with
+    fn default() -> Self {
+        BackupSettings {
+            enabled: true,
+            max_backups: 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BackupInfo {
@@ -92,8 +181,8 @@ pub fn expand_path(path: &str) -> Option<PathBuf> {
 }
 
 #[tauri::command]
-pub fn get_tools() -> Vec<CliTool> {
-    get_cli_tools()
+pub fn get_tools(app: AppHandle) -> Vec<CliTool> {
+    tool_registry(&app).tools
 }
 
 #[tauri::command]
@@ -109,7 +198,7 @@ pub fn get_current_os() -> String {
 }
 
 #[tauri::command]
-pub fn read_file(path: String) -> Result<String, CommandError> {
+pub fn read_file(path: String) -> Result<FileContent, CommandError> {
     let expanded = expand_path(&path)
         .ok_or_else(|| CommandError::PathResolution(path.clone()))?;
 
@@ -119,34 +208,232 @@ pub fn read_file(path: String) -> Result<String, CommandError> {
         ));
     }
 
-    Ok(fs::read_to_string(&expanded)?)
+    let content = fs::read_to_string(&expanded)?;
+    let token = fingerprint(Some(&content));
+    Ok(FileContent { content, token })
 }
 
-#[tauri::command]
-pub fn write_file(path: String, content: String, backup_settings: Option<BackupSettings>) -> Result<(), CommandError> {
+/// Fail with `Conflict` unless the file still matches `expected_token`.
+pub(crate) fn check_token(path: &Path, expected_token: Option<&str>) -> Result<(), CommandError> {
+    let Some(expected) = expected_token else {
+        return Ok(());
+    };
+
+    let current_content = if path.exists() {
+        Some(fs::read_to_string(path)?)
+    } else {
+        None
+    };
+    let token = fingerprint(current_content.as_deref());
+    if token == expected {
+        return Ok(());
+    }
+    Err(CommandError::Conflict {
+        path: path.to_string_lossy().to_string(),
+        current_content,
+        token,
+    })
+}
+
+/// Refuse the write when `validate` names a suggested config and the content
+/// has schema errors. Warnings never block a write.
+fn check_schema(
+    app: &impl AppPaths,
+    validate: Option<&SchemaTarget>,
+    content: &str,
+) -> Result<(), CommandError> {
+    let Some(target) = validate else {
+        return Ok(());
+    };
+    let report = validate_content(&tool_registry(app).tools, target, content);
+    if report.has_errors() {
+        return Err(CommandError::Validation(report.diagnostics));
+    }
+    Ok(())
+}
+
+/// Like `check_schema`, but for a document that was edited at a path: the
+/// schema applies to the part of the file the suggested config covers.
+fn check_document_schema(
+    app: &impl AppPaths,
+    validate: Option<&SchemaTarget>,
+    document: &ConfigDocument,
+) -> Result<(), CommandError> {
+    let Some(target) = validate else {
+        return Ok(());
+    };
+    let report = validate_document(&tool_registry(app).tools, target, document);
+    if report.has_errors() {
+        return Err(CommandError::Validation(report.diagnostics));
+    }
+    Ok(())
+}
+
+pub fn write_file_internal(
+    app: &impl AppPaths,
+    path: String,
+    content: String,
+    backup_settings: Option<BackupSettings>,
+    validate: Option<SchemaTarget>,
+    expected_token: Option<String>,
+    config_id: Option<String>,
+) -> Result<String, CommandError> {
     let expanded = expand_path(&path)
         .ok_or_else(|| CommandError::PathResolution(path.clone()))?;
 
+    check_schema(app, validate.as_ref(), &content)?;
+    check_token(&expanded, expected_token.as_deref())?;
+
     // Create parent directories if needed
     if let Some(parent) = expanded.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    // Create backup if enabled and file exists
-    let settings = backup_settings.unwrap_or(BackupSettings { enabled: true, max_backups: 1 });
-    if settings.enabled && expanded.exists() && settings.max_backups > 0 {
-        do_create_backup(&expanded, settings.max_backups)?;
+    replace_file(
+        app,
+        &path,
+        &expanded,
+        &content,
+        backup_settings,
+        "write_file",
+        None,
+    )?;
+
+    record_write(app, &path, config_id.as_deref(), &content);
+    Ok(fingerprint(Some(&content)))
+}
+
+/// Returns the token of the written content. `config_id` names the config
+/// the automatic snapshot is filed under; without it, the config last
+/// linked to `path` is used.
+#[tauri::command]
+pub fn write_file(
+    app: AppHandle,
+    path: String,
+    content: String,
+    backup_settings: Option<BackupSettings>,
+    validate: Option<SchemaTarget>,
+    expected_token: Option<String>,
+    config_id: Option<String>,
+) -> Result<String, CommandError> {
+    write_file_internal(
+        &app,
+        path,
+        content,
+        backup_settings,
+        validate,
+        expected_token,
+        config_id,
+    )
+}
+
+/// One file in a `write_files` batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWrite {
+    pub path: String,
+    pub content: String,
+    /// `fingerprint` of the content the edit started from
+    pub expected_fingerprint: Option<String>,
+}
+
+/// Write several files at once. Either every file is written or, if one
+/// write fails or a file changed since it was read, none are.
+pub fn write_files_internal(
+    app: &impl AppPaths,
+    writes: Vec<FileWrite>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<(), CommandError> {
+    let mut transaction = Transaction::new(app, backup_settings)?;
+    let mut written = Vec::new();
+    for write in writes {
+        transaction.stage(
+            FileTarget::parse(&write.path)?,
+            write.content.clone(),
+            write.expected_fingerprint,
+        );
+        written.push((write.path, write.content));
     }
+    transaction.commit()?;
+
+    for (path, content) in &written {
+        record_history(
+            app,
+            &HistoryEntry {
+                path,
+                previous: None,
+                content,
+                origin: "write_files",
+                json_path: None,
+                tool_id: None,
+            },
+        );
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn write_files(
+    app: AppHandle,
+    writes: Vec<FileWrite>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<(), CommandError> {
+    write_files_internal(&app, writes, backup_settings)
+}
+
+/// Replace the file at `expanded` with `content`. The old content goes to
+/// rotated `.bak` files, and to the git history too when it is enabled.
+/// History failures are only warnings, so the backup is never skipped for it.
+pub(crate) fn replace_file(
+    app: &impl AppPaths,
+    path: &str,
+    expanded: &Path,
+    content: &str,
+    backup_settings: Option<BackupSettings>,
+    origin: &str,
+    json_path: Option<&str>,
+) -> Result<(), CommandError> {
+    let history = history_enabled(app);
+    let previous = if history && expanded.exists() {
+        Some(fs::read_to_string(expanded)?)
+    } else {
+        None
+    };
+
+    create_backup(expanded, &backup_settings.unwrap_or_default())?;
 
     // Write atomically via temp file
     let temp_path = expanded.with_extension("tmp");
-    fs::write(&temp_path, &content)?;
-    fs::rename(&temp_path, &expanded)?;
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, expanded)?;
+
+    record_history(
+        app,
+        &HistoryEntry {
+            path,
+            previous: previous.as_deref(),
+            content,
+            origin,
+            json_path,
+            tool_id: None,
+        },
+    );
+    Ok(())
+}
 
+/// Rotate the `.bak` files of `expanded` before it is replaced, if the
+/// settings ask for backups and there is something to back up.
+pub(crate) fn create_backup(
+    expanded: &Path,
+    settings: &BackupSettings,
+) -> Result<(), std::io::Error> {
+    if settings.enabled && expanded.exists() && settings.max_backups > 0 {
+        do_create_backup(expanded, settings.max_backups)?;
+    }
     Ok(())
 }
 
-fn do_create_backup(file_path: &PathBuf, max_backups: u32) -> Result<(), std::io::Error> {
+fn do_create_backup(file_path: &Path, max_backups: u32) -> Result<(), std::io::Error> {
     // Cap max_backups at 20 to match listing limit
     let max_backups = max_backups.min(20);
     
@@ -321,10 +608,23 @@ pub fn restore_backup(original_path: String, backup_path: String, create_backup:
     Ok(())
 }
 
+/// Load a structured config file, or an empty document if it doesn't exist yet
+fn load_document(path: &Path, format: &ConfigFormat) -> Result<ConfigDocument, CommandError> {
+    if path.exists() {
+        let content = fs::read_to_string(path)?;
+        Ok(ConfigDocument::parse(&content, format)?)
+    } else {
+        Ok(ConfigDocument::empty(format)?)
+    }
+}
+
 #[tauri::command]
-pub fn read_json_path(path: String, json_path: String) -> Result<String, CommandError> {
-    let expanded = expand_path(&path)
-        .ok_or_else(|| CommandError::PathResolution(path.clone()))?;
+pub fn read_json_path(
+    path: String,
+    json_path: String,
+    format: Option<ConfigFormat>,
+) -> Result<FileContent, CommandError> {
+    let expanded = expand_path(&path).ok_or_else(|| CommandError::PathResolution(path.clone()))?;
 
     if !expanded.exists() {
         return Err(CommandError::ConfigNotFound(
@@ -332,21 +632,30 @@ pub fn read_json_path(path: String, json_path: String) -> Result<String, Command
         ));
     }
 
-    let content = fs::read_to_string(&expanded)?;
-    let root: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| CommandError::JsonParse(e.to_string()))?;
+    let format = resolve_format(format, &expanded);
+    let raw = fs::read_to_string(&expanded)?;
+    let document = ConfigDocument::parse(&raw, &format)?;
 
-    let value = get_json_value(&root, &json_path)
+    let value = document
+        .get(&json_path)
         .ok_or_else(|| CommandError::JsonPathNotFound(json_path.clone()))?;
 
-    serde_json::to_string_pretty(value)
-        .map_err(|e| CommandError::JsonParse(e.to_string()))
+    // The token covers the whole file, since writes replace the whole file
+    let content =
+        serde_json::to_string_pretty(&value).map_err(|e| CommandError::JsonParse(e.to_string()))?;
+    Ok(FileContent {
+        content,
+        token: fingerprint(Some(&raw)),
+    })
 }
 
 #[tauri::command]
-pub fn read_json_prefix(path: String, prefix: String) -> Result<String, CommandError> {
-    let expanded = expand_path(&path)
-        .ok_or_else(|| CommandError::PathResolution(path.clone()))?;
+pub fn read_json_prefix(
+    path: String,
+    prefix: String,
+    format: Option<ConfigFormat>,
+) -> Result<FileContent, CommandError> {
+    let expanded = expand_path(&path).ok_or_else(|| CommandError::PathResolution(path.clone()))?;
 
     if !expanded.exists() {
         return Err(CommandError::ConfigNotFound(
@@ -354,9 +663,9 @@ pub fn read_json_prefix(path: String, prefix: String) -> Result<String, CommandE
         ));
     }
 
-    let content = fs::read_to_string(&expanded)?;
-    let root: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| CommandError::JsonParse(e.to_string()))?;
+    let format = resolve_format(format, &expanded);
+    let raw = fs::read_to_string(&expanded)?;
+    let root = ConfigDocument::parse(&raw, &format)?.to_value();
 
     // Extract all keys that start with the prefix
     let mut result = serde_json::Map::new();
@@ -369,19 +678,29 @@ pub fn read_json_prefix(path: String, prefix: String) -> Result<String, CommandE
         }
     }
 
-    serde_json::to_string_pretty(&serde_json::Value::Object(result))
-        .map_err(|e| CommandError::JsonParse(e.to_string()))
+    let content = serde_json::to_string_pretty(&serde_json::Value::Object(result))
+        .map_err(|e| CommandError::JsonParse(e.to_string()))?;
+    Ok(FileContent {
+        content,
+        token: fingerprint(Some(&raw)),
+    })
 }
 
-#[tauri::command]
-pub fn write_json_path(
+#[allow(clippy::too_many_arguments)]
+pub fn write_json_path_internal(
+    app: &impl AppPaths,
     path: String,
     json_path: String,
     content: String,
     backup_settings: Option<BackupSettings>,
-) -> Result<(), CommandError> {
+    format: Option<ConfigFormat>,
+    validate: Option<SchemaTarget>,
+    expected_token: Option<String>,
+    config_id: Option<String>,
+) -> Result<String, CommandError> {
     let expanded = expand_path(&path)
         .ok_or_else(|| CommandError::PathResolution(path.clone()))?;
+    check_token(&expanded, expected_token.as_deref())?;
 
     // Create parent directories if needed
     if let Some(parent) = expanded.parent() {
@@ -392,46 +711,77 @@ pub fn write_json_path(
     let new_value: serde_json::Value = serde_json::from_str(&content)
         .map_err(|e| CommandError::JsonParse(e.to_string()))?;
 
-    // Read existing file or create empty object
-    let mut root: serde_json::Value = if expanded.exists() {
-        let existing = fs::read_to_string(&expanded)?;
-        serde_json::from_str(&existing)
-            .map_err(|e| CommandError::JsonParse(e.to_string()))?
-    } else {
-        serde_json::json!({})
-    };
+    // Read existing file or create empty document
+    let format = resolve_format(format, &expanded);
+    let mut document = load_document(&expanded, &format)?;
 
     // Set the value at the specified path
-    set_json_value(&mut root, &json_path, new_value)
+    document
+        .set(&json_path, new_value)?
         .ok_or_else(|| CommandError::JsonPathNotFound(json_path.clone()))?;
 
-    // Serialize the updated root
-    let final_content = serde_json::to_string_pretty(&root)
-        .map_err(|e| CommandError::JsonParse(e.to_string()))?;
+    check_document_schema(app, validate.as_ref(), &document)?;
 
-    // Create backup if enabled and file exists
-    let settings = backup_settings.unwrap_or(BackupSettings { enabled: true, max_backups: 1 });
-    if settings.enabled && expanded.exists() && settings.max_backups > 0 {
-        do_create_backup(&expanded, settings.max_backups)?;
-    }
+    // Serialize the updated document in its own format
+    let final_content = document.render()?;
 
-    // Write atomically via temp file
-    let temp_path = expanded.with_extension("tmp");
-    fs::write(&temp_path, &final_content)?;
-    fs::rename(&temp_path, &expanded)?;
+    replace_file(
+        app,
+        &path,
+        &expanded,
+        &final_content,
+        backup_settings,
+        "write_json_path",
+        Some(&json_path),
+    )?;
 
-    Ok(())
+    record_write(app, &path, config_id.as_deref(), &final_content);
+    Ok(fingerprint(Some(&final_content)))
 }
 
+/// Returns the token of the written file.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn write_json_path(
+    app: AppHandle,
+    path: String,
+    json_path: String,
+    content: String,
+    backup_settings: Option<BackupSettings>,
+    format: Option<ConfigFormat>,
+    validate: Option<SchemaTarget>,
+    expected_token: Option<String>,
+    config_id: Option<String>,
+) -> Result<String, CommandError> {
+    write_json_path_internal(
+        &app,
+        path,
+        json_path,
+        content,
+        backup_settings,
+        format,
+        validate,
+        expected_token,
+        config_id,
+    )
+}
+
+/// Returns the token of the written file.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn write_json_prefix(
+    app: AppHandle,
     path: String,
     prefix: String,
     content: String,
     backup_settings: Option<BackupSettings>,
-) -> Result<(), CommandError> {
+    format: Option<ConfigFormat>,
+    expected_token: Option<String>,
+    config_id: Option<String>,
+) -> Result<String, CommandError> {
     let expanded = expand_path(&path)
         .ok_or_else(|| CommandError::PathResolution(path.clone()))?;
+    check_token(&expanded, expected_token.as_deref())?;
 
     // Create parent directories if needed
     if let Some(parent) = expanded.parent() {
@@ -442,102 +792,44 @@ pub fn write_json_prefix(
     let new_values: serde_json::Value = serde_json::from_str(&content)
         .map_err(|e| CommandError::JsonParse(e.to_string()))?;
 
-    // Read existing file or create empty object
-    let mut root: serde_json::Value = if expanded.exists() {
-        let existing = fs::read_to_string(&expanded)?;
-        serde_json::from_str(&existing)
-            .map_err(|e| CommandError::JsonParse(e.to_string()))?
-    } else {
-        serde_json::json!({})
-    };
+    // Read existing file or create empty document
+    let format = resolve_format(format, &expanded);
+    let mut document = load_document(&expanded, &format)?;
 
     // Remove all existing keys with the prefix
-    if let serde_json::Value::Object(ref mut map) = root {
-        let keys_to_remove: Vec<String> = map
+    if let serde_json::Value::Object(map) = document.to_value() {
+        for key in map
             .keys()
             .filter(|k| k.starts_with(&prefix) || *k == &prefix)
-            .cloned()
-            .collect();
-        for key in keys_to_remove {
-            map.remove(&key);
-        }
-
-        // Add the new values
-        if let serde_json::Value::Object(new_map) = new_values {
-            for (key, value) in new_map {
-                map.insert(key, value);
-            }
+        {
+            document.remove(key);
         }
     }
 
-    // Serialize the updated root
-    let final_content = serde_json::to_string_pretty(&root)
-        .map_err(|e| CommandError::JsonParse(e.to_string()))?;
-
-    // Create backup if enabled and file exists
-    let settings = backup_settings.unwrap_or(BackupSettings { enabled: true, max_backups: 1 });
-    if settings.enabled && expanded.exists() && settings.max_backups > 0 {
-        do_create_backup(&expanded, settings.max_backups)?;
-    }
-
-    // Write atomically via temp file
-    let temp_path = expanded.with_extension("tmp");
-    fs::write(&temp_path, &final_content)?;
-    fs::rename(&temp_path, &expanded)?;
-
-    Ok(())
-}
-
-fn get_json_value<'a>(root: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
-    // First try the path as a literal key (for keys like "amp.mcpServers")
-    if let Some(value) = root.get(path) {
-        return Some(value);
-    }
-    
-    // Fall back to nested path traversal (for paths like "foo.bar.baz")
-    let parts: Vec<&str> = path.split('.').collect();
-    let mut current = root;
-    
-    for part in parts {
-        current = current.get(part)?;
-    }
-    
-    Some(current)
-}
-
-fn set_json_value(root: &mut serde_json::Value, path: &str, value: serde_json::Value) -> Option<()> {
-    // First check if the path exists as a literal key (for keys like "amp.mcpServers")
-    if root.get(path).is_some() {
-        if let serde_json::Value::Object(map) = root {
-            map.insert(path.to_string(), value);
-            return Some(());
+    // Add the new values
+    if let serde_json::Value::Object(new_map) = new_values {
+        for (key, value) in new_map {
+            document
+                .set(&key, value)?
+                .ok_or_else(|| CommandError::JsonPathNotFound(key.clone()))?;
         }
     }
-    
-    // Fall back to nested path traversal
-    let parts: Vec<&str> = path.split('.').collect();
-    let mut current = root;
-    
-    for (i, part) in parts.iter().enumerate() {
-        if i == parts.len() - 1 {
-            // Last part - set the value
-            if let serde_json::Value::Object(map) = current {
-                map.insert(part.to_string(), value);
-                return Some(());
-            }
-            return None;
-        } else {
-            // Traverse or create intermediate objects
-            if current.get(part).is_none() {
-                if let serde_json::Value::Object(map) = current {
-                    map.insert(part.to_string(), serde_json::json!({}));
-                }
-            }
-            current = current.get_mut(part)?;
-        }
-    }
-    
-    Some(())
+
+    // Serialize the updated document in its own format
+    let final_content = document.render()?;
+
+    replace_file(
+        &app,
+        &path,
+        &expanded,
+        &final_content,
+        backup_settings,
+        "write_json_prefix",
+        Some(&prefix),
+    )?;
+
+    record_write(&app, &path, config_id.as_deref(), &final_content);
+    Ok(fingerprint(Some(&final_content)))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ConfigFormat {
     Json,
@@ -30,6 +30,70 @@ pub struct CliTool {
     pub docs_url: Option<String>,
     pub description: Option<String>,
     pub suggested_configs: Option<Vec<SuggestedConfig>>,
+    /// Settings files the tool itself merges, for `effective_config`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layering: Option<ConfigLayering>,
+}
+
+/// Where a settings layer sits in a tool's precedence order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerScope {
+    Defaults,
+    User,
+    Project,
+    Local,
+    Enterprise,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLayer {
+    pub scope: LayerScope,
+    /// Project-relative paths resolve against the project root
+    #[serde(default)]
+    pub path: String,
+    /// Per-OS locations, used instead of `path` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_paths: Option<SettingsPaths>,
+}
+
+impl ConfigLayer {
+    pub fn at(scope: LayerScope, path: &str) -> Self {
+        ConfigLayer {
+            scope,
+            path: path.to_string(),
+            platform_paths: None,
+        }
+    }
+
+    pub fn per_platform(scope: LayerScope, paths: SettingsPaths) -> Self {
+        ConfigLayer {
+            scope,
+            path: String::new(),
+            platform_paths: Some(paths),
+        }
+    }
+
+    /// The configured path for the current OS
+    pub fn path_for_os(&self) -> &str {
+        match &self.platform_paths {
+            Some(paths) => paths.current(),
+            None => &self.path,
+        }
+    }
+}
+
+/// How a tool combines its settings layers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLayering {
+    /// Lowest precedence first; later layers override earlier ones
+    pub layers: Vec<ConfigLayer>,
+    /// Dot paths whose arrays are concatenated across layers instead of
+    /// replaced by the higher layer
+    #[serde(default)]
+    pub merge_arrays: Vec<String>,
 }
 
 // IDE Extension setting definition
@@ -79,6 +143,18 @@ pub struct SettingsPaths {
     pub windows: String,
 }
 
+impl SettingsPaths {
+    pub fn current(&self) -> &str {
+        if cfg!(target_os = "windows") {
+            &self.windows
+        } else if cfg!(target_os = "macos") {
+            &self.macos
+        } else {
+            &self.linux
+        }
+    }
+}
+
 // IDE Platform definition
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -261,6 +337,29 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: Some(ConfigLayering {
+                layers: vec![
+                    ConfigLayer::at(LayerScope::User, "~/.claude/settings.json"),
+                    ConfigLayer::at(LayerScope::Project, ".claude/settings.json"),
+                    ConfigLayer::at(LayerScope::Local, ".claude/settings.local.json"),
+                    ConfigLayer::per_platform(
+                        LayerScope::Enterprise,
+                        SettingsPaths {
+                            linux: "/etc/claude-code/managed-settings.json".to_string(),
+                            macos: "/Library/Application Support/ClaudeCode/managed-settings.json"
+                                .to_string(),
+                            windows: "C:\\ProgramData\\ClaudeCode\\managed-settings.json"
+                                .to_string(),
+                        },
+                    ),
+                ],
+                merge_arrays: vec![
+                    "permissions.allow".to_string(),
+                    "permissions.ask".to_string(),
+                    "permissions.deny".to_string(),
+                    "permissions.additionalDirectories".to_string(),
+                ],
+            }),
         },
         CliTool {
             id: "gemini-cli".to_string(),
@@ -286,6 +385,32 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: Some(ConfigLayering {
+                layers: vec![
+                    ConfigLayer::per_platform(
+                        LayerScope::Defaults,
+                        SettingsPaths {
+                            linux: "/etc/gemini-cli/system-defaults.json".to_string(),
+                            macos: "/Library/Application Support/GeminiCli/system-defaults.json"
+                                .to_string(),
+                            windows: "C:\\ProgramData\\gemini-cli\\system-defaults.json"
+                                .to_string(),
+                        },
+                    ),
+                    ConfigLayer::at(LayerScope::User, "~/.gemini/settings.json"),
+                    ConfigLayer::at(LayerScope::Project, ".gemini/settings.json"),
+                    ConfigLayer::per_platform(
+                        LayerScope::Enterprise,
+                        SettingsPaths {
+                            linux: "/etc/gemini-cli/settings.json".to_string(),
+                            macos: "/Library/Application Support/GeminiCli/settings.json"
+                                .to_string(),
+                            windows: "C:\\ProgramData\\gemini-cli\\settings.json".to_string(),
+                        },
+                    ),
+                ],
+                merge_arrays: vec!["context.includeDirectories".to_string()],
+            }),
         },
         CliTool {
             id: "aider".to_string(),
@@ -303,6 +428,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "continue".to_string(),
@@ -320,6 +446,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "amp".to_string(),
@@ -377,6 +504,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "gh-copilot".to_string(),
@@ -402,6 +530,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: Some("servers".to_string()),
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "cursor".to_string(),
@@ -435,6 +564,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "cody".to_string(),
@@ -452,6 +582,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "opencode".to_string(),
@@ -501,6 +632,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "qwen-code".to_string(),
@@ -542,6 +674,31 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: Some(ConfigLayering {
+                layers: vec![
+                    ConfigLayer::per_platform(
+                        LayerScope::Defaults,
+                        SettingsPaths {
+                            linux: "/etc/qwen-code/system-defaults.json".to_string(),
+                            macos: "/Library/Application Support/QwenCode/system-defaults.json"
+                                .to_string(),
+                            windows: "C:\\ProgramData\\qwen-code\\system-defaults.json".to_string(),
+                        },
+                    ),
+                    ConfigLayer::at(LayerScope::User, "~/.qwen/settings.json"),
+                    ConfigLayer::at(LayerScope::Project, ".qwen/settings.json"),
+                    ConfigLayer::per_platform(
+                        LayerScope::Enterprise,
+                        SettingsPaths {
+                            linux: "/etc/qwen-code/settings.json".to_string(),
+                            macos: "/Library/Application Support/QwenCode/settings.json"
+                                .to_string(),
+                            windows: "C:\\ProgramData\\qwen-code\\settings.json".to_string(),
+                        },
+                    ),
+                ],
+                merge_arrays: vec!["context.includeDirectories".to_string()],
+            }),
         },
         CliTool {
             id: "auggie".to_string(),
@@ -559,6 +716,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "kiro-cli".to_string(),
@@ -584,6 +742,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "qoder-cli".to_string(),
@@ -617,6 +776,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: Some("mcpServers".to_string()),
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "letta-code".to_string(),
@@ -634,6 +794,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: None,
                 },
             ]),
+            layering: None,
         },
         CliTool {
             id: "kilo-code-cli".to_string(),
@@ -659,6 +820,7 @@ pub fn get_cli_tools() -> Vec<CliTool> {
                     json_path: Some("mcpServers".to_string()),
                 },
             ]),
+            layering: None,
         },
     ]
 }
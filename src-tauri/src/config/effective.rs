@@ -0,0 +1,328 @@
+use super::{tool_registry, ConfigLayering, LayerScope};
+use crate::app_paths::AppPaths;
+use crate::commands::{expand_path, CommandError};
+use crate::formats::{resolve_format, ConfigDocument};
+use crate::workspace::is_project_relative;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// The layer a leaf value was taken from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafSource {
+    pub scope: LayerScope,
+    pub file: String,
+}
+
+/// One settings layer as it was found on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveLayer {
+    pub scope: LayerScope,
+    pub path: String,
+    /// Resolved file, or `None` for a project layer without a project root
+    pub file: Option<String>,
+    pub loaded: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    pub tool_id: String,
+    pub value: Value,
+    /// JSON pointer of every leaf in `value` to the layer that set it
+    pub sources: BTreeMap<String, LeafSource>,
+    pub layers: Vec<EffectiveLayer>,
+}
+
+fn escape_pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+struct Merger<'a> {
+    merge_arrays: &'a [String],
+    sources: BTreeMap<String, LeafSource>,
+}
+
+impl Merger<'_> {
+    fn pointer(keys: &[String]) -> String {
+        keys.iter()
+            .map(|k| format!("/{}", escape_pointer_segment(k)))
+            .collect()
+    }
+
+    fn concatenates(&self, keys: &[String]) -> bool {
+        self.merge_arrays.contains(&keys.join("."))
+    }
+
+    /// Record `source` for every leaf of `value` under `keys`. Empty objects
+    /// and arrays count as leaves.
+    fn annotate(&mut self, value: &Value, keys: &mut Vec<String>, source: &LeafSource) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    keys.push(key.clone());
+                    self.annotate(child, keys, source);
+                    keys.pop();
+                }
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for (index, child) in items.iter().enumerate() {
+                    keys.push(index.to_string());
+                    self.annotate(child, keys, source);
+                    keys.pop();
+                }
+            }
+            _ => {
+                self.sources.insert(Self::pointer(keys), source.clone());
+            }
+        }
+    }
+
+    fn forget(&mut self, keys: &[String]) {
+        let pointer = Self::pointer(keys);
+        let prefix = format!("{}/", pointer);
+        self.sources
+            .retain(|p, _| *p != pointer && !p.starts_with(&prefix));
+    }
+
+    /// Objects merge key by key, arrays listed in `merge_arrays` are
+    /// concatenated without duplicates, everything else is replaced.
+    fn merge(
+        &mut self,
+        target: &mut Value,
+        overlay: &Value,
+        keys: &mut Vec<String>,
+        source: &LeafSource,
+    ) {
+        match (target, overlay) {
+            (Value::Object(target), Value::Object(overlay)) => {
+                for (key, value) in overlay {
+                    keys.push(key.clone());
+                    match target.get_mut(key) {
+                        Some(existing) => self.merge(existing, value, keys, source),
+                        None => {
+                            self.annotate(value, keys, source);
+                            target.insert(key.clone(), value.clone());
+                        }
+                    }
+                    keys.pop();
+                }
+            }
+            (Value::Array(target), Value::Array(overlay)) if self.concatenates(keys) => {
+                for item in overlay {
+                    if target.contains(item) {
+                        continue;
+                    }
+                    if target.is_empty() {
+                        self.forget(keys);
+                    }
+                    keys.push(target.len().to_string());
+                    self.annotate(item, keys, source);
+                    keys.pop();
+                    target.push(item.clone());
+                }
+            }
+            (target, overlay) => {
+                self.forget(keys);
+                self.annotate(overlay, keys, source);
+                *target = overlay.clone();
+            }
+        }
+    }
+}
+
+/// Load every layer of `layering` and merge them in precedence order.
+/// Project and local layers are skipped without a `project_root`; a layer
+/// that fails to parse is reported and left out of the merge.
+pub fn resolve_layers(
+    tool_id: &str,
+    layering: &ConfigLayering,
+    project_root: Option<&Path>,
+) -> EffectiveConfig {
+    let mut merger = Merger {
+        merge_arrays: &layering.merge_arrays,
+        sources: BTreeMap::new(),
+    };
+    let mut value = Value::Object(Map::new());
+    let mut layers = Vec::new();
+
+    for layer in &layering.layers {
+        let path = layer.path_for_os();
+        let resolved = if is_project_relative(path) {
+            project_root.map(|root| root.join(path))
+        } else {
+            expand_path(path)
+        };
+
+        let mut status = EffectiveLayer {
+            scope: layer.scope,
+            path: path.to_string(),
+            file: resolved.as_ref().map(|p| p.to_string_lossy().to_string()),
+            loaded: false,
+            error: None,
+        };
+
+        if let Some(file) = resolved.filter(|file| file.is_file()) {
+            let parsed = fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    ConfigDocument::parse(&content, &resolve_format(None, &file))
+                        .map_err(|e| e.to_string())
+                });
+
+            match parsed {
+                Ok(document) => {
+                    let source = LeafSource {
+                        scope: layer.scope,
+                        file: file.to_string_lossy().to_string(),
+                    };
+                    merger.merge(&mut value, &document.to_value(), &mut Vec::new(), &source);
+                    status.loaded = true;
+                }
+                Err(message) => status.error = Some(message),
+            }
+        }
+        layers.push(status);
+    }
+
+    EffectiveConfig {
+        tool_id: tool_id.to_string(),
+        value,
+        sources: merger.sources,
+        layers,
+    }
+}
+
+pub fn effective_config_internal(
+    app: &impl AppPaths,
+    tool_id: &str,
+    project_root: Option<String>,
+) -> Result<EffectiveConfig, CommandError> {
+    let layering = tool_registry(app)
+        .tools
+        .into_iter()
+        .find(|tool| tool.id == tool_id)
+        .and_then(|tool| tool.layering)
+        .ok_or_else(|| {
+            CommandError::ConfigNotFound(format!("No settings layers defined for {}", tool_id))
+        })?;
+
+    let project_root = match project_root {
+        Some(root) => Some(expand_path(&root).ok_or(CommandError::PathResolution(root))?),
+        None => None,
+    };
+
+    Ok(resolve_layers(tool_id, &layering, project_root.as_deref()))
+}
+
+#[tauri::command]
+pub fn effective_config(
+    app: AppHandle,
+    tool_id: String,
+    project_root: Option<String>,
+) -> Result<EffectiveConfig, CommandError> {
+    effective_config_internal(&app, &tool_id, project_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigLayer;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("effective-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("project/.claude")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_layers_merge_with_precedence_and_sources() {
+        let dir = temp_dir();
+        let user = dir.join("user.json");
+        let managed = dir.join("managed.json");
+        let project = dir.join("project");
+        fs::write(
+            &user,
+            r#"{
+                // user layer
+                "model": "sonnet",
+                "env": { "A": "1", "B": "2" },
+                "permissions": { "allow": ["Bash(ls)"], "deny": [] },
+                "hooks": ["user"]
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            project.join(".claude/settings.json"),
+            r#"{ "env": { "B": "3" }, "permissions": { "allow": ["Bash(ls)", "Bash(git:*)"] }, "hooks": ["project"] }"#,
+        )
+        .unwrap();
+        fs::write(project.join(".claude/settings.local.json"), "{ not json").unwrap();
+        fs::write(&managed, r#"{ "permissions": { "deny": ["Bash(rm:*)"] } }"#).unwrap();
+
+        let layering = ConfigLayering {
+            layers: vec![
+                ConfigLayer::at(LayerScope::User, user.to_str().unwrap()),
+                ConfigLayer::at(LayerScope::Project, ".claude/settings.json"),
+                ConfigLayer::at(LayerScope::Local, ".claude/settings.local.json"),
+                ConfigLayer::at(LayerScope::Enterprise, managed.to_str().unwrap()),
+            ],
+            merge_arrays: vec![
+                "permissions.allow".to_string(),
+                "permissions.deny".to_string(),
+            ],
+        };
+
+        let effective = resolve_layers("claude-code", &layering, Some(&project));
+
+        assert_eq!(
+            effective.value,
+            json!({
+                "model": "sonnet",
+                "env": { "A": "1", "B": "3" },
+                "permissions": { "allow": ["Bash(ls)", "Bash(git:*)"], "deny": ["Bash(rm:*)"] },
+                "hooks": ["project"]
+            })
+        );
+
+        let scope_of = |pointer: &str| effective.sources.get(pointer).map(|s| s.scope);
+        assert_eq!(scope_of("/model"), Some(LayerScope::User));
+        assert_eq!(scope_of("/env/B"), Some(LayerScope::Project));
+        assert_eq!(scope_of("/permissions/allow/0"), Some(LayerScope::User));
+        assert_eq!(scope_of("/permissions/allow/1"), Some(LayerScope::Project));
+        assert_eq!(scope_of("/permissions/deny"), None);
+        assert_eq!(
+            scope_of("/permissions/deny/0"),
+            Some(LayerScope::Enterprise)
+        );
+        assert_eq!(scope_of("/hooks/0"), Some(LayerScope::Project));
+
+        assert!(effective.layers[2].error.is_some());
+        assert!(!effective.layers[2].loaded);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_project_layers_need_a_root() {
+        let layering = ConfigLayering {
+            layers: vec![ConfigLayer::at(
+                LayerScope::Project,
+                ".claude/settings.json",
+            )],
+            merge_arrays: Vec::new(),
+        };
+        let effective = resolve_layers("claude-code", &layering, None);
+
+        assert_eq!(effective.value, json!({}));
+        assert_eq!(effective.layers[0].file, None);
+    }
+}
@@ -1,3 +1,7 @@
 mod cli_tools;
+mod effective;
+mod registry;
 
 pub use cli_tools::*;
+pub use effective::*;
+pub use registry::*;
@@ -0,0 +1,445 @@
+use super::{get_cli_tools, CliTool, ConfigFormat};
+use crate::app_paths::AppPaths;
+use crate::formats::{resolve_format, ConfigDocument};
+use crate::mcp::{get_mcp_tool_definitions, McpToolFormat, McpToolInfo};
+use crate::workspace::{load_workspaces, Workspace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const BUILT_IN: &str = "built-in";
+
+/// A problem with one user registry file. Valid tools from the same file are
+/// still loaded; only the offending entry (or the whole file on a parse
+/// error) is skipped.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryError {
+    pub file: String,
+    pub message: String,
+}
+
+/// Built-in tool definitions merged with user-defined ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRegistry {
+    pub tools: Vec<CliTool>,
+    pub mcp_tools: Vec<McpToolInfo>,
+    pub errors: Vec<RegistryError>,
+}
+
+/// MCP sync location for a user-defined tool. The tool id and name are taken
+/// from the surrounding tool definition.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserMcpDefinition {
+    config_path: String,
+    #[serde(default = "default_mcp_json_path")]
+    json_path: String,
+    #[serde(default)]
+    format: McpToolFormat,
+    #[serde(default)]
+    project_config_path: Option<String>,
+}
+
+fn default_mcp_json_path() -> String {
+    "mcpServers".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserToolDefinition {
+    #[serde(flatten)]
+    tool: CliTool,
+    mcp: Option<UserMcpDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserToolFile {
+    #[serde(default)]
+    tools: Vec<serde_json::Value>,
+}
+
+impl ToolRegistry {
+    pub fn builtin() -> Self {
+        ToolRegistry {
+            tools: get_cli_tools(),
+            mcp_tools: get_mcp_tool_definitions(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn mcp_tool(&self, tool_id: &str) -> Option<McpToolInfo> {
+        self.mcp_tools
+            .iter()
+            .find(|t| t.tool_id == tool_id)
+            .cloned()
+    }
+
+    /// Merge every `*.toml` and `*.json` file in `dir`, in file name order.
+    /// A missing directory simply yields the built-ins.
+    pub fn load(dir: &Path) -> Self {
+        let mut registry = Self::builtin();
+
+        let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && matches!(
+                            path.extension().and_then(|e| e.to_str()),
+                            Some("toml") | Some("json")
+                        )
+                })
+                .collect(),
+            Err(_) => return registry,
+        };
+        files.sort();
+
+        // Which source claimed each id, so duplicates can name the other side
+        let mut owners: HashMap<String, String> = HashMap::new();
+        for id in registry
+            .tools
+            .iter()
+            .map(|t| &t.id)
+            .chain(registry.mcp_tools.iter().map(|t| &t.tool_id))
+        {
+            owners.insert(id.clone(), BUILT_IN.to_string());
+        }
+
+        for file in files {
+            registry.merge_file(&file, &mut owners);
+        }
+        registry
+    }
+
+    fn merge_file(&mut self, file: &Path, owners: &mut HashMap<String, String>) {
+        let file_name = file.to_string_lossy().to_string();
+        let mut messages = Vec::new();
+
+        let parsed = fs::read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                let format = resolve_format(None, file);
+                ConfigDocument::parse(&content, &format)
+                    .map(|doc| doc.to_value())
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|value| {
+                serde_json::from_value::<UserToolFile>(value).map_err(|e| e.to_string())
+            });
+
+        let entries = match parsed {
+            Ok(parsed) => parsed.tools,
+            Err(message) => {
+                self.errors.push(RegistryError {
+                    file: file_name,
+                    message,
+                });
+                return;
+            }
+        };
+
+        let mut accepted = Vec::new();
+        for (index, entry) in entries.into_iter().enumerate() {
+            let definition: UserToolDefinition = match serde_json::from_value(entry) {
+                Ok(definition) => definition,
+                Err(e) => {
+                    messages.push(format!("tools[{}]: {}", index, e));
+                    continue;
+                }
+            };
+
+            let id = definition.tool.id.clone();
+            if let Err(message) = validate_tool(&definition.tool) {
+                messages.push(format!("tools[{}] '{}': {}", index, id, message));
+                continue;
+            }
+            if let Some(Err(e)) = definition.mcp.as_ref().map(|mcp| mcp.format.adapter()) {
+                messages.push(format!("tools[{}] '{}': {}", index, id, e));
+                continue;
+            }
+            if let Some(owner) = owners.get(&id) {
+                messages.push(format!(
+                    "tools[{}]: duplicate tool id '{}' (already defined by {})",
+                    index, id, owner
+                ));
+                continue;
+            }
+            owners.insert(id, file_name.clone());
+            accepted.push(definition);
+        }
+
+        for definition in accepted {
+            if let Some(mcp) = definition.mcp {
+                self.mcp_tools.push(McpToolInfo {
+                    tool_id: definition.tool.id.clone(),
+                    config_path: mcp.config_path,
+                    json_path: mcp.json_path,
+                    format: mcp.format,
+                    name: definition.tool.name.clone(),
+                    project_config_path: mcp.project_config_path,
+                    workspace: None,
+                });
+            }
+            self.tools.push(definition.tool);
+        }
+
+        self.errors
+            .extend(messages.into_iter().map(|message| RegistryError {
+                file: file_name.clone(),
+                message,
+            }));
+    }
+
+    /// Add a project-level MCP target (`<tool>@<workspace>`) for every tool
+    /// with a project config, in every registered workspace.
+    pub fn add_workspace_targets(&mut self, workspaces: &[Workspace]) {
+        let project_targets: Vec<McpToolInfo> = workspaces
+            .iter()
+            .flat_map(|workspace| {
+                self.mcp_tools
+                    .iter()
+                    .filter(|t| t.workspace.is_none())
+                    .filter_map(move |tool| {
+                        let relative = tool.project_config_path.as_ref()?;
+                        Some(McpToolInfo {
+                            tool_id: format!("{}@{}", tool.tool_id, workspace.id),
+                            config_path: workspace
+                                .root()
+                                .join(relative)
+                                .to_string_lossy()
+                                .to_string(),
+                            json_path: tool.json_path.clone(),
+                            format: tool.format.clone(),
+                            name: format!("{} ({})", tool.name, workspace.name),
+                            project_config_path: Some(relative.clone()),
+                            workspace: Some(workspace.id.clone()),
+                        })
+                    })
+            })
+            .collect();
+        self.mcp_tools.extend(project_targets);
+    }
+}
+
+/// Tool ids end up in file names and event payloads, so keep them to
+/// lowercase ASCII words separated by dashes.
+pub fn is_valid_tool_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && !id.starts_with('-')
+        && !id.ends_with('-')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn validate_tool(tool: &CliTool) -> Result<(), String> {
+    if !is_valid_tool_id(&tool.id) {
+        return Err("id must be lowercase letters, digits and dashes".to_string());
+    }
+    if tool.name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+
+    let mut labels = Vec::new();
+    for config in tool.suggested_configs.iter().flatten() {
+        if config.path.trim().is_empty() {
+            return Err(format!("config '{}' has an empty path", config.label));
+        }
+        if config.json_path.is_some() && config.format == ConfigFormat::Md {
+            return Err(format!(
+                "config '{}' sets jsonPath on a markdown file",
+                config.label
+            ));
+        }
+        if labels.contains(&&config.label) {
+            return Err(format!("duplicate config label '{}'", config.label));
+        }
+        labels.push(&config.label);
+    }
+    for layer in tool.layering.iter().flat_map(|l| &l.layers) {
+        if layer.path_for_os().trim().is_empty() {
+            return Err(format!("{:?} layer has an empty path", layer.scope).to_lowercase());
+        }
+    }
+    Ok(())
+}
+
+/// Directory holding user tool definitions: `<app config dir>/tools`.
+pub fn tools_dir(app: &impl AppPaths) -> Option<PathBuf> {
+    app.app_config_dir().map(|dir| dir.join("tools"))
+}
+
+pub fn tool_registry(app: &impl AppPaths) -> ToolRegistry {
+    let mut registry = match tools_dir(app) {
+        Some(dir) => ToolRegistry::load(&dir),
+        None => ToolRegistry::builtin(),
+    };
+    // A broken workspaces file only hides the project-level targets
+    registry.add_workspace_targets(&load_workspaces(app).unwrap_or_default());
+    registry
+}
+
+#[tauri::command]
+pub fn get_tool_registry(app: AppHandle) -> ToolRegistry {
+    tool_registry(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("registry-test-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_missing_dir_yields_builtins() {
+        let registry = ToolRegistry::load(Path::new("/nonexistent/registry/dir"));
+        assert_eq!(registry.tools.len(), get_cli_tools().len());
+        assert_eq!(registry.mcp_tools.len(), get_mcp_tool_definitions().len());
+        assert!(registry.errors.is_empty());
+    }
+
+    #[test]
+    fn test_merge_toml_and_json_files() {
+        let dir = temp_dir("merge");
+        fs::write(
+            dir.join("acme.toml"),
+            r#"
+# In-house agent
+[[tools]]
+id = "acme-agent"
+name = "Acme Agent"
+
+[[tools.suggestedConfigs]]
+label = "Settings"
+path = "~/.acme/settings.yaml"
+format = "yaml"
+
+[tools.mcp]
+configPath = "~/.acme/settings.json"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("other.json"),
+            r#"{
+                // JSONC is accepted
+                "tools": [{ "id": "other-cli", "name": "Other CLI", "icon": "🧪" }]
+            }"#,
+        )
+        .unwrap();
+
+        let registry = ToolRegistry::load(&dir);
+        assert!(registry.errors.is_empty(), "{:?}", registry.errors);
+
+        let acme = registry
+            .tools
+            .iter()
+            .find(|t| t.id == "acme-agent")
+            .unwrap();
+        let configs = acme.suggested_configs.as_ref().unwrap();
+        assert_eq!(configs[0].format, ConfigFormat::Yaml);
+        assert!(registry.tools.iter().any(|t| t.id == "other-cli"));
+
+        let mcp = registry.mcp_tool("acme-agent").unwrap();
+        assert_eq!(mcp.json_path, "mcpServers");
+        assert_eq!(mcp.format, McpToolFormat::default());
+        assert_eq!(mcp.name, "Acme Agent");
+        assert!(registry.mcp_tool("other-cli").is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_errors_are_reported_per_file() {
+        let dir = temp_dir("errors");
+        fs::write(
+            dir.join("a.json"),
+            r#"{ "tools": [{ "id": "dup-tool", "name": "First" }] }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.json"),
+            r#"{ "tools": [
+                { "id": "dup-tool", "name": "Second" },
+                { "id": "claude-code", "name": "Shadow" },
+                { "id": "Bad Id", "name": "Bad" },
+                { "name": "No id" },
+                { "id": "fine-tool", "name": "Fine" },
+                { "id": "odd-format", "name": "Odd", "mcp": { "configPath": "~/.odd.json", "format": "xml" } }
+            ] }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("c.toml"), "[[tools]\nid = ").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let registry = ToolRegistry::load(&dir);
+
+        let b_errors: Vec<&RegistryError> = registry
+            .errors
+            .iter()
+            .filter(|e| e.file.ends_with("b.json"))
+            .collect();
+        assert_eq!(b_errors.len(), 5, "{:?}", b_errors);
+        assert!(b_errors[0].message.contains("a.json"));
+        assert!(b_errors[1].message.contains("built-in"));
+        assert!(b_errors[4].message.contains("Unknown MCP format 'xml'"));
+        assert_eq!(
+            registry
+                .errors
+                .iter()
+                .filter(|e| e.file.ends_with("c.toml"))
+                .count(),
+            1
+        );
+
+        assert_eq!(
+            registry.tools.iter().filter(|t| t.id == "dup-tool").count(),
+            1
+        );
+        assert!(registry.tools.iter().any(|t| t.id == "fine-tool"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_workspace_targets() {
+        let mut registry = ToolRegistry::builtin();
+        registry.add_workspace_targets(&[Workspace {
+            id: "shop".to_string(),
+            name: "Shop".to_string(),
+            path: "/work/shop".to_string(),
+        }]);
+
+        let target = registry.mcp_tool("claude-code@shop").unwrap();
+        assert_eq!(
+            Path::new(&target.config_path),
+            Path::new("/work/shop/.mcp.json")
+        );
+        assert_eq!(target.name, "Claude Code (Shop)");
+        assert_eq!(target.workspace.as_deref(), Some("shop"));
+        assert!(registry.mcp_tool("amp@shop").is_some());
+        assert!(registry.mcp_tool("gemini-cli@shop").is_none());
+    }
+
+    #[test]
+    fn test_is_valid_tool_id() {
+        assert!(is_valid_tool_id("claude-code"));
+        assert!(is_valid_tool_id("agent2"));
+        assert!(!is_valid_tool_id(""));
+        assert!(!is_valid_tool_id("-lead"));
+        assert!(!is_valid_tool_id("Upper"));
+        assert!(!is_valid_tool_id("has space"));
+        assert!(!is_valid_tool_id("../escape"));
+    }
+}
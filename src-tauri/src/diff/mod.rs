@@ -0,0 +1,516 @@
+use crate::app_paths::AppPaths;
+use crate::commands::CommandError;
+use crate::config::ConfigFormat;
+use crate::formats::ConfigDocument;
+use crate::ssh::SshConnection;
+use crate::transaction::FileTarget;
+use crate::versions::{load_version_internal, VersionError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// One side of a diff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum DiffSource {
+    /// A live file, local or `user@host:path`
+    File { path: String },
+    /// A `.bak` / `.bak.N` file written by `write_file`
+    Backup { path: String },
+    /// A file on an SSH host
+    Remote { path: String },
+    /// A saved version
+    Version {
+        config_id: String,
+        version_id: String,
+    },
+    /// Unsaved content, e.g. the editor buffer. `path` only hints the format.
+    Content {
+        content: String,
+        #[serde(default)]
+        path: Option<String>,
+    },
+}
+
+impl DiffSource {
+    /// File the content belongs to, for picking a format. Backups drop their
+    /// `.bak` suffix so `settings.json.bak.2` still reads as JSON.
+    fn format_hint(&self) -> Option<PathBuf> {
+        match self {
+            DiffSource::File { path } | DiffSource::Remote { path } => {
+                let path = SshConnection::parse_remote(path)
+                    .map(|c| c.path)
+                    .unwrap_or_else(|| path.clone());
+                Some(PathBuf::from(path))
+            }
+            DiffSource::Backup { path } => {
+                let name = match path.rsplit_once(".bak") {
+                    Some((base, suffix))
+                        if suffix.is_empty()
+                            || suffix
+                                .strip_prefix('.')
+                                .is_some_and(|n| n.parse::<u32>().is_ok()) =>
+                    {
+                        base
+                    }
+                    _ => path.as_str(),
+                };
+                Some(PathBuf::from(name))
+            }
+            DiffSource::Content { path, .. } => path.as_ref().map(PathBuf::from),
+            DiffSource::Version { .. } => None,
+        }
+    }
+
+    fn read(&self, app: &impl AppPaths) -> Result<String, CommandError> {
+        let read_target = |path: &str| -> Result<String, CommandError> {
+            FileTarget::parse(path)?
+                .read()?
+                .ok_or_else(|| CommandError::ConfigNotFound(path.to_string()))
+        };
+
+        match self {
+            DiffSource::File { path } | DiffSource::Backup { path } => read_target(path),
+            DiffSource::Remote { path } => {
+                if SshConnection::parse_remote(path).is_none() {
+                    return Err(CommandError::PathResolution(format!(
+                        "Not an SSH path: {}",
+                        path
+                    )));
+                }
+                read_target(path)
+            }
+            DiffSource::Version {
+                config_id,
+                version_id,
+            } => match load_version_internal(app, config_id.clone(), version_id.clone()) {
+                Ok(version) => Ok(version.content),
+                Err(VersionError::NotFound(id)) => {
+                    Err(CommandError::ConfigNotFound(format!("Version {}", id)))
+                }
+                Err(VersionError::Io(e)) => Err(CommandError::Io(e)),
+                Err(e) => Err(CommandError::ConfigParse(e.to_string())),
+            },
+            DiffSource::Content { content, .. } => Ok(content.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+    /// An object or array with changes somewhere below it
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffNode {
+    /// Object key or array index
+    pub key: String,
+    /// JSON pointer. Array indexes are from the right side, except for
+    /// removed elements, which use their index on the left side.
+    pub path: String,
+    pub kind: DiffKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DiffNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    pub format: ConfigFormat,
+    pub identical: bool,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    /// Changes below the document root
+    pub changes: Vec<DiffNode>,
+}
+
+fn escape_pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn leaf(
+    key: String,
+    path: String,
+    kind: DiffKind,
+    old: Option<&Value>,
+    new: Option<&Value>,
+) -> DiffNode {
+    DiffNode {
+        key,
+        path,
+        kind,
+        old_value: old.cloned(),
+        new_value: new.cloned(),
+        children: Vec::new(),
+    }
+}
+
+/// Array elements with the same `id`, `name` or `key` are the same entry,
+/// so an edited MCP server or permission object diffs field by field.
+fn identity(value: &Value) -> Option<(&'static str, &Value)> {
+    let object = value.as_object()?;
+    ["id", "name", "key"].into_iter().find_map(|field| {
+        object
+            .get(field)
+            .filter(|v| !v.is_object() && !v.is_array())
+            .map(|v| (field, v))
+    })
+}
+
+fn diff_children(path: &str, old: &Value, new: &Value) -> Option<Vec<DiffNode>> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            let nodes = keys
+                .into_iter()
+                .filter_map(|key| {
+                    let child_path = format!("{}/{}", path, escape_pointer_segment(key));
+                    match (old.get(key), new.get(key)) {
+                        (Some(o), Some(n)) => diff_values(key.clone(), child_path, o, n),
+                        (Some(o), None) => Some(leaf(
+                            key.clone(),
+                            child_path,
+                            DiffKind::Removed,
+                            Some(o),
+                            None,
+                        )),
+                        (None, Some(n)) => Some(leaf(
+                            key.clone(),
+                            child_path,
+                            DiffKind::Added,
+                            None,
+                            Some(n),
+                        )),
+                        (None, None) => None,
+                    }
+                })
+                .collect();
+            Some(nodes)
+        }
+        (Value::Array(old), Value::Array(new)) => Some(diff_arrays(path, old, new)),
+        _ => None,
+    }
+}
+
+/// Arrays are compared as multisets: an element that only moved is not a
+/// change. What is left over is paired by identity, then reported as
+/// removed or added.
+fn diff_arrays(path: &str, old: &[Value], new: &[Value]) -> Vec<DiffNode> {
+    let mut matched = vec![false; new.len()];
+    let mut unmatched_old = Vec::new();
+    for (i, item) in old.iter().enumerate() {
+        match (0..new.len()).find(|&j| !matched[j] && new[j] == *item) {
+            Some(j) => matched[j] = true,
+            None => unmatched_old.push(i),
+        }
+    }
+
+    let mut nodes = Vec::new();
+    let mut removed = Vec::new();
+    for i in unmatched_old {
+        let paired = identity(&old[i])
+            .and_then(|id| (0..new.len()).find(|&j| !matched[j] && identity(&new[j]) == Some(id)));
+        match paired {
+            Some(j) => {
+                matched[j] = true;
+                nodes.extend(diff_values(
+                    j.to_string(),
+                    format!("{}/{}", path, j),
+                    &old[i],
+                    &new[j],
+                ));
+            }
+            None => removed.push(i),
+        }
+    }
+
+    for i in removed {
+        nodes.push(leaf(
+            i.to_string(),
+            format!("{}/{}", path, i),
+            DiffKind::Removed,
+            Some(&old[i]),
+            None,
+        ));
+    }
+    for (j, item) in new.iter().enumerate().filter(|(j, _)| !matched[*j]) {
+        nodes.push(leaf(
+            j.to_string(),
+            format!("{}/{}", path, j),
+            DiffKind::Added,
+            None,
+            Some(item),
+        ));
+    }
+    nodes
+}
+
+fn diff_values(key: String, path: String, old: &Value, new: &Value) -> Option<DiffNode> {
+    if old == new {
+        return None;
+    }
+
+    match diff_children(&path, old, new) {
+        Some(children) if children.is_empty() => None,
+        Some(children) => Some(DiffNode {
+            key,
+            path,
+            kind: DiffKind::Modified,
+            old_value: None,
+            new_value: None,
+            children,
+        }),
+        None => Some(leaf(key, path, DiffKind::Changed, Some(old), Some(new))),
+    }
+}
+
+fn count(nodes: &[DiffNode], diff: &mut ConfigDiff) {
+    for node in nodes {
+        match node.kind {
+            DiffKind::Added => diff.added += 1,
+            DiffKind::Removed => diff.removed += 1,
+            DiffKind::Changed => diff.changed += 1,
+            DiffKind::Modified => count(&node.children, diff),
+        }
+    }
+}
+
+/// Structural diff of two parsed documents. Key order and array element
+/// order are ignored.
+pub fn diff_documents(old: &Value, new: &Value, format: ConfigFormat) -> ConfigDiff {
+    let changes = match diff_children("", old, new) {
+        Some(children) => children,
+        None => diff_values(String::new(), String::new(), old, new)
+            .into_iter()
+            .collect(),
+    };
+
+    let mut diff = ConfigDiff {
+        format,
+        identical: changes.is_empty(),
+        added: 0,
+        removed: 0,
+        changed: 0,
+        changes: Vec::new(),
+    };
+    count(&changes, &mut diff);
+    diff.changes = changes;
+    diff
+}
+
+pub fn diff_configs_internal(
+    app: &impl AppPaths,
+    left: &DiffSource,
+    right: &DiffSource,
+    format: Option<ConfigFormat>,
+) -> Result<ConfigDiff, CommandError> {
+    // An explicit format wins, then either side's extension, then JSON
+    let format = format
+        .or_else(|| {
+            [left, right]
+                .iter()
+                .filter_map(|source| source.format_hint())
+                .find_map(|hint| ConfigFormat::from_path(&hint))
+        })
+        .unwrap_or(ConfigFormat::Json);
+
+    let parse = |source: &DiffSource| -> Result<Value, CommandError> {
+        let content = source.read(app)?;
+        Ok(ConfigDocument::parse(&content, &format)?.to_value())
+    };
+    let (old, new) = (parse(left)?, parse(right)?);
+
+    Ok(diff_documents(&old, &new, format))
+}
+
+#[tauri::command]
+pub fn diff_configs(
+    app: AppHandle,
+    left: DiffSource,
+    right: DiffSource,
+    format: Option<ConfigFormat>,
+) -> Result<ConfigDiff, CommandError> {
+    diff_configs_internal(&app, &left, &right, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use crate::versions::save_version_internal;
+    use serde_json::json;
+    use std::fs;
+
+    fn kinds(diff: &ConfigDiff) -> Vec<(String, DiffKind)> {
+        fn walk(nodes: &[DiffNode], out: &mut Vec<(String, DiffKind)>) {
+            for node in nodes {
+                out.push((node.path.clone(), node.kind));
+                walk(&node.children, out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(&diff.changes, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_reordering_is_not_a_change() {
+        let old =
+            json!({ "a": 1, "list": [1, 2, 3], "servers": [{ "name": "x" }, { "name": "y" }] });
+        let new =
+            json!({ "servers": [{ "name": "y" }, { "name": "x" }], "list": [3, 1, 2], "a": 1 });
+        let diff = diff_documents(&old, &new, ConfigFormat::Json);
+        assert!(diff.identical);
+        assert!(diff.changes.is_empty());
+    }
+
+    #[test]
+    fn test_reports_added_removed_and_changed_paths() {
+        let old = json!({
+            "model": "sonnet",
+            "old/key": true,
+            "permissions": { "allow": ["Bash(ls)", "Read"] },
+            "servers": [{ "name": "github", "command": "gh" }, { "name": "fs", "command": "fs" }]
+        });
+        let new = json!({
+            "model": "opus",
+            "env": { "A": "1" },
+            "permissions": { "allow": ["Read", "Bash(git:*)"] },
+            "servers": [{ "name": "fs", "command": "fs" }, { "name": "github", "command": "gh-mcp" }]
+        });
+        let diff = diff_documents(&old, &new, ConfigFormat::Json);
+
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                ("/env".to_string(), DiffKind::Added),
+                ("/model".to_string(), DiffKind::Changed),
+                ("/old~1key".to_string(), DiffKind::Removed),
+                ("/permissions".to_string(), DiffKind::Modified),
+                ("/permissions/allow".to_string(), DiffKind::Modified),
+                ("/permissions/allow/0".to_string(), DiffKind::Removed),
+                ("/permissions/allow/1".to_string(), DiffKind::Added),
+                ("/servers".to_string(), DiffKind::Modified),
+                ("/servers/1".to_string(), DiffKind::Modified),
+                ("/servers/1/command".to_string(), DiffKind::Changed),
+            ]
+        );
+        assert_eq!((diff.added, diff.removed, diff.changed), (2, 2, 2));
+
+        let model = &diff.changes[1];
+        assert_eq!(model.old_value, Some(json!("sonnet")));
+        assert_eq!(model.new_value, Some(json!("opus")));
+    }
+
+    #[test]
+    fn test_backup_format_hint_drops_only_backup_suffixes() {
+        let hint = |path: &str| {
+            DiffSource::Backup {
+                path: path.to_string(),
+            }
+            .format_hint()
+            .unwrap()
+        };
+        assert_eq!(hint("settings.json.bak"), PathBuf::from("settings.json"));
+        assert_eq!(hint("config.toml.bak.2"), PathBuf::from("config.toml"));
+        assert_eq!(
+            hint("settings.json.bak2"),
+            PathBuf::from("settings.json.bak2")
+        );
+        assert_eq!(
+            hint("settings.json.bakü"),
+            PathBuf::from("settings.json.bakü")
+        );
+    }
+
+    #[test]
+    fn test_diff_sources_across_formats() {
+        let dir = std::env::temp_dir().join(format!("diff-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let paths = StandalonePaths {
+            data_dir: Some(dir.join("data")),
+            config_dir: None,
+        };
+
+        let live = dir.join("config.toml");
+        fs::write(
+            &live,
+            "model = \"opus\"\n[mcp_servers.github]\ncommand = \"gh\"\nargs = [\"a\", \"b\"]\n",
+        )
+        .unwrap();
+        let backup = dir.join("config.toml.bak.1");
+        fs::write(
+            &backup,
+            "model = \"o3\"\n[mcp_servers.github]\nargs = [\"b\", \"a\"]\ncommand = \"gh\"\n",
+        )
+        .unwrap();
+        let version = save_version_internal(
+            &paths,
+            "codex".to_string(),
+            "v1".to_string(),
+            "model = \"o3\"\n".to_string(),
+            None,
+            "manual".to_string(),
+        )
+        .unwrap();
+
+        let file = DiffSource::File {
+            path: live.to_string_lossy().to_string(),
+        };
+        let diff = diff_configs_internal(
+            &paths,
+            &DiffSource::Backup {
+                path: backup.to_string_lossy().to_string(),
+            },
+            &file,
+            None,
+        )
+        .unwrap();
+        assert_eq!(diff.format, ConfigFormat::Toml);
+        assert_eq!(
+            kinds(&diff),
+            vec![("/model".to_string(), DiffKind::Changed)]
+        );
+
+        let diff = diff_configs_internal(
+            &paths,
+            &DiffSource::Version {
+                config_id: "codex".to_string(),
+                version_id: version.id,
+            },
+            &file,
+            None,
+        )
+        .unwrap();
+        assert_eq!(diff.format, ConfigFormat::Toml);
+        assert_eq!(diff.added, 1);
+
+        let missing = DiffSource::Version {
+            config_id: "codex".to_string(),
+            version_id: "nope".to_string(),
+        };
+        assert!(matches!(
+            diff_configs_internal(&paths, &missing, &file, None),
+            Err(CommandError::ConfigNotFound(_))
+        ));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,110 @@
+use super::FormatError;
+use ::ini::Ini;
+use serde_json::Value;
+
+const FORMAT: &str = "INI";
+
+/// Parse INI into a JSON view: keys outside any section live at the top
+/// level, each `[section]` becomes an object of string values.
+pub fn parse(content: &str) -> Result<Value, String> {
+    let ini = Ini::load_from_str(content).map_err(|e| e.to_string())?;
+    let mut root = serde_json::Map::new();
+
+    for (section, properties) in ini.iter() {
+        let entries = properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())));
+
+        match section {
+            None => root.extend(entries),
+            Some(name) => {
+                let section_obj = root
+                    .entry(name.to_string())
+                    .or_insert_with(|| Value::Object(serde_json::Map::new()));
+                if let Value::Object(map) = section_obj {
+                    map.extend(entries);
+                }
+            }
+        }
+    }
+
+    Ok(Value::Object(root))
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn invalid(message: String) -> FormatError {
+    FormatError::InvalidValue {
+        format: FORMAT,
+        message,
+    }
+}
+
+/// INI only has two levels: reject values that would need a third
+pub fn check_value(path: &str, value: &Value) -> Result<(), FormatError> {
+    let depth = path.split('.').count();
+    match value {
+        Value::Object(map) if depth == 1 => {
+            for (key, v) in map {
+                if scalar_to_string(v).is_none() {
+                    return Err(invalid(format!("{}.{} must be a scalar", path, key)));
+                }
+            }
+            Ok(())
+        }
+        v if depth <= 2 && scalar_to_string(v).is_some() => Ok(()),
+        _ => Err(invalid(format!(
+            "{} is nested too deeply or is not a scalar",
+            path
+        ))),
+    }
+}
+
+pub fn render(root: &Value) -> Result<String, FormatError> {
+    let map = root
+        .as_object()
+        .ok_or_else(|| invalid("document root must be an object".to_string()))?;
+    let mut ini = Ini::new();
+
+    // General section first so top-level keys are not swallowed by a section
+    for (key, value) in map {
+        if let Some(s) = scalar_to_string(value) {
+            ini.with_general_section().set(key.as_str(), s);
+        }
+    }
+
+    for (section, value) in map {
+        match value {
+            Value::Object(entries) => {
+                for (key, v) in entries {
+                    let s = scalar_to_string(v)
+                        .ok_or_else(|| invalid(format!("{}.{} must be a scalar", section, key)))?;
+                    ini.with_section(Some(section.as_str()))
+                        .set(key.as_str(), s);
+                }
+            }
+            Value::Null => {}
+            v if scalar_to_string(v).is_some() => {}
+            _ => {
+                return Err(invalid(format!(
+                    "{} must be a scalar or a section",
+                    section
+                )))
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    ini.write_to(&mut out).map_err(|e| FormatError::Serialize {
+        format: FORMAT,
+        message: e.to_string(),
+    })?;
+    Ok(String::from_utf8_lossy(&out).to_string())
+}
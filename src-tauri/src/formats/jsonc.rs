@@ -0,0 +1,565 @@
+//! Lossless JSONC editing.
+//!
+//! VS Code style `settings.json` files allow comments and trailing commas, and
+//! users care about their key order and layout. Instead of parsing into a
+//! `serde_json::Value` and pretty-printing the whole file back, edits here
+//! splice new text into the original source so that only the target subtree
+//! changes.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Replace comments and trailing commas with spaces. Byte offsets (and line
+/// numbers, since newlines are kept) are identical between the input and the
+/// output, so spans found in the stripped text apply to the original.
+fn strip(content: &str) -> (String, Vec<usize>) {
+    let bytes = content.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut trailing_commas = Vec::new();
+    let mut last_comma: Option<usize> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                last_comma = None;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    out[i] = b' ';
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = content[i + 2..]
+                    .find("*/")
+                    .map(|p| i + 2 + p + 2)
+                    .unwrap_or(bytes.len());
+                for b in out.iter_mut().take(end).skip(i) {
+                    if *b != b'\n' {
+                        *b = b' ';
+                    }
+                }
+                i = end;
+            }
+            b',' => {
+                last_comma = Some(i);
+                i += 1;
+            }
+            b'}' | b']' => {
+                if let Some(pos) = last_comma.take() {
+                    out[pos] = b' ';
+                    trailing_commas.push(pos);
+                }
+                i += 1;
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => {
+                last_comma = None;
+                i += 1;
+            }
+        }
+    }
+
+    // Only ASCII bytes outside of strings were replaced, so this stays valid UTF-8
+    let stripped = String::from_utf8(out)
+        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).to_string());
+    (stripped, trailing_commas)
+}
+
+/// Parse JSON with comments and trailing commas into a `Value`
+pub fn parse(content: &str) -> Result<Value, serde_json::Error> {
+    let (stripped, _) = strip(content);
+    serde_json::from_str(&stripped)
+}
+
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    members: Option<Vec<Member>>,
+}
+
+#[derive(Debug)]
+struct Member {
+    key: String,
+    key_start: usize,
+    value: Node,
+}
+
+/// Minimal span parser over already-stripped (and already validated) text
+struct SpanParser<'a> {
+    bytes: &'a [u8],
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> SpanParser<'a> {
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn string_end(&self, start: usize) -> Option<usize> {
+        let mut i = start + 1;
+        while i < self.bytes.len() {
+            match self.bytes[i] {
+                b'\\' => i += 2,
+                b'"' => return Some(i + 1),
+                _ => i += 1,
+            }
+        }
+        None
+    }
+
+    fn value(&mut self) -> Option<Node> {
+        self.skip_ws();
+        let start = self.pos;
+        match *self.bytes.get(start)? {
+            b'{' => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                loop {
+                    self.skip_ws();
+                    match *self.bytes.get(self.pos)? {
+                        b'}' => {
+                            self.pos += 1;
+                            break;
+                        }
+                        b',' => self.pos += 1,
+                        b'"' => {
+                            let key_start = self.pos;
+                            let key_end = self.string_end(key_start)?;
+                            let key: String =
+                                serde_json::from_str(&self.text[key_start..key_end]).ok()?;
+                            self.pos = key_end;
+                            self.skip_ws();
+                            if self.bytes.get(self.pos) != Some(&b':') {
+                                return None;
+                            }
+                            self.pos += 1;
+                            let value = self.value()?;
+                            members.push(Member {
+                                key,
+                                key_start,
+                                value,
+                            });
+                        }
+                        _ => return None,
+                    }
+                }
+                Some(Node {
+                    start,
+                    end: self.pos,
+                    members: Some(members),
+                })
+            }
+            b'[' => {
+                self.pos += 1;
+                loop {
+                    self.skip_ws();
+                    match *self.bytes.get(self.pos)? {
+                        b']' => {
+                            self.pos += 1;
+                            break;
+                        }
+                        b',' => self.pos += 1,
+                        _ => {
+                            self.value()?;
+                        }
+                    }
+                }
+                Some(Node {
+                    start,
+                    end: self.pos,
+                    members: None,
+                })
+            }
+            b'"' => {
+                self.pos = self.string_end(start)?;
+                Some(Node {
+                    start,
+                    end: self.pos,
+                    members: None,
+                })
+            }
+            _ => {
+                while self.pos < self.bytes.len()
+                    && !matches!(self.bytes[self.pos], b',' | b'}' | b']')
+                    && !self.bytes[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+                Some(Node {
+                    start,
+                    end: self.pos,
+                    members: None,
+                })
+            }
+        }
+    }
+}
+
+/// A JSONC source text that can be edited in place
+#[derive(Debug, Clone)]
+pub struct JsoncDocument {
+    text: String,
+    value: Value,
+}
+
+impl JsoncDocument {
+    pub fn parse(content: &str) -> Result<Self, serde_json::Error> {
+        Ok(JsoncDocument {
+            text: content.to_string(),
+            value: parse(content)?,
+        })
+    }
+
+    pub fn empty() -> Self {
+        JsoncDocument {
+            text: "{}\n".to_string(),
+            value: serde_json::json!({}),
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn root(&self) -> Option<Node> {
+        let (stripped, _) = strip(&self.text);
+        SpanParser {
+            bytes: stripped.as_bytes(),
+            text: &stripped,
+            pos: 0,
+        }
+        .value()
+    }
+
+    fn indent_unit(&self) -> String {
+        self.text
+            .lines()
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .find(|indent| !indent.is_empty())
+            .unwrap_or("  ")
+            .to_string()
+    }
+
+    fn line_indent(&self, pos: usize) -> &str {
+        let line_start = self.text[..pos].rfind('\n').map(|p| p + 1).unwrap_or(0);
+        let line = &self.text[line_start..];
+        &line[..line.len() - line.trim_start().len()]
+    }
+
+    fn render_value(&self, value: &Value, indent: &str) -> String {
+        let unit = self.indent_unit();
+        let mut buf = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+        let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+        if value.serialize(&mut ser).is_err() {
+            return value.to_string();
+        }
+        String::from_utf8_lossy(&buf).replace('\n', &format!("\n{}", indent))
+    }
+
+    /// Set the value under a sequence of object keys, creating missing
+    /// objects along the way. Returns `None` if an intermediate value exists
+    /// but is not an object.
+    pub fn set(&mut self, keys: &[&str], value: Value) -> Option<()> {
+        let root = self.root()?;
+        let (stripped, trailing_commas) = strip(&self.text);
+        // Edits go to a copy, so a splice that doesn't parse leaves the document intact
+        let mut text = self.text.clone();
+
+        let mut node = &root;
+        let mut depth = 0;
+        while depth < keys.len() {
+            let members = node.members.as_ref()?;
+            match members.iter().rev().find(|m| m.key == keys[depth]) {
+                Some(member) => {
+                    node = &member.value;
+                    depth += 1;
+                }
+                None => break,
+            }
+        }
+
+        if depth == keys.len() {
+            // Replace the existing value in place
+            let indent = self.line_indent(node.start).to_string();
+            let rendered = self.render_value(&value, &indent);
+            text.replace_range(node.start..node.end, &rendered);
+        } else {
+            // Insert a new member into the deepest existing object
+            let members = node.members.as_ref()?;
+            let mut new_value = value;
+            for key in keys[depth + 1..].iter().rev() {
+                let mut map = serde_json::Map::new();
+                map.insert(key.to_string(), new_value);
+                new_value = Value::Object(map);
+            }
+            let key_json = Value::String(keys[depth].to_string()).to_string();
+            let close = node.end - 1;
+
+            match members.last() {
+                None => {
+                    let outer = self.line_indent(node.start).to_string();
+                    let inner = format!("{}{}", outer, self.indent_unit());
+                    let rendered = self.render_value(&new_value, &inner);
+                    let insert = format!("\n{}{}: {}\n{}", inner, key_json, rendered, outer);
+                    // Only whitespace goes; comments inside the braces stay
+                    let before_close = self.text[..close].trim_end().len();
+                    text.replace_range(before_close..close, &insert);
+                }
+                Some(last) => {
+                    let first = &members[0];
+                    let multiline = stripped[node.start..first.key_start].contains('\n');
+                    let has_trailing = trailing_commas
+                        .iter()
+                        .any(|&c| c >= last.value.end && c < close);
+
+                    if multiline {
+                        let indent = self.line_indent(first.key_start).to_string();
+                        let rendered = self.render_value(&new_value, &indent);
+                        let trailing = if has_trailing { "," } else { "" };
+                        // Insert before the whitespace that precedes the closing brace
+                        let before_close = self.text[..close].trim_end().len().max(last.value.end);
+                        let insert = format!("\n{}{}: {}{}", indent, key_json, rendered, trailing);
+                        text.insert_str(before_close, &insert);
+                        if !has_trailing {
+                            text.insert(last.value.end, ',');
+                        }
+                    } else {
+                        let insert = format!(", {}: {}", key_json, new_value);
+                        text.insert_str(last.value.end, &insert);
+                    }
+                }
+            }
+        }
+
+        self.value = parse(&text).ok()?;
+        self.text = text;
+        Some(())
+    }
+
+    /// Remove a member by its key path, returning whether it existed
+    pub fn remove(&mut self, keys: &[&str]) -> bool {
+        let (parent_keys, key) = match keys.split_last() {
+            Some((key, parents)) => (parents, *key),
+            None => return false,
+        };
+        let root = match self.root() {
+            Some(root) => root,
+            None => return false,
+        };
+        let (stripped, _) = strip(&self.text);
+
+        let mut node = &root;
+        for k in parent_keys {
+            match node
+                .members
+                .as_ref()
+                .and_then(|m| m.iter().rev().find(|m| m.key == *k))
+            {
+                Some(member) => node = &member.value,
+                None => return false,
+            }
+        }
+        let members = match node.members.as_ref() {
+            Some(members) => members,
+            None => return false,
+        };
+        let idx = match members.iter().rposition(|m| m.key == key) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let member = &members[idx];
+
+        let bytes = stripped.as_bytes();
+        let mut after = member.value.end;
+        while after < bytes.len() && bytes[after].is_ascii_whitespace() && bytes[after] != b'\n' {
+            after += 1;
+        }
+
+        let range = if bytes.get(after) == Some(&b',') {
+            // Drop the member together with its comma and, if it sat on its own line, the line
+            let line_start = self.text[..member.key_start]
+                .rfind('\n')
+                .map(|p| p + 1)
+                .unwrap_or(0);
+            let start = if self.text[line_start..member.key_start].trim().is_empty()
+                && line_start > node.start
+            {
+                line_start
+            } else {
+                member.key_start
+            };
+            let mut end = after + 1;
+            let rest_end = self.text[end..]
+                .find('\n')
+                .map(|p| end + p + 1)
+                .unwrap_or(self.text.len());
+            if start == line_start && self.text[end..rest_end].trim().is_empty() {
+                end = rest_end;
+            }
+            start..end
+        } else if idx > 0 {
+            // Last member: drop the preceding comma instead
+            let prev_end = members[idx - 1].value.end;
+            let comma = stripped[prev_end..member.key_start]
+                .find(',')
+                .map(|p| prev_end + p)
+                .unwrap_or(prev_end);
+            comma..member.value.end
+        } else {
+            // Only member
+            node.start + 1..node.end - 1
+        };
+
+        let mut text = self.text.clone();
+        text.replace_range(range, "");
+        match parse(&text) {
+            Ok(value) => {
+                self.text = text;
+                self.value = value;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r#"{
+    // Editor preferences
+    "editor.fontSize": 14,
+    "workbench.colorTheme": "Default Dark+", /* theme */
+    "amp.mcpServers": {
+        "old": { "command": "old-cmd" }
+    },
+}
+"#;
+
+    #[test]
+    fn test_parse_with_comments_and_trailing_commas() {
+        let value = parse(SETTINGS).unwrap();
+        assert_eq!(value["editor.fontSize"], 14);
+        assert_eq!(value["amp.mcpServers"]["old"]["command"], "old-cmd");
+    }
+
+    #[test]
+    fn test_comment_markers_inside_strings_are_kept() {
+        let value = parse(r#"{ "url": "https://example.com/*x*/", "a": "//", }"#).unwrap();
+        assert_eq!(value["url"], "https://example.com/*x*/");
+        assert_eq!(value["a"], "//");
+    }
+
+    #[test]
+    fn test_replace_subtree_preserves_everything_else() {
+        let mut doc = JsoncDocument::parse(SETTINGS).unwrap();
+        doc.set(
+            &["amp.mcpServers"],
+            serde_json::json!({ "new": { "command": "new-cmd" } }),
+        )
+        .unwrap();
+
+        let text = doc.text();
+        assert!(text.contains("// Editor preferences"));
+        assert!(text.contains("/* theme */"));
+        assert!(text.starts_with("{\n    // Editor preferences\n    \"editor.fontSize\": 14,\n"));
+        assert!(!text.contains("old-cmd"));
+        assert!(text.contains("    \"amp.mcpServers\": {\n        \"new\": {\n            \"command\": \"new-cmd\"\n        }\n    },\n}"));
+        assert_eq!(doc.value()["amp.mcpServers"]["new"]["command"], "new-cmd");
+    }
+
+    #[test]
+    fn test_insert_keeps_trailing_comma_style() {
+        let mut doc = JsoncDocument::parse(SETTINGS).unwrap();
+        doc.set(&["mcp", "servers"], serde_json::json!({})).unwrap();
+
+        let text = doc.text();
+        assert!(text.ends_with("    },\n    \"mcp\": {\n        \"servers\": {}\n    },\n}\n"));
+        assert_eq!(doc.value()["editor.fontSize"], 14);
+    }
+
+    #[test]
+    fn test_insert_after_line_comment() {
+        let mut doc = JsoncDocument::parse("{\n  \"a\": 1 // note\n}\n").unwrap();
+        doc.set(&["b"], serde_json::json!(true)).unwrap();
+        assert_eq!(doc.text(), "{\n  \"a\": 1, // note\n  \"b\": true\n}\n");
+    }
+
+    #[test]
+    fn test_insert_into_empty_document() {
+        let mut doc = JsoncDocument::empty();
+        doc.set(
+            &["mcpServers"],
+            serde_json::json!({ "x": { "command": "y" } }),
+        )
+        .unwrap();
+        assert_eq!(
+            doc.text(),
+            "{\n  \"mcpServers\": {\n    \"x\": {\n      \"command\": \"y\"\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_into_empty_object_keeps_comments() {
+        let mut doc = JsoncDocument::parse("{ /* servers */ }\n").unwrap();
+        doc.set(&["a"], serde_json::json!(1)).unwrap();
+        assert_eq!(doc.text(), "{ /* servers */\n  \"a\": 1\n}\n");
+    }
+
+    #[test]
+    fn test_set_through_scalar_fails() {
+        let mut doc = JsoncDocument::parse(r#"{ "a": 1 }"#).unwrap();
+        assert!(doc.set(&["a", "b"], serde_json::json!(1)).is_none());
+    }
+
+    #[test]
+    fn test_untouched_document_is_byte_identical() {
+        let doc = JsoncDocument::parse(SETTINGS).unwrap();
+        assert_eq!(doc.text(), SETTINGS);
+    }
+
+    #[test]
+    fn test_remove_member() {
+        let mut doc = JsoncDocument::parse("{\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3\n}\n").unwrap();
+        assert!(doc.remove(&["b"]));
+        assert_eq!(doc.text(), "{\n  \"a\": 1,\n  \"c\": 3\n}\n");
+        assert!(doc.remove(&["c"]));
+        assert_eq!(doc.text(), "{\n  \"a\": 1\n}\n");
+        assert!(!doc.remove(&["missing"]));
+    }
+
+    #[test]
+    fn test_failed_edit_keeps_document() {
+        // Text that stops parsing once edited, like a file with junk after the root
+        let text = "{ \"a\": 1 } junk";
+        let mut doc = JsoncDocument {
+            text: text.to_string(),
+            value: serde_json::json!({ "a": 1 }),
+        };
+
+        assert!(doc.set(&["b"], serde_json::json!(2)).is_none());
+        assert!(!doc.remove(&["a"]));
+        assert_eq!(doc.text(), text);
+        assert_eq!(doc.value(), &serde_json::json!({ "a": 1 }));
+    }
+}
@@ -0,0 +1,407 @@
+mod ini;
+pub mod jsonc;
+mod toml;
+
+use crate::config::ConfigFormat;
+use serde_json::Value;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FormatError {
+    #[error("Failed to parse {format} config: {message}")]
+    Parse {
+        format: &'static str,
+        message: String,
+    },
+    #[error("Failed to serialize {format} config: {message}")]
+    Serialize {
+        format: &'static str,
+        message: String,
+    },
+    #[error("Format does not support structured editing: {0}")]
+    Unsupported(&'static str),
+    #[error("Value cannot be represented in {format}: {message}")]
+    InvalidValue {
+        format: &'static str,
+        message: String,
+    },
+}
+
+impl ConfigFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Yaml => "YAML",
+            ConfigFormat::Toml => "TOML",
+            ConfigFormat::Ini => "INI",
+            ConfigFormat::Md => "Markdown",
+        }
+    }
+
+    /// Guess the format from a file extension, e.g. `~/.aider.conf.yml` -> Yaml
+    pub fn from_path(path: &Path) -> Option<ConfigFormat> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "json" | "jsonc" => Some(ConfigFormat::Json),
+            "yml" | "yaml" => Some(ConfigFormat::Yaml),
+            "toml" => Some(ConfigFormat::Toml),
+            "ini" | "cfg" | "conf" => Some(ConfigFormat::Ini),
+            "md" | "markdown" => Some(ConfigFormat::Md),
+            _ => None,
+        }
+    }
+}
+
+/// Pick the format for a file: an explicit format (from `SuggestedConfig.format`)
+/// wins, then the file extension, then JSON for backwards compatibility.
+pub fn resolve_format(explicit: Option<ConfigFormat>, path: &Path) -> ConfigFormat {
+    explicit
+        .or_else(|| ConfigFormat::from_path(path))
+        .unwrap_or(ConfigFormat::Json)
+}
+
+/// A parsed config file that supports path-level reads and writes.
+///
+/// Every format exposes the same JSON view, so path semantics (literal dotted
+/// keys first, then nested traversal) are identical across formats. JSON and
+/// TOML keep their source text so comments and layout survive a write.
+pub enum ConfigDocument {
+    Json(jsonc::JsoncDocument),
+    Yaml(Value),
+    Toml(toml_edit::DocumentMut),
+    Ini(Value),
+}
+
+impl ConfigDocument {
+    pub fn parse(content: &str, format: &ConfigFormat) -> Result<Self, FormatError> {
+        let parse_error = |message: String| FormatError::Parse {
+            format: format.name(),
+            message,
+        };
+
+        match format {
+            ConfigFormat::Json => jsonc::JsoncDocument::parse(content)
+                .map(ConfigDocument::Json)
+                .map_err(|e| parse_error(e.to_string())),
+            ConfigFormat::Yaml => {
+                // An empty YAML file is a valid (null) document; treat it as an empty map
+                if content.trim().is_empty() {
+                    return Ok(ConfigDocument::Yaml(serde_json::json!({})));
+                }
+                let yaml: serde_yaml::Value =
+                    serde_yaml::from_str(content).map_err(|e| parse_error(e.to_string()))?;
+                serde_json::to_value(yaml)
+                    .map(ConfigDocument::Yaml)
+                    .map_err(|e| parse_error(e.to_string()))
+            }
+            ConfigFormat::Toml => content
+                .parse::<toml_edit::DocumentMut>()
+                .map(ConfigDocument::Toml)
+                .map_err(|e| parse_error(e.to_string())),
+            ConfigFormat::Ini => ini::parse(content)
+                .map(ConfigDocument::Ini)
+                .map_err(parse_error),
+            ConfigFormat::Md => Err(FormatError::Unsupported(format.name())),
+        }
+    }
+
+    /// An empty document, used when writing to a file that does not exist yet
+    pub fn empty(format: &ConfigFormat) -> Result<Self, FormatError> {
+        match format {
+            ConfigFormat::Json => Ok(ConfigDocument::Json(jsonc::JsoncDocument::empty())),
+            ConfigFormat::Yaml => Ok(ConfigDocument::Yaml(serde_json::json!({}))),
+            ConfigFormat::Toml => Ok(ConfigDocument::Toml(toml_edit::DocumentMut::new())),
+            ConfigFormat::Ini => Ok(ConfigDocument::Ini(serde_json::json!({}))),
+            ConfigFormat::Md => Err(FormatError::Unsupported(format.name())),
+        }
+    }
+
+    pub fn format(&self) -> ConfigFormat {
+        match self {
+            ConfigDocument::Json(_) => ConfigFormat::Json,
+            ConfigDocument::Yaml(_) => ConfigFormat::Yaml,
+            ConfigDocument::Toml(_) => ConfigFormat::Toml,
+            ConfigDocument::Ini(_) => ConfigFormat::Ini,
+        }
+    }
+
+    /// The whole document as JSON
+    pub fn to_value(&self) -> Value {
+        match self {
+            ConfigDocument::Json(doc) => doc.value().clone(),
+            ConfigDocument::Yaml(v) | ConfigDocument::Ini(v) => v.clone(),
+            ConfigDocument::Toml(doc) => toml::table_to_value(doc.as_table()),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<Value> {
+        match self {
+            ConfigDocument::Json(doc) => get_json_value(doc.value(), path).cloned(),
+            ConfigDocument::Yaml(v) | ConfigDocument::Ini(v) => get_json_value(v, path).cloned(),
+            ConfigDocument::Toml(_) => get_json_value(&self.to_value(), path).cloned(),
+        }
+    }
+
+    /// Set the value at `path`. Returns `Ok(None)` when the path cannot be
+    /// created (e.g. an intermediate key holds a scalar).
+    pub fn set(&mut self, path: &str, value: Value) -> Result<Option<()>, FormatError> {
+        // First try the path as a literal key (for keys like "amp.mcpServers")
+        let keys: Vec<&str> = if self.to_value().get(path).is_some() {
+            vec![path]
+        } else {
+            path.split('.').collect()
+        };
+        self.set_keys(&keys, value)
+    }
+
+    /// Set the value under an explicit sequence of object keys, for callers
+    /// that already know whether a dotted key is literal or nested
+    pub fn set_keys(&mut self, keys: &[&str], value: Value) -> Result<Option<()>, FormatError> {
+        match self {
+            ConfigDocument::Json(doc) => Ok(doc.set(keys, value)),
+            ConfigDocument::Yaml(root) => Ok(set_value_at_keys(root, keys, value)),
+            ConfigDocument::Ini(root) => {
+                ini::check_value(&keys.join("."), &value)?;
+                Ok(set_value_at_keys(root, keys, value))
+            }
+            ConfigDocument::Toml(doc) => toml::set_keys(doc, keys, &value),
+        }
+    }
+
+    /// Remove a top-level key, returning whether it existed
+    pub fn remove(&mut self, key: &str) -> bool {
+        match self {
+            ConfigDocument::Json(doc) => doc.remove(&[key]),
+            ConfigDocument::Yaml(root) | ConfigDocument::Ini(root) => root
+                .as_object_mut()
+                .map(|map| map.remove(key).is_some())
+                .unwrap_or(false),
+            ConfigDocument::Toml(doc) => doc.remove(key).is_some(),
+        }
+    }
+
+    /// Remove the value under an explicit sequence of object keys, returning
+    /// whether it existed
+    pub fn remove_keys(&mut self, keys: &[&str]) -> bool {
+        match self {
+            ConfigDocument::Json(doc) => doc.remove(keys),
+            ConfigDocument::Yaml(root) | ConfigDocument::Ini(root) => {
+                remove_value_at_keys(root, keys)
+            }
+            ConfigDocument::Toml(doc) => toml::remove_keys(doc, keys),
+        }
+    }
+
+    pub fn render(&self) -> Result<String, FormatError> {
+        let format = self.format();
+        let serialize_error = |message: String| FormatError::Serialize {
+            format: format.name(),
+            message,
+        };
+
+        match self {
+            ConfigDocument::Json(doc) => Ok(doc.text().to_string()),
+            ConfigDocument::Yaml(root) => {
+                serde_yaml::to_string(root).map_err(|e| serialize_error(e.to_string()))
+            }
+            ConfigDocument::Toml(doc) => Ok(doc.to_string()),
+            ConfigDocument::Ini(root) => ini::render(root),
+        }
+    }
+}
+
+pub fn get_json_value<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    // First try the path as a literal key (for keys like "amp.mcpServers")
+    if let Some(value) = root.get(path) {
+        return Some(value);
+    }
+
+    // Fall back to nested path traversal (for paths like "foo.bar.baz")
+    let parts: Vec<&str> = path.split('.').collect();
+    let mut current = root;
+
+    for part in parts {
+        current = current.get(part)?;
+    }
+
+    Some(current)
+}
+
+fn set_value_at_keys(root: &mut Value, keys: &[&str], value: Value) -> Option<()> {
+    let (last, parents) = keys.split_last()?;
+    let mut current = root;
+
+    // Traverse or create intermediate objects
+    for part in parents {
+        if current.get(part).is_none() {
+            if let Value::Object(map) = current {
+                map.insert(part.to_string(), serde_json::json!({}));
+            }
+        }
+        current = current.get_mut(part)?;
+    }
+
+    match current {
+        Value::Object(map) => {
+            map.insert(last.to_string(), value);
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+fn remove_value_at_keys(root: &mut Value, keys: &[&str]) -> bool {
+    let Some((last, parents)) = keys.split_last() else {
+        return false;
+    };
+    parents
+        .iter()
+        .try_fold(root, |current, part| current.get_mut(part))
+        .and_then(Value::as_object_mut)
+        .is_some_and(|map| map.remove(*last).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert!(matches!(
+            ConfigFormat::from_path(Path::new("/home/u/.aider.conf.yml")),
+            Some(ConfigFormat::Yaml)
+        ));
+        assert!(matches!(
+            ConfigFormat::from_path(Path::new("config.toml")),
+            Some(ConfigFormat::Toml)
+        ));
+        assert!(matches!(
+            ConfigFormat::from_path(Path::new("setup.cfg")),
+            Some(ConfigFormat::Ini)
+        ));
+        assert!(ConfigFormat::from_path(Path::new(".claude.json")).is_some());
+        assert!(ConfigFormat::from_path(Path::new("Makefile")).is_none());
+    }
+
+    #[test]
+    fn test_explicit_format_wins() {
+        let format = resolve_format(Some(ConfigFormat::Yaml), Path::new("settings.json"));
+        assert!(matches!(format, ConfigFormat::Yaml));
+        assert!(matches!(
+            resolve_format(None, Path::new("noext")),
+            ConfigFormat::Json
+        ));
+    }
+
+    #[test]
+    fn test_yaml_get_and_set() {
+        let content = "model: gpt-4\nlint-cmd:\n  python: flake8\n";
+        let mut doc = ConfigDocument::parse(content, &ConfigFormat::Yaml).unwrap();
+        assert_eq!(
+            doc.get("lint-cmd.python"),
+            Some(serde_json::json!("flake8"))
+        );
+
+        doc.set("lint-cmd.rust", serde_json::json!("clippy"))
+            .unwrap()
+            .unwrap();
+        doc.set("auto-commits", serde_json::json!(false))
+            .unwrap()
+            .unwrap();
+
+        let rendered = doc.render().unwrap();
+        let reparsed = ConfigDocument::parse(&rendered, &ConfigFormat::Yaml).unwrap();
+        assert_eq!(
+            reparsed.get("lint-cmd.rust"),
+            Some(serde_json::json!("clippy"))
+        );
+        assert_eq!(reparsed.get("auto-commits"), Some(serde_json::json!(false)));
+        assert_eq!(reparsed.get("model"), Some(serde_json::json!("gpt-4")));
+    }
+
+    #[test]
+    fn test_toml_set_preserves_comments() {
+        let content = "# Codex config\nmodel = \"o3\" # default model\n\n[history]\npersistence = \"save-all\"\n";
+        let mut doc = ConfigDocument::parse(content, &ConfigFormat::Toml).unwrap();
+        assert_eq!(
+            doc.get("history.persistence"),
+            Some(serde_json::json!("save-all"))
+        );
+
+        doc.set("history.max_bytes", serde_json::json!(1024))
+            .unwrap()
+            .unwrap();
+        doc.set("sandbox", serde_json::json!({ "mode": "workspace-write" }))
+            .unwrap()
+            .unwrap();
+
+        let rendered = doc.render().unwrap();
+        assert!(rendered.contains("# Codex config"));
+        assert!(rendered.contains("# default model"));
+
+        let reparsed = ConfigDocument::parse(&rendered, &ConfigFormat::Toml).unwrap();
+        assert_eq!(
+            reparsed.get("history.max_bytes"),
+            Some(serde_json::json!(1024))
+        );
+        assert_eq!(
+            reparsed.get("sandbox.mode"),
+            Some(serde_json::json!("workspace-write"))
+        );
+    }
+
+    #[test]
+    fn test_toml_set_through_scalar_fails() {
+        let mut doc = ConfigDocument::parse("model = \"o3\"\n", &ConfigFormat::Toml).unwrap();
+        assert!(doc
+            .set("model.name", serde_json::json!("x"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_ini_get_and_set() {
+        let content = "verbose = true\n\n[core]\neditor = vim\n";
+        let mut doc = ConfigDocument::parse(content, &ConfigFormat::Ini).unwrap();
+        assert_eq!(doc.get("core.editor"), Some(serde_json::json!("vim")));
+        assert_eq!(doc.get("verbose"), Some(serde_json::json!("true")));
+
+        doc.set("core.pager", serde_json::json!("less"))
+            .unwrap()
+            .unwrap();
+        let rendered = doc.render().unwrap();
+        let reparsed = ConfigDocument::parse(&rendered, &ConfigFormat::Ini).unwrap();
+        assert_eq!(reparsed.get("core.pager"), Some(serde_json::json!("less")));
+        assert_eq!(reparsed.get("verbose"), Some(serde_json::json!("true")));
+    }
+
+    #[test]
+    fn test_ini_rejects_nested_values() {
+        let mut doc = ConfigDocument::empty(&ConfigFormat::Ini).unwrap();
+        let result = doc.set("core", serde_json::json!({ "deep": { "nested": 1 } }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_json_set_preserves_comments() {
+        let content =
+            "{\n  // keep me\n  \"model\": \"sonnet\",\n  \"permissions\": { \"allow\": [] }\n}\n";
+        let mut doc = ConfigDocument::parse(content, &ConfigFormat::Json).unwrap();
+        doc.set("permissions.allow", serde_json::json!(["Bash(ls)"]))
+            .unwrap()
+            .unwrap();
+
+        let rendered = doc.render().unwrap();
+        assert!(rendered.starts_with("{\n  // keep me\n  \"model\": \"sonnet\",\n"));
+        assert_eq!(
+            doc.get("permissions.allow"),
+            Some(serde_json::json!(["Bash(ls)"]))
+        );
+    }
+
+    #[test]
+    fn test_markdown_is_unsupported() {
+        assert!(ConfigDocument::parse("# Notes", &ConfigFormat::Md).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use super::FormatError;
+use serde_json::Value;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike};
+
+const FORMAT: &str = "TOML";
+
+pub fn table_to_value(table: &dyn TableLike) -> Value {
+    let map = table
+        .iter()
+        .filter_map(|(key, item)| item_to_value(item).map(|v| (key.to_string(), v)))
+        .collect();
+    Value::Object(map)
+}
+
+fn item_to_value(item: &Item) -> Option<Value> {
+    match item {
+        Item::None => None,
+        Item::Value(value) => Some(toml_value_to_value(value)),
+        Item::Table(table) => Some(table_to_value(table)),
+        Item::ArrayOfTables(tables) => Some(Value::Array(
+            tables.iter().map(|t| table_to_value(t)).collect(),
+        )),
+    }
+}
+
+fn toml_value_to_value(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => serde_json::Number::from_f64(*f.value())
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(arr) => Value::Array(arr.iter().map(toml_value_to_value).collect()),
+        toml_edit::Value::InlineTable(table) => table_to_value(table),
+    }
+}
+
+fn value_to_toml_value(value: &Value) -> Result<toml_edit::Value, FormatError> {
+    Ok(match value {
+        Value::Null => {
+            return Err(FormatError::InvalidValue {
+                format: FORMAT,
+                message: "null has no TOML representation".to_string(),
+            })
+        }
+        Value::Bool(b) => toml_edit::Value::from(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => toml_edit::Value::from(i),
+            None => toml_edit::Value::from(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => toml_edit::Value::from(s.as_str()),
+        Value::Array(items) => {
+            let mut arr = Array::new();
+            for item in items {
+                arr.push(value_to_toml_value(item)?);
+            }
+            toml_edit::Value::Array(arr)
+        }
+        Value::Object(map) => {
+            let mut table = InlineTable::new();
+            for (key, item) in map {
+                // TOML has no null; drop such keys instead of failing the whole write
+                if item.is_null() {
+                    continue;
+                }
+                table.insert(key, value_to_toml_value(item)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+/// Convert a JSON value into a TOML item. Objects become standard tables and
+/// arrays of objects become arrays of tables, matching hand-written TOML.
+pub fn value_to_item(value: &Value) -> Result<Item, FormatError> {
+    match value {
+        Value::Object(map) => {
+            let mut table = Table::new();
+            for (key, item) in map {
+                if item.is_null() {
+                    continue;
+                }
+                table.insert(key, value_to_item(item)?);
+            }
+            Ok(Item::Table(table))
+        }
+        Value::Array(items) if !items.is_empty() && items.iter().all(|i| i.is_object()) => {
+            let mut tables = ArrayOfTables::new();
+            for item in items {
+                if let Item::Table(table) = value_to_item(item)? {
+                    tables.push(table);
+                }
+            }
+            Ok(Item::ArrayOfTables(tables))
+        }
+        _ => Ok(Item::Value(value_to_toml_value(value)?)),
+    }
+}
+
+pub fn set_keys(
+    doc: &mut DocumentMut,
+    keys: &[&str],
+    value: &Value,
+) -> Result<Option<()>, FormatError> {
+    let item = value_to_item(value)?;
+
+    let (last, parents) = match keys.split_last() {
+        Some(split) => split,
+        None => return Ok(None),
+    };
+
+    let mut current: &mut dyn TableLike = doc.as_table_mut();
+    for part in parents {
+        if current.get(part).is_none() {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            current.insert(part, Item::Table(table));
+        }
+        current = match current
+            .get_mut(part)
+            .and_then(|item| item.as_table_like_mut())
+        {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+    }
+
+    // A replaced table keeps its place in the file and the comments above it
+    let mut item = item;
+    if let (Some(Item::Table(old)), Item::Table(new)) = (current.get(last), &mut item) {
+        if let Some(position) = old.position() {
+            new.set_position(position);
+        }
+        *new.decor_mut() = old.decor().clone();
+    }
+    current.insert(last, item);
+    Ok(Some(()))
+}
+
+/// Remove the item under `keys`, returning whether it existed
+pub fn remove_keys(doc: &mut DocumentMut, keys: &[&str]) -> bool {
+    let Some((last, parents)) = keys.split_last() else {
+        return false;
+    };
+
+    let mut current: &mut dyn TableLike = doc.as_table_mut();
+    for part in parents {
+        current = match current
+            .get_mut(part)
+            .and_then(|item| item.as_table_like_mut())
+        {
+            Some(table) => table,
+            None => return false,
+        };
+    }
+    current.remove(last).is_some()
+}
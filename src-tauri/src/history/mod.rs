@@ -0,0 +1,669 @@
+use crate::app_paths::AppPaths;
+use crate::commands::BackupSettings;
+use crate::config::tool_registry;
+use crate::transaction::{fingerprint, FileTarget, Transaction, TransactionError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("git is not installed or not on PATH")]
+    GitUnavailable,
+    #[error("git failed: {0}")]
+    Git(String),
+    #[error("History commit not found: {0}")]
+    NotFound(String),
+    #[error("Cannot revert {0}: later changes touch the same lines")]
+    Conflict(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to get app data directory")]
+    AppDataDir,
+    #[error("{0}")]
+    Transaction(#[from] TransactionError),
+}
+
+impl Serialize for HistoryError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// `history.json`, next to the repository rather than inside it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySettings {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStatus {
+    pub enabled: bool,
+    pub git_available: bool,
+    /// The mirror repository, for use with `git log` and friends
+    pub repo_path: String,
+}
+
+/// A commit in the mirror, with the fields of its structured message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryCommit {
+    pub id: String,
+    pub timestamp: u64,
+    pub subject: String,
+    /// The real file, local or `user@host:path`
+    pub path: String,
+    /// Where the file lives inside the mirror
+    pub mirror_path: String,
+    pub origin: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryShow {
+    pub commit: HistoryCommit,
+    /// File content as of this commit
+    pub content: String,
+    /// Unified diff against the previous commit of the file
+    pub patch: String,
+}
+
+/// One write to mirror. `previous` is what the file held before the write,
+/// when the caller knows it; if it differs from the mirror, the outside edit
+/// is committed first so the write's own commit shows only its change.
+pub struct HistoryEntry<'a> {
+    pub path: &'a str,
+    pub previous: Option<&'a str>,
+    pub content: &'a str,
+    /// The command that made the write, e.g. `write_json_path`
+    pub origin: &'a str,
+    pub json_path: Option<&'a str>,
+    pub tool_id: Option<&'a str>,
+}
+
+/// Serializes git invocations on the mirror across commands.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+const EXTERNAL_ORIGIN: &str = "external";
+
+fn settings_path(app: &impl AppPaths) -> Result<PathBuf, HistoryError> {
+    Ok(app
+        .app_data_dir()
+        .ok_or(HistoryError::AppDataDir)?
+        .join("history.json"))
+}
+
+fn repo_dir(app: &impl AppPaths) -> Result<PathBuf, HistoryError> {
+    Ok(app
+        .app_data_dir()
+        .ok_or(HistoryError::AppDataDir)?
+        .join("history"))
+}
+
+pub fn load_history_settings(app: &impl AppPaths) -> Result<HistorySettings, HistoryError> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(HistorySettings::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Whether writes should also be mirrored to git.
+pub fn history_enabled(app: &impl AppPaths) -> bool {
+    load_history_settings(app)
+        .map(|s| s.enabled)
+        .unwrap_or(false)
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, HistoryError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => HistoryError::GitUnavailable,
+            _ => HistoryError::Io(e),
+        })?;
+
+    if !output.status.success() {
+        return Err(HistoryError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Create the mirror repository on first use. The identity is set locally
+/// so commits work without a global git config.
+fn ensure_repo(app: &impl AppPaths) -> Result<PathBuf, HistoryError> {
+    let repo = repo_dir(app)?;
+    if repo.join(".git").exists() {
+        return Ok(repo);
+    }
+
+    fs::create_dir_all(&repo)?;
+    git(&repo, &["init", "-q"])?;
+    git(&repo, &["config", "user.name", "CLI Config Editor"])?;
+    git(
+        &repo,
+        &["config", "user.email", "history@cli-config-editor.local"],
+    )?;
+    git(&repo, &["config", "commit.gpgsign", "false"])?;
+    Ok(repo)
+}
+
+/// Where a file is mirrored: `home/...` for files under the home directory,
+/// `local/...` for other local files and `remote/<host>/...` for SSH paths.
+fn mirror_path(target: &FileTarget) -> String {
+    let (prefix, path) = match target {
+        FileTarget::Local(path) => match dirs::home_dir()
+            .and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf))
+        {
+            Some(relative) => ("home".to_string(), relative),
+            None => ("local".to_string(), path.clone()),
+        },
+        FileTarget::Remote(conn) => {
+            let path = conn.path.trim_start_matches("~/");
+            let prefix = if conn.path.starts_with('/') {
+                "root"
+            } else {
+                "home"
+            };
+            (
+                format!("remote/{}/{}", conn.host, prefix),
+                PathBuf::from(path),
+            )
+        }
+    };
+
+    let mut parts = vec![prefix];
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => parts.push(
+                prefix
+                    .as_os_str()
+                    .to_string_lossy()
+                    .trim_end_matches(':')
+                    .to_string(),
+            ),
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            _ => {}
+        }
+    }
+    parts.join("/")
+}
+
+/// Tool id and config label for `target`, from the registry's suggested
+/// configs. A config whose `jsonPath` matches wins over one that doesn't.
+fn describe(
+    app: &impl AppPaths,
+    target: &FileTarget,
+    json_path: Option<&str>,
+) -> (Option<String>, Option<String>) {
+    let same_file = |path: &str| match (FileTarget::parse(path), target) {
+        (Ok(FileTarget::Local(a)), FileTarget::Local(b)) => a == *b,
+        (Ok(FileTarget::Remote(a)), FileTarget::Remote(b)) => a.host == b.host && a.path == b.path,
+        _ => false,
+    };
+
+    let registry = tool_registry(app);
+    let mut best: Option<(bool, String, String)> = None;
+    for tool in &registry.tools {
+        for config in tool
+            .suggested_configs
+            .iter()
+            .flatten()
+            .filter(|c| same_file(&c.path))
+        {
+            let exact = config.json_path.as_deref() == json_path;
+            if best
+                .as_ref()
+                .map(|(was_exact, ..)| exact && !was_exact)
+                .unwrap_or(true)
+            {
+                best = Some((exact, tool.id.clone(), config.label.clone()));
+            }
+        }
+    }
+    if let Some((_, tool, label)) = best {
+        return (Some(tool), Some(label));
+    }
+
+    match registry
+        .mcp_tools
+        .iter()
+        .find(|t| same_file(&t.config_path))
+    {
+        Some(tool) => (
+            Some(tool.tool_id.clone()),
+            Some(format!("{} MCP servers", tool.name)),
+        ),
+        None => (None, None),
+    }
+}
+
+fn commit_message(subject: &str, trailers: &[(&str, Option<&str>)]) -> String {
+    let mut message = format!("{}\n", subject);
+    for (key, value) in trailers {
+        if let Some(value) = value {
+            message.push_str(&format!("\n{}: {}", key, value));
+        }
+    }
+    message
+}
+
+/// Write `content` to the mirror and commit it. Returns `false` when the
+/// mirror already held exactly that content.
+fn commit_file(
+    repo: &Path,
+    mirror: &str,
+    content: &str,
+    message: &str,
+) -> Result<bool, HistoryError> {
+    let file = repo.join(mirror);
+    if fs::read_to_string(&file).ok().as_deref() == Some(content) {
+        return Ok(false);
+    }
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&file, content)?;
+    git(repo, &["add", "--", mirror])?;
+    git(repo, &["commit", "-q", "-m", message, "--", mirror])?;
+    Ok(true)
+}
+
+pub fn record_history_internal(
+    app: &impl AppPaths,
+    entry: &HistoryEntry,
+) -> Result<(), HistoryError> {
+    if !history_enabled(app) {
+        return Ok(());
+    }
+
+    let target = FileTarget::parse(entry.path)?;
+    let (tool, config) = match entry.tool_id {
+        Some(tool_id) => (
+            Some(tool_id.to_string()),
+            describe(app, &target, entry.json_path).1,
+        ),
+        None => describe(app, &target, entry.json_path),
+    };
+    let display = target.to_string();
+    let mirror = mirror_path(&target);
+
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let repo = ensure_repo(app)?;
+
+    if let Some(previous) = entry.previous {
+        let message = commit_message(
+            &format!("Record outside changes to {}", display),
+            &[
+                ("Tool", tool.as_deref()),
+                ("Config", config.as_deref()),
+                ("Origin", Some(EXTERNAL_ORIGIN)),
+                ("Path", Some(&display)),
+            ],
+        );
+        commit_file(&repo, &mirror, previous, &message)?;
+    }
+
+    let message = commit_message(
+        &format!("Update {}", display),
+        &[
+            ("Tool", tool.as_deref()),
+            ("Config", config.as_deref()),
+            ("Json-Path", entry.json_path),
+            ("Origin", Some(entry.origin)),
+            ("Path", Some(&display)),
+        ],
+    );
+    commit_file(&repo, &mirror, entry.content, &message)?;
+    Ok(())
+}
+
+/// Mirror a write that has already landed. Failures are reported with
+/// `AppPaths::warn`.
+pub fn record_history(app: &impl AppPaths, entry: &HistoryEntry) {
+    if let Err(e) = record_history_internal(app, entry) {
+        app.warn(&format!(
+            "Failed to record history for {}: {}",
+            entry.path, e
+        ));
+    }
+}
+
+/// `git log` fields, one record per commit. `--name-only` appends the
+/// mirrored file after the last field.
+const LOG_FORMAT: &str = "--format=%x1e%H%x1f%ct%x1f%B%x1f";
+
+fn parse_log(output: &str) -> Vec<HistoryCommit> {
+    output
+        .split('\u{1e}')
+        .filter_map(|record| {
+            let [id, timestamp, message, files] = record.split('\u{1f}').collect::<Vec<_>>()[..]
+            else {
+                return None;
+            };
+            let trailer = |key: &str| {
+                message
+                    .lines()
+                    .skip(1)
+                    .find_map(|line| line.strip_prefix(key)?.strip_prefix(": "))
+                    .map(str::to_string)
+            };
+
+            Some(HistoryCommit {
+                id: id.to_string(),
+                timestamp: timestamp.parse().unwrap_or(0),
+                subject: message.lines().next().unwrap_or_default().to_string(),
+                path: trailer("Path").unwrap_or_default(),
+                mirror_path: files.trim().lines().next().unwrap_or_default().to_string(),
+                origin: trailer("Origin").unwrap_or_default(),
+                tool: trailer("Tool"),
+                config: trailer("Config"),
+                json_path: trailer("Json-Path"),
+            })
+        })
+        .collect()
+}
+
+fn open_repo(app: &impl AppPaths) -> Result<Option<PathBuf>, HistoryError> {
+    let repo = repo_dir(app)?;
+    Ok(repo.join(".git").exists().then_some(repo))
+}
+
+fn find_commit(repo: &Path, id: &str) -> Result<HistoryCommit, HistoryError> {
+    let output = git(repo, &["log", "-1", "--name-only", LOG_FORMAT, id, "--"])
+        .map_err(|_| HistoryError::NotFound(id.to_string()))?;
+    parse_log(&output)
+        .into_iter()
+        .next()
+        .ok_or_else(|| HistoryError::NotFound(id.to_string()))
+}
+
+/// File content at `revision`, or `None` if the file didn't exist there.
+fn content_at(repo: &Path, revision: &str, mirror: &str) -> Result<Option<String>, HistoryError> {
+    match git(repo, &["show", &format!("{}:{}", revision, mirror)]) {
+        Ok(content) => Ok(Some(content)),
+        Err(HistoryError::Git(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Newest first. With `path`, only commits of that file.
+pub fn history_log_internal(
+    app: &impl AppPaths,
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryCommit>, HistoryError> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(repo) = open_repo(app)? else {
+        return Ok(Vec::new());
+    };
+    if git(&repo, &["rev-parse", "--verify", "-q", "HEAD"]).is_err() {
+        return Ok(Vec::new());
+    }
+
+    let limit = format!("-{}", limit.unwrap_or(100));
+    let mut args = vec!["log", "--name-only", limit.as_str(), LOG_FORMAT];
+    let mirror;
+    if let Some(path) = &path {
+        mirror = mirror_path(&FileTarget::parse(path)?);
+        args.extend(["--", mirror.as_str()]);
+    }
+    Ok(parse_log(&git(&repo, &args)?))
+}
+
+pub fn history_show_internal(
+    app: &impl AppPaths,
+    commit: &str,
+) -> Result<HistoryShow, HistoryError> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let repo = open_repo(app)?.ok_or_else(|| HistoryError::NotFound(commit.to_string()))?;
+    let commit = find_commit(&repo, commit)?;
+    let content = content_at(&repo, &commit.id, &commit.mirror_path)?.unwrap_or_default();
+    let patch = git(&repo, &["show", "--format=", &commit.id])?;
+    Ok(HistoryShow {
+        commit,
+        content,
+        patch,
+    })
+}
+
+/// Undo one commit's change to the live file, keeping later changes, the
+/// way `git revert` would. Edits made outside the app since the last
+/// recorded write are kept too; without `expected_token`, the write fails if
+/// the file changes again while the revert runs. Returns the new token.
+pub fn history_revert_internal(
+    app: &impl AppPaths,
+    commit: &str,
+    expected_token: Option<String>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<String, HistoryError> {
+    let (target, live, reverted) = {
+        let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let repo = open_repo(app)?.ok_or_else(|| HistoryError::NotFound(commit.to_string()))?;
+        let commit = find_commit(&repo, commit)?;
+        let mirror = &commit.mirror_path;
+
+        let before = content_at(&repo, &format!("{}^", commit.id), mirror)?.ok_or_else(|| {
+            HistoryError::Conflict(format!("{} (it first recorded the file)", commit.path))
+        })?;
+        let after = content_at(&repo, &commit.id, mirror)?.unwrap_or_default();
+        let target = FileTarget::parse(&commit.path)?;
+        let live = target.read()?;
+        let current = live.clone().unwrap_or_default();
+
+        // Three-way merge: apply the inverse of after -> before to current
+        let scratch = repo.join(".git").join("revert");
+        fs::create_dir_all(&scratch)?;
+        let files = [
+            ("current", &current),
+            ("after", &after),
+            ("before", &before),
+        ];
+        for (name, content) in files {
+            fs::write(scratch.join(name), content)?;
+        }
+        let merged = Command::new("git")
+            .current_dir(&scratch)
+            .args(["merge-file", "-p", "current", "after", "before"])
+            .output()?;
+        fs::remove_dir_all(&scratch).ok();
+        if !merged.status.success() {
+            return Err(HistoryError::Conflict(commit.path));
+        }
+
+        (
+            target,
+            live,
+            String::from_utf8_lossy(&merged.stdout).into_owned(),
+        )
+    };
+
+    let expected = expected_token.unwrap_or_else(|| fingerprint(live.as_deref()));
+    let mut transaction = Transaction::new(app, backup_settings)?;
+    transaction.stage(target.clone(), reverted.clone(), Some(expected));
+    transaction.commit()?;
+
+    let path = target.to_string();
+    record_history(
+        app,
+        &HistoryEntry {
+            path: &path,
+            previous: live.as_deref(),
+            content: &reverted,
+            origin: "history_revert",
+            json_path: None,
+            tool_id: None,
+        },
+    );
+    Ok(fingerprint(Some(&reverted)))
+}
+
+pub fn history_status_internal(app: &impl AppPaths) -> Result<HistoryStatus, HistoryError> {
+    Ok(HistoryStatus {
+        enabled: load_history_settings(app)?.enabled,
+        git_available: git_available(),
+        repo_path: repo_dir(app)?.to_string_lossy().to_string(),
+    })
+}
+
+pub fn set_history_enabled_internal(
+    app: &impl AppPaths,
+    enabled: bool,
+) -> Result<HistoryStatus, HistoryError> {
+    if enabled {
+        let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        ensure_repo(app)?;
+    }
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(
+        &path,
+        serde_json::to_string_pretty(&HistorySettings { enabled })?,
+    )?;
+    history_status_internal(app)
+}
+
+#[tauri::command]
+pub fn get_history_status(app: AppHandle) -> Result<HistoryStatus, HistoryError> {
+    history_status_internal(&app)
+}
+
+#[tauri::command]
+pub fn set_history_enabled(app: AppHandle, enabled: bool) -> Result<HistoryStatus, HistoryError> {
+    set_history_enabled_internal(&app, enabled)
+}
+
+#[tauri::command]
+pub fn history_log(
+    app: AppHandle,
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryCommit>, HistoryError> {
+    history_log_internal(&app, path, limit)
+}
+
+#[tauri::command]
+pub fn history_show(app: AppHandle, commit: String) -> Result<HistoryShow, HistoryError> {
+    history_show_internal(&app, &commit)
+}
+
+#[tauri::command]
+pub fn history_revert(
+    app: AppHandle,
+    commit: String,
+    expected_token: Option<String>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<String, HistoryError> {
+    history_revert_internal(&app, &commit, expected_token, backup_settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use crate::commands::write_file_internal;
+
+    #[test]
+    fn test_writes_are_committed_and_revertible() {
+        let dir = std::env::temp_dir().join(format!("history-test-{}", uuid::Uuid::new_v4()));
+        let paths = StandalonePaths {
+            data_dir: Some(dir.join("data")),
+            config_dir: None,
+        };
+        let file = dir.join("settings.json");
+        let path = file.to_string_lossy().to_string();
+        let write = |content: &str| {
+            write_file_internal(
+                &paths,
+                path.clone(),
+                content.to_string(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&file, "a\nb\nc\n").unwrap();
+        set_history_enabled_internal(&paths, true).unwrap();
+
+        write("A\nb\nc\n");
+        write("A\nb\nC\n");
+        // History doesn't replace the backup, since a failed commit is only a warning
+        assert_eq!(
+            fs::read_to_string(dir.join("settings.bak")).unwrap(),
+            "A\nb\nc\n"
+        );
+
+        let log = history_log_internal(&paths, Some(path.clone()), None).unwrap();
+        let origins: Vec<&str> = log.iter().map(|c| c.origin.as_str()).collect();
+        assert_eq!(origins, vec!["write_file", "write_file", "external"]);
+        assert_eq!(log[0].path, path);
+
+        let shown = history_show_internal(&paths, &log[1].id).unwrap();
+        assert_eq!(shown.content, "A\nb\nc\n");
+        assert!(shown.patch.contains("-a\n+A"));
+
+        // Undo the first edit while keeping the second and an outside edit
+        fs::write(&file, "A\nb\nC\nd\n").unwrap();
+        assert!(matches!(
+            history_revert_internal(
+                &paths,
+                &log[1].id,
+                Some(fingerprint(Some("A\nb\nC\n"))),
+                None
+            ),
+            Err(HistoryError::Transaction(TransactionError::Changed(_)))
+        ));
+        let token = history_revert_internal(&paths, &log[1].id, None, None).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\nb\nC\nd\n");
+        assert_eq!(token, fingerprint(Some("a\nb\nC\nd\n")));
+        assert_eq!(
+            history_log_internal(&paths, None, None).unwrap()[0].origin,
+            "history_revert"
+        );
+
+        // The baseline commit has nothing to revert to
+        assert!(matches!(
+            history_revert_internal(&paths, &log[2].id, None, None),
+            Err(HistoryError::Conflict(_))
+        ));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
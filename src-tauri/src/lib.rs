@@ -1,13 +1,25 @@
+mod app_paths;
+mod cli;
 mod commands;
 mod config;
+mod diff;
+mod formats;
+mod history;
 mod mcp;
 mod path_safety;
+mod profile;
+mod secrets;
 mod ssh;
+mod transaction;
+mod validation;
 mod versions;
+mod watcher;
+mod workspace;
 
 use commands::{
     check_multiple_paths, check_path_safety, delete_file, file_exists, get_current_os, get_tools, list_backups, read_backup,
-    read_file, read_json_path, read_json_prefix, resolve_path, restore_backup, write_file, write_json_path, write_json_prefix,
+    read_file, read_json_path, read_json_prefix, resolve_path, restore_backup, write_file, write_files, write_json_path,
+    write_json_prefix,
     save_sidebar_state, load_sidebar_state,
 };
 use mcp::{
@@ -15,16 +27,64 @@ use mcp::{
     save_app_mcp_servers, add_mcp_server, update_mcp_server, remove_mcp_server,
     read_claude_mcp_servers, get_tool_mcp_servers, get_mcp_tool_statuses, set_tool_mcp_enabled,
     preview_mcp_sync, preview_mcp_sync_all, sync_mcp_to_tool, sync_mcp_to_all,
-    preview_mcp_config_content, import_mcp_config_file,
+    preview_mcp_config_content, import_mcp_config_file, set_mcp_auto_sync, get_mcp_sync_log, probe_mcp_server,
+    list_claude_projects, get_claude_project_servers, save_claude_project_servers, sync_claude_project,
+};
+use secrets::{
+    apply_secret_remediation, delete_secret, extract_mcp_secrets, list_secret_usages, list_secrets, lock_secret_vault,
+    scan_for_secrets, set_secret, unlock_secret_vault,
 };
 use ssh::{
     backup_remote_config, check_remote_connection, check_remote_file_exists, parse_ssh_path,
     read_remote_config, test_ssh_host, write_remote_config,
 };
+use config::{effective_config, get_tool_registry};
+use diff::diff_configs;
+use profile::{export_profile, import_profile};
+use history::{get_history_status, history_log, history_revert, history_show, set_history_enabled};
+use validation::validate_config;
 use versions::{
     save_version, list_versions, load_version, delete_version, update_version_metadata,
-    duplicate_version, get_default_version, update_version_content,
+    duplicate_version, get_default_version, update_version_content, get_version_policy, set_version_policy,
+    preview_version_pruning, prune_versions,
 };
+use tauri::Manager;
+use watcher::{refresh_config_watcher, unwatch_file, watch_file, ConfigWatcher};
+use workspace::{add_workspace, get_workspace_tools, list_workspaces, remove_workspace};
+
+/// Headless entry point. Returns the exit code when the process arguments
+/// name a CLI subcommand, or `None` when the GUI should start instead.
+pub fn run_cli() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    if cli::is_cli_invocation(&args) {
+        attach_parent_console();
+        Some(cli::run(args))
+    } else {
+        None
+    }
+}
+
+/// Release builds on Windows use the GUI subsystem and start without a
+/// console, so CLI output would go nowhere. Attach to the console of the
+/// shell that launched us instead; redirected handles are kept as they are.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails when there is no parent console (e.g. started from Explorer) or
+    // one is already attached (debug builds); both are fine to ignore.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -32,11 +92,28 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .setup(|app| {
+            // Put back files from a multi-file write that a crash interrupted
+            if let Err(e) = transaction::recover_transactions(app.handle()) {
+                eprintln!("Failed to recover interrupted writes: {}", e);
+            }
+            match ConfigWatcher::start(app.handle().clone()) {
+                Ok(watcher) => {
+                    app.manage(watcher);
+                }
+                Err(e) => eprintln!("Failed to start config watcher: {}", e),
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_tools,
+            get_tool_registry,
+            effective_config,
+            diff_configs,
             get_current_os,
             read_file,
             write_file,
+            write_files,
             read_json_path,
             read_json_prefix,
             write_json_path,
@@ -55,8 +132,21 @@ pub fn run() {
             delete_version,
             update_version_metadata,
             update_version_content,
+            get_version_policy,
+            set_version_policy,
+            preview_version_pruning,
+            prune_versions,
             duplicate_version,
             get_default_version,
+            // Profile commands
+            export_profile,
+            import_profile,
+            // Git history commands
+            get_history_status,
+            set_history_enabled,
+            history_log,
+            history_show,
+            history_revert,
             // MCP commands
             get_mcp_config,
             save_mcp_config,
@@ -69,6 +159,8 @@ pub fn run() {
             read_claude_mcp_servers,
             get_tool_mcp_servers,
             get_mcp_tool_statuses,
+            set_mcp_auto_sync,
+            get_mcp_sync_log,
             set_tool_mcp_enabled,
             preview_mcp_sync,
             preview_mcp_sync_all,
@@ -76,6 +168,29 @@ pub fn run() {
             sync_mcp_to_all,
             preview_mcp_config_content,
             import_mcp_config_file,
+            probe_mcp_server,
+            list_claude_projects,
+            get_claude_project_servers,
+            save_claude_project_servers,
+            sync_claude_project,
+            // Secret vault commands
+            unlock_secret_vault,
+            lock_secret_vault,
+            list_secrets,
+            set_secret,
+            delete_secret,
+            list_secret_usages,
+            extract_mcp_secrets,
+            scan_for_secrets,
+            apply_secret_remediation,
+            // Workspace commands
+            list_workspaces,
+            add_workspace,
+            remove_workspace,
+            get_workspace_tools,
+            watch_file,
+            unwatch_file,
+            refresh_config_watcher,
             // Sidebar state commands
             save_sidebar_state,
             load_sidebar_state,
@@ -89,6 +204,8 @@ pub fn run() {
             check_remote_file_exists,
             backup_remote_config,
             parse_ssh_path,
+            // Validation commands
+            validate_config,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
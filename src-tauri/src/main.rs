@@ -1,5 +1,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = cli_config_editor_lib::run_cli() {
+        std::process::exit(code);
+    }
     cli_config_editor_lib::run();
 }
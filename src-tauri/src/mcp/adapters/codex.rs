@@ -0,0 +1,272 @@
+use super::{
+    server_map, string_list, string_list_value, string_map, string_map_value, write_entries,
+    McpFormatAdapter,
+};
+use crate::formats::ConfigDocument;
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Codex CLI: `[mcp_servers.<name>]` tables in ~/.codex/config.toml with
+/// `command`, `args`, `env` or `url`. Servers are switched off with
+/// `enabled = false` and remote headers live in `http_headers`.
+pub struct CodexAdapter;
+
+const KEY: &str = "mcp_servers";
+const KNOWN_FIELDS: [&str; 6] = ["command", "args", "env", "url", "enabled", "http_headers"];
+
+impl McpFormatAdapter for CodexAdapter {
+    fn name(&self) -> &'static str {
+        "codex"
+    }
+
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        root.get(KEY)
+            .filter(|v| v.is_object())
+            .map(|_| vec![KEY.to_string()])
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        let mut result = Vec::new();
+
+        for (name, config) in server_map(servers, self)? {
+            let Value::Object(server_config) = config else {
+                continue;
+            };
+
+            let mut extra: HashMap<String, Value> = server_config
+                .iter()
+                .filter(|(k, _)| !KNOWN_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            if let Some(headers) = server_config.get("http_headers") {
+                extra.insert("headers".to_string(), headers.clone());
+            }
+
+            let disabled = match server_config.get("enabled") {
+                Some(Value::Bool(enabled)) => Some(!enabled),
+                _ => None,
+            };
+
+            result.push(McpServer {
+                name: name.clone(),
+                command: server_config
+                    .get("command")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                args: string_list(server_config.get("args")),
+                env: string_map(server_config.get("env")),
+                disabled,
+                url: server_config
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                target: None,
+                extra: if extra.is_empty() { None } else { Some(extra) },
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        let mut map = Map::new();
+
+        for server in servers {
+            let mut server_obj = Map::new();
+
+            if !server.command.is_empty() {
+                server_obj.insert("command".to_string(), Value::String(server.command.clone()));
+            }
+
+            if let Some(args) = &server.args {
+                server_obj.insert("args".to_string(), string_list_value(args.iter().cloned()));
+            }
+
+            if let Some(env) = &server.env {
+                server_obj.insert("env".to_string(), string_map_value(env));
+            }
+
+            if let Some(url) = &server.url {
+                server_obj.insert("url".to_string(), Value::String(url.clone()));
+            }
+
+            if server.disabled == Some(true) {
+                server_obj.insert("enabled".to_string(), Value::Bool(false));
+            }
+
+            // Codex infers the transport, and rejects the standard `type` key
+            for (k, v) in server.extra.iter().flatten() {
+                match k.as_str() {
+                    "type" => {}
+                    "headers" => {
+                        server_obj.insert("http_headers".to_string(), v.clone());
+                    }
+                    _ => {
+                        server_obj.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+
+            map.insert(server.name.clone(), Value::Object(server_obj));
+        }
+
+        Value::Object(map)
+    }
+
+    /// Each server is its own table, so only the tables that changed are
+    /// rewritten and the rest of config.toml keeps its comments
+    fn write(
+        &self,
+        document: &mut ConfigDocument,
+        keys: &[&str],
+        servers: &[McpServer],
+    ) -> Result<Option<()>, McpError> {
+        write_entries(document, keys, self.render(servers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigFormat;
+    use serde_json::json;
+
+    const CONFIG: &str = r#"# Codex settings
+model = "o4-mini"
+
+# Docs server, keep pinned
+[mcp_servers.docs]
+command = "docs-mcp" # local build
+args = ["--stdio"]
+startup_timeout_sec = 20
+
+[mcp_servers.old]
+command = "old-mcp"
+
+[mcp_servers.remote]
+url = "https://mcp.example.com/mcp"
+enabled = false
+http_headers = { "X-Team" = "core" }
+
+# Sandbox comes last
+[sandbox_workspace_write]
+network_access = true
+"#;
+
+    fn write(content: &str, servers: &[McpServer]) -> String {
+        let mut document = ConfigDocument::parse(content, &ConfigFormat::Toml).unwrap();
+        CodexAdapter
+            .write(&mut document, &[KEY], servers)
+            .unwrap()
+            .unwrap();
+        document.render().unwrap()
+    }
+
+    #[test]
+    fn test_parse_codex_tables() {
+        let root = ConfigDocument::parse(CONFIG, &ConfigFormat::Toml)
+            .unwrap()
+            .to_value();
+        let keys = CodexAdapter.detect(&root).unwrap();
+        let servers = CodexAdapter.parse(&root[&keys[0]]).unwrap();
+        let find = |name: &str| servers.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("docs").command, "docs-mcp");
+        assert_eq!(find("docs").args, Some(vec!["--stdio".to_string()]));
+        assert_eq!(
+            find("docs").extra.as_ref().unwrap()["startup_timeout_sec"],
+            20
+        );
+        assert_eq!(
+            find("remote").url.as_deref(),
+            Some("https://mcp.example.com/mcp")
+        );
+        assert_eq!(find("remote").disabled, Some(true));
+        assert_eq!(
+            find("remote").extra.as_ref().unwrap()["headers"],
+            json!({ "X-Team": "core" })
+        );
+
+        assert_eq!(CodexAdapter.render(&servers), root[KEY]);
+    }
+
+    #[test]
+    fn test_write_only_touches_changed_tables() {
+        let root = ConfigDocument::parse(CONFIG, &ConfigFormat::Toml)
+            .unwrap()
+            .to_value();
+        let mut servers = CodexAdapter.parse(&root[KEY]).unwrap();
+        servers.retain(|s| s.name != "old");
+        let remote = servers.iter_mut().find(|s| s.name == "remote").unwrap();
+        remote.disabled = None;
+        servers.push(McpServer {
+            name: "github".to_string(),
+            command: "npx".to_string(),
+            args: Some(vec![
+                "-y".to_string(),
+                "@modelcontextprotocol/server-github".to_string(),
+            ]),
+            env: Some(HashMap::from([(
+                "GITHUB_TOKEN".to_string(),
+                "t".to_string(),
+            )])),
+            disabled: None,
+            url: None,
+            target: None,
+            // Transport hints from other tools are not Codex keys
+            extra: Some(HashMap::from([("type".to_string(), json!("stdio"))])),
+        });
+
+        let written = write(CONFIG, &servers);
+        for kept in [
+            "# Codex settings",
+            "# Docs server, keep pinned",
+            "command = \"docs-mcp\" # local build",
+            "# Sandbox comes last",
+            "network_access = true",
+        ] {
+            assert!(
+                written.contains(kept),
+                "{} missing from:\n{}",
+                kept,
+                written
+            );
+        }
+        assert!(!written.contains("old-mcp"));
+        assert!(!written.contains("enabled"));
+        assert!(!written.contains("type"));
+
+        let root = ConfigDocument::parse(&written, &ConfigFormat::Toml)
+            .unwrap()
+            .to_value();
+        assert_eq!(root["model"], "o4-mini");
+        assert_eq!(root[KEY]["github"]["env"]["GITHUB_TOKEN"], "t");
+        assert_eq!(CodexAdapter.parse(&root[KEY]).unwrap().len(), 3);
+
+        // Writing the same servers again is a no-op
+        assert_eq!(write(&written, &servers), written);
+    }
+
+    #[test]
+    fn test_write_creates_tables_in_new_file() {
+        let server = McpServer {
+            name: "files".to_string(),
+            command: "files-mcp".to_string(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        };
+        let written = write("model = \"o3\"\n", &[server]);
+        assert!(written.starts_with("model = \"o3\"\n"));
+        assert!(
+            written.contains("[mcp_servers.files]\ncommand = \"files-mcp\""),
+            "{}",
+            written
+        );
+    }
+}
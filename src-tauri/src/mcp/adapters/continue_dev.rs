@@ -0,0 +1,186 @@
+use super::standard::parse_standard;
+use super::{string_list, string_list_value, string_map, string_map_value, McpFormatAdapter};
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Continue: `mcpServers` in config.yaml is a list of `{ name, command, args,
+/// env }` blocks; remote blocks carry `type: sse | streamable-http` and `url`.
+/// JSON block files use the standard name-keyed map, which is read as well.
+pub struct ContinueAdapter;
+
+const KNOWN_FIELDS: [&str; 6] = ["name", "command", "args", "env", "url", "type"];
+
+/// Continue's remote transport names, mapped to the standard layout's
+fn standard_type(continue_type: &str) -> &str {
+    match continue_type {
+        "streamable-http" => "http",
+        other => other,
+    }
+}
+
+fn continue_type(standard_type: &str) -> &str {
+    match standard_type {
+        "http" => "streamable-http",
+        other => other,
+    }
+}
+
+impl McpFormatAdapter for ContinueAdapter {
+    fn name(&self) -> &'static str {
+        "continue"
+    }
+
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        root.get("mcpServers")
+            .filter(|v| v.is_array())
+            .map(|_| vec!["mcpServers".to_string()])
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        let blocks = match servers {
+            Value::Array(blocks) => blocks,
+            Value::Object(_) => return parse_standard(self, servers),
+            _ => {
+                return Err(McpError::InvalidFormat(
+                    "continue servers are not a list".to_string(),
+                ))
+            }
+        };
+
+        let mut result = Vec::new();
+        for block in blocks {
+            let Value::Object(block) = block else {
+                continue;
+            };
+            let Some(name) = block.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+
+            let mut extra: HashMap<String, Value> = block
+                .iter()
+                .filter(|(k, _)| !KNOWN_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            if let Some(kind) = block.get("type").and_then(|v| v.as_str()) {
+                extra.insert(
+                    "type".to_string(),
+                    Value::String(standard_type(kind).to_string()),
+                );
+            }
+
+            result.push(McpServer {
+                name: name.to_string(),
+                command: block
+                    .get("command")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                args: string_list(block.get("args")),
+                env: string_map(block.get("env")),
+                disabled: None,
+                url: block
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                target: None,
+                extra: if extra.is_empty() { None } else { Some(extra) },
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        let blocks = servers
+            .iter()
+            .map(|server| {
+                let mut block = Map::new();
+                block.insert("name".to_string(), Value::String(server.name.clone()));
+
+                if !server.command.is_empty() {
+                    block.insert("command".to_string(), Value::String(server.command.clone()));
+                }
+
+                if let Some(args) = &server.args {
+                    block.insert("args".to_string(), string_list_value(args.iter().cloned()));
+                }
+
+                if let Some(env) = &server.env {
+                    block.insert("env".to_string(), string_map_value(env));
+                }
+
+                for (k, v) in server.extra.iter().flatten() {
+                    block.insert(k.clone(), v.clone());
+                }
+
+                if let Some(url) = &server.url {
+                    block.insert("url".to_string(), Value::String(url.clone()));
+                    let kind = server
+                        .extra
+                        .as_ref()
+                        .and_then(|extra| extra.get("type"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("http");
+                    block.insert(
+                        "type".to_string(),
+                        Value::String(continue_type(kind).to_string()),
+                    );
+                }
+
+                Value::Object(block)
+            })
+            .collect();
+
+        Value::Array(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigFormat;
+    use crate::formats::ConfigDocument;
+
+    #[test]
+    fn test_continue_yaml_round_trip() {
+        let yaml = r#"
+name: My Assistant
+version: 1.0.0
+schema: v1
+mcpServers:
+  - name: sqlite
+    command: npx
+    args: ["-y", "mcp-sqlite", "/tmp/db.sqlite"]
+    env:
+      DEBUG: "1"
+  - name: remote
+    type: streamable-http
+    url: https://mcp.example.com/mcp
+"#;
+        let root = ConfigDocument::parse(yaml, &ConfigFormat::Yaml)
+            .unwrap()
+            .to_value();
+        let keys = ContinueAdapter.detect(&root).unwrap();
+        let servers = ContinueAdapter.parse(&root[&keys[0]]).unwrap();
+
+        assert_eq!(servers[0].name, "sqlite");
+        assert_eq!(servers[0].args.as_ref().unwrap().len(), 3);
+        assert_eq!(
+            servers[1].url.as_deref(),
+            Some("https://mcp.example.com/mcp")
+        );
+        // Continue's transport names are mapped to the standard ones
+        assert_eq!(servers[1].extra.as_ref().unwrap()["type"], "http");
+
+        assert_eq!(ContinueAdapter.render(&servers), root["mcpServers"]);
+    }
+
+    #[test]
+    fn test_continue_json_block() {
+        let json = serde_json::json!({ "files": { "command": "npx", "args": ["files"] } });
+        let servers = ContinueAdapter.parse(&json).unwrap();
+        assert_eq!(servers[0].name, "files");
+        assert_eq!(servers[0].command, "npx");
+    }
+}
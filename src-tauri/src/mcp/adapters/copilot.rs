@@ -0,0 +1,139 @@
+use super::{
+    server_map, string_list, string_list_value, string_map, string_map_value, McpFormatAdapter,
+};
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::Value;
+
+/// GitHub Copilot CLI: `servers: { name: { command, args, env } }`.
+pub struct CopilotAdapter;
+
+impl McpFormatAdapter for CopilotAdapter {
+    fn name(&self) -> &'static str {
+        "copilot"
+    }
+
+    /// A top-level `servers` map, but not one belonging to an `mcp` section
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        if root.get("mcp").is_some() {
+            return None;
+        }
+        root.get("servers")
+            .filter(|v| v.is_object())
+            .map(|_| vec!["servers".to_string()])
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        let mut result = Vec::new();
+
+        for (name, config) in server_map(servers, self)? {
+            if let Value::Object(server_config) = config {
+                result.push(McpServer {
+                    name: name.clone(),
+                    command: server_config
+                        .get("command")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    args: string_list(server_config.get("args")),
+                    env: string_map(server_config.get("env")),
+                    disabled: None,
+                    url: server_config
+                        .get("url")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    target: None,
+                    extra: None,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        let mut map = serde_json::Map::new();
+
+        for server in servers {
+            let mut server_obj = serde_json::Map::new();
+
+            if server.url.is_none() || !server.command.is_empty() {
+                server_obj.insert("command".to_string(), Value::String(server.command.clone()));
+            }
+
+            if let Some(args) = &server.args {
+                server_obj.insert("args".to_string(), string_list_value(args.iter().cloned()));
+            }
+
+            if let Some(env) = &server.env {
+                server_obj.insert("env".to_string(), string_map_value(env));
+            }
+
+            if let Some(url) = &server.url {
+                server_obj.insert("url".to_string(), Value::String(url.clone()));
+            }
+
+            map.insert(server.name.clone(), Value::Object(server_obj));
+        }
+
+        Value::Object(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_copilot_format() {
+        let json = serde_json::json!({
+            "test-server": {
+                "command": "test-cmd",
+                "args": ["arg1"]
+            }
+        });
+
+        let servers = CopilotAdapter.parse(&json).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "test-server");
+        assert_eq!(servers[0].command, "test-cmd");
+    }
+
+    #[test]
+    fn test_servers_to_copilot_format() {
+        let servers = vec![
+            McpServer {
+                name: "test".to_string(),
+                command: "cmd".to_string(),
+                args: None,
+                env: None,
+                disabled: None,
+                url: None,
+                target: None,
+                extra: None,
+            },
+            McpServer {
+                name: "remote".to_string(),
+                command: String::new(),
+                args: None,
+                env: None,
+                disabled: None,
+                url: Some("http://localhost:3000/sse".to_string()),
+                target: None,
+                extra: None,
+            },
+        ];
+
+        let result = CopilotAdapter.render(&servers);
+        assert!(result.is_object());
+        assert!(result.get("test").is_some());
+        assert_eq!(
+            result["remote"],
+            serde_json::json!({ "url": "http://localhost:3000/sse" })
+        );
+        let parsed = CopilotAdapter.parse(&result).unwrap();
+        assert_eq!(
+            parsed.iter().find(|s| s.name == "remote").unwrap().url,
+            servers[1].url
+        );
+    }
+}
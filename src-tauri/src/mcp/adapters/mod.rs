@@ -0,0 +1,318 @@
+mod codex;
+mod continue_dev;
+mod copilot;
+mod opencode;
+mod standard;
+mod vscode;
+mod zed;
+
+use crate::formats::ConfigDocument;
+use crate::mcp::types::{McpError, McpServer, McpToolFormat};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+pub use codex::CodexAdapter;
+pub use continue_dev::ContinueAdapter;
+pub use copilot::CopilotAdapter;
+pub use opencode::OpencodeAdapter;
+pub use standard::{AmpAdapter, StandardAdapter};
+pub use vscode::VscodeAdapter;
+pub use zed::ZedAdapter;
+
+/// How one family of tools lays out MCP servers in its config file.
+///
+/// A tool names its adapter in `McpToolInfo.format`. Reading, writing,
+/// previewing and import detection all go through the adapter, so a new
+/// layout only needs a new implementation added to `ADAPTERS`.
+pub trait McpFormatAdapter: Sync {
+    /// Registry name, as used in `McpToolInfo.format` and user tool definitions
+    fn name(&self) -> &'static str;
+
+    /// Keys of the server map in a file of unknown origin, if `root` looks
+    /// like this format. Used to detect the format of an imported file.
+    fn detect(&self, root: &Value) -> Option<Vec<String>>;
+
+    /// Keys of the server map in a tool's config, given its `json_path`.
+    /// Dotted paths are nested unless the file already uses them as a key.
+    fn locate(&self, root: &Value, json_path: &str) -> Vec<String> {
+        servers_key_path(root, json_path)
+    }
+
+    /// Servers from the value found at the located keys
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError>;
+
+    /// The value to store at the located keys
+    fn render(&self, servers: &[McpServer]) -> Value;
+
+    /// Store the servers at `keys` in a tool's config. Returns `None` when the
+    /// keys can't be created. The default replaces the whole server map.
+    fn write(
+        &self,
+        document: &mut ConfigDocument,
+        keys: &[&str],
+        servers: &[McpServer],
+    ) -> Result<Option<()>, McpError> {
+        Ok(document.set_keys(keys, self.render(servers))?)
+    }
+}
+
+/// Registered adapters, in the order import detection tries them: the more
+/// specific layouts come before the generic `mcpServers` map.
+static ADAPTERS: &[&dyn McpFormatAdapter] = &[
+    &AmpAdapter,
+    &VscodeAdapter,
+    &OpencodeAdapter,
+    &ZedAdapter,
+    &ContinueAdapter,
+    &CodexAdapter,
+    &CopilotAdapter,
+    &StandardAdapter,
+];
+
+pub fn format_adapter(name: &str) -> Option<&'static dyn McpFormatAdapter> {
+    ADAPTERS
+        .iter()
+        .copied()
+        .find(|adapter| adapter.name() == name)
+}
+
+impl McpToolFormat {
+    pub fn adapter(&self) -> Result<&'static dyn McpFormatAdapter, McpError> {
+        format_adapter(self.as_str()).ok_or_else(|| {
+            McpError::InvalidFormat(format!("Unknown MCP format '{}'", self.as_str()))
+        })
+    }
+}
+
+/// The value under an explicit sequence of object keys
+pub fn value_at<'a>(root: &'a Value, keys: &[String]) -> Option<&'a Value> {
+    keys.iter().try_fold(root, |current, key| current.get(key))
+}
+
+/// The adapter that recognized a file, with the servers it read
+pub type DetectedServers = (&'static dyn McpFormatAdapter, Vec<McpServer>);
+
+/// Find the first adapter that recognizes `root` and holds at least one server.
+pub fn detect_servers(root: &Value) -> Result<Option<DetectedServers>, McpError> {
+    for adapter in ADAPTERS {
+        let Some(keys) = adapter.detect(root) else {
+            continue;
+        };
+        let Some(value) = value_at(root, &keys) else {
+            continue;
+        };
+        let servers = adapter.parse(value)?;
+        if !servers.is_empty() {
+            return Ok(Some((*adapter, servers)));
+        }
+    }
+    Ok(None)
+}
+
+/// Decide which keys hold the server map. Amp stores "amp.mcpServers" as a
+/// literal dotted key, while other dotted paths are nested objects.
+fn servers_key_path(root: &Value, json_path: &str) -> Vec<String> {
+    // First check if the path exists as a literal key (e.g., "amp.mcpServers")
+    if root.get(json_path).is_some() || !json_path.contains('.') {
+        return vec![json_path.to_string()];
+    }
+
+    // Check if it should be a literal key by looking at existing keys
+    let prefix = json_path.split('.').next().unwrap_or("");
+    let has_dotted_keys = root
+        .as_object()
+        .map(|map| map.keys().any(|k| k.contains('.') && k.starts_with(prefix)))
+        .unwrap_or(false);
+
+    if has_dotted_keys {
+        vec![json_path.to_string()]
+    } else {
+        json_path.split('.').map(String::from).collect()
+    }
+}
+
+/// Update a name-keyed server map one entry at a time: unchanged entries are
+/// left as they are, stale ones removed and the rest replaced or appended.
+fn write_entries(
+    document: &mut ConfigDocument,
+    keys: &[&str],
+    rendered: Value,
+) -> Result<Option<()>, McpError> {
+    let root = document.to_value();
+    let current = keys
+        .iter()
+        .try_fold(&root, |value, key| value.get(key))
+        .and_then(Value::as_object);
+    let (Some(current), Value::Object(entries)) = (current, &rendered) else {
+        return Ok(document.set_keys(keys, rendered)?);
+    };
+
+    for name in current.keys().filter(|name| !entries.contains_key(*name)) {
+        document.remove_keys(&[keys, &[name.as_str()]].concat());
+    }
+    for (name, value) in entries {
+        if current.get(name) == Some(value) {
+            continue;
+        }
+        if document
+            .set_keys(&[keys, &[name.as_str()]].concat(), value.clone())?
+            .is_none()
+        {
+            return Ok(None);
+        }
+    }
+    Ok(Some(()))
+}
+
+/// The server map at `servers`, erroring if it holds something else
+fn server_map<'a>(
+    servers: &'a Value,
+    adapter: &dyn McpFormatAdapter,
+) -> Result<&'a Map<String, Value>, McpError> {
+    servers.as_object().ok_or_else(|| {
+        McpError::InvalidFormat(format!("{} servers are not an object", adapter.name()))
+    })
+}
+
+/// Env values may be written as numbers or booleans; keep them as strings
+fn value_to_string(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+fn string_list(value: Option<&Value>) -> Option<Vec<String>> {
+    value.and_then(Value::as_array).map(|items| {
+        items
+            .iter()
+            .filter_map(|item| item.as_str().map(String::from))
+            .collect()
+    })
+}
+
+fn string_map(value: Option<&Value>) -> Option<HashMap<String, String>> {
+    value.and_then(Value::as_object).map(|map| {
+        map.iter()
+            .filter_map(|(k, v)| value_to_string(v).map(|s| (k.clone(), s)))
+            .collect()
+    })
+}
+
+fn string_map_value(map: &HashMap<String, String>) -> Value {
+    Value::Object(
+        map.iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect(),
+    )
+}
+
+fn string_list_value(items: impl IntoIterator<Item = String>) -> Value {
+    Value::Array(items.into_iter().map(Value::String).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_servers_prefers_specific_formats() {
+        let cases = [
+            (
+                json!({ "amp.mcpServers": { "a": { "command": "x" } } }),
+                "amp",
+            ),
+            (json!({ "mcp": { "a": { "command": ["x"] } } }), "opencode"),
+            (json!({ "servers": { "a": { "command": "x" } } }), "copilot"),
+            (
+                json!({ "mcp": { "servers": { "a": { "command": "x" } } } }),
+                "vscode",
+            ),
+            (
+                json!({ "context_servers": { "a": { "command": "x" } } }),
+                "zed",
+            ),
+            (
+                json!({ "mcpServers": [{ "name": "a", "command": "x" }] }),
+                "continue",
+            ),
+            (
+                json!({ "mcp_servers": { "a": { "command": "x" } } }),
+                "codex",
+            ),
+            (
+                json!({ "mcpServers": { "a": { "command": "x" } } }),
+                "standard",
+            ),
+            // An empty `mcp.servers` falls through to the next format
+            (
+                json!({ "mcp": { "servers": {} }, "mcpServers": { "a": { "command": "x" } } }),
+                "standard",
+            ),
+        ];
+        for (root, expected) in cases {
+            let (adapter, servers) = detect_servers(&root).unwrap().unwrap();
+            assert_eq!(adapter.name(), expected, "{}", root);
+            assert_eq!(servers[0].name, "a");
+        }
+
+        assert!(detect_servers(&json!({ "mcpServers": {} }))
+            .unwrap()
+            .is_none());
+        assert!(detect_servers(&json!({ "other": 1 })).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_every_format_round_trips() {
+        let server = McpServer {
+            name: "everything".to_string(),
+            command: "npx".to_string(),
+            args: Some(vec!["-y".to_string(), "server".to_string()]),
+            env: Some(HashMap::from([("TOKEN".to_string(), "abc".to_string())])),
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        };
+        for adapter in ADAPTERS {
+            let parsed = adapter
+                .parse(&adapter.render(std::slice::from_ref(&server)))
+                .unwrap();
+            assert_eq!(parsed.len(), 1, "{}", adapter.name());
+            assert_eq!(parsed[0].command, server.command, "{}", adapter.name());
+            assert_eq!(parsed[0].args, server.args, "{}", adapter.name());
+            assert_eq!(parsed[0].env, server.env, "{}", adapter.name());
+        }
+
+        let format: McpToolFormat = serde_json::from_value(json!("opencode")).unwrap();
+        assert_eq!(format.adapter().unwrap().name(), "opencode");
+        assert!(McpToolFormat::new("nope").adapter().is_err());
+    }
+
+    #[test]
+    fn test_servers_key_path() {
+        let amp = json!({ "amp.permissions": [] });
+        assert_eq!(
+            servers_key_path(&amp, "amp.mcpServers"),
+            vec!["amp.mcpServers"]
+        );
+
+        let nested = json!({ "amp": {} });
+        assert_eq!(
+            servers_key_path(&nested, "amp.mcpServers"),
+            vec!["amp", "mcpServers"]
+        );
+
+        let plain = json!({});
+        assert_eq!(servers_key_path(&plain, "mcpServers"), vec!["mcpServers"]);
+
+        // Amp's own adapter always uses the literal dotted key, even in a new file
+        assert_eq!(
+            AmpAdapter.locate(&plain, "amp.mcpServers"),
+            vec!["amp.mcpServers"]
+        );
+    }
+}
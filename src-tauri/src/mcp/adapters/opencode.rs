@@ -0,0 +1,253 @@
+use super::{
+    server_map, string_list, string_list_value, string_map, string_map_value, McpFormatAdapter,
+};
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::Value;
+
+/// OpenCode: `mcp: { name: { type, command: [cmd, ...args], environment, url, enabled } }`.
+pub struct OpencodeAdapter;
+
+impl McpFormatAdapter for OpencodeAdapter {
+    fn name(&self) -> &'static str {
+        "opencode"
+    }
+
+    /// Servers directly under `mcp.<server-name>`
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        let has_server_configs = root.get("mcp")?.as_object()?.values().any(|v| {
+            v.as_object()
+                .is_some_and(|obj| obj.contains_key("command") || obj.contains_key("url"))
+        });
+        has_server_configs.then(|| vec!["mcp".to_string()])
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        let mut result = Vec::new();
+
+        for (name, config) in server_map(servers, self)? {
+            if let Value::Object(server_config) = config {
+                let mut command_parts = string_list(server_config.get("command"))
+                    .unwrap_or_default()
+                    .into_iter();
+                let command = command_parts.next().unwrap_or_default();
+                let args: Vec<String> = command_parts.collect();
+
+                result.push(McpServer {
+                    name: name.clone(),
+                    command,
+                    args: if args.is_empty() { None } else { Some(args) },
+                    env: string_map(server_config.get("environment")),
+                    disabled: server_config
+                        .get("enabled")
+                        .and_then(|v| v.as_bool())
+                        .map(|enabled| !enabled),
+                    url: server_config
+                        .get("url")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    target: None,
+                    extra: None,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        let mut mcp_map = serde_json::Map::new();
+
+        for server in servers {
+            let mut server_obj = serde_json::Map::new();
+
+            let command = std::iter::once(server.command.clone())
+                .chain(server.args.iter().flatten().cloned());
+            server_obj.insert("command".to_string(), string_list_value(command));
+
+            if let Some(env) = &server.env {
+                server_obj.insert("environment".to_string(), string_map_value(env));
+            }
+
+            if let Some(url) = &server.url {
+                server_obj.insert("url".to_string(), Value::String(url.clone()));
+                server_obj.insert("type".to_string(), Value::String("remote".to_string()));
+            } else {
+                server_obj.insert("type".to_string(), Value::String("local".to_string()));
+            }
+
+            let enabled = !server.disabled.unwrap_or(false);
+            server_obj.insert("enabled".to_string(), Value::Bool(enabled));
+
+            mcp_map.insert(server.name.clone(), Value::Object(server_obj));
+        }
+
+        Value::Object(mcp_map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opencode_format() {
+        let json = serde_json::json!({
+            "test-server": {
+                "command": ["test-cmd", "arg1", "arg2"],
+                "environment": {
+                    "API_KEY": "secret"
+                },
+                "type": "local",
+                "enabled": true
+            }
+        });
+
+        let servers = OpencodeAdapter.parse(&json).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "test-server");
+        assert_eq!(servers[0].command, "test-cmd");
+        assert_eq!(
+            servers[0].args,
+            Some(vec!["arg1".to_string(), "arg2".to_string()])
+        );
+        assert!(servers[0].env.as_ref().unwrap().contains_key("API_KEY"));
+        assert_eq!(servers[0].disabled, Some(false));
+    }
+
+    #[test]
+    fn test_parse_opencode_disabled_server() {
+        let json = serde_json::json!({
+            "disabled-server": {
+                "command": ["cmd"],
+                "enabled": false
+            }
+        });
+
+        let servers = OpencodeAdapter.parse(&json).unwrap();
+        assert_eq!(servers[0].disabled, Some(true));
+    }
+
+    #[test]
+    fn test_parse_opencode_remote_server() {
+        let json = serde_json::json!({
+            "remote-server": {
+                "command": [],
+                "url": "https://api.example.com/mcp",
+                "type": "remote",
+                "enabled": true
+            }
+        });
+
+        let servers = OpencodeAdapter.parse(&json).unwrap();
+        assert_eq!(
+            servers[0].url,
+            Some("https://api.example.com/mcp".to_string())
+        );
+    }
+
+    #[test]
+    fn test_servers_to_opencode_format() {
+        let servers = vec![McpServer {
+            name: "test".to_string(),
+            command: "cmd".to_string(),
+            args: Some(vec!["arg1".to_string()]),
+            env: Some(
+                [("KEY".to_string(), "value".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }];
+
+        let result = OpencodeAdapter.render(&servers);
+        let server = result.get("test").unwrap();
+
+        let cmd = server.get("command").unwrap().as_array().unwrap();
+        assert_eq!(cmd[0].as_str().unwrap(), "cmd");
+        assert_eq!(cmd[1].as_str().unwrap(), "arg1");
+
+        assert!(server.get("environment").unwrap().get("KEY").is_some());
+        assert_eq!(server.get("type").unwrap().as_str().unwrap(), "local");
+        assert_eq!(server.get("enabled").unwrap().as_bool().unwrap(), true);
+    }
+
+    #[test]
+    fn test_servers_to_opencode_format_disabled() {
+        let servers = vec![McpServer {
+            name: "disabled".to_string(),
+            command: "cmd".to_string(),
+            args: None,
+            env: None,
+            disabled: Some(true),
+            url: None,
+            target: None,
+            extra: None,
+        }];
+
+        let result = OpencodeAdapter.render(&servers);
+        let server = result.get("disabled").unwrap();
+        assert_eq!(server.get("enabled").unwrap().as_bool().unwrap(), false);
+    }
+
+    #[test]
+    fn test_servers_to_opencode_format_remote() {
+        let servers = vec![McpServer {
+            name: "remote".to_string(),
+            command: "".to_string(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: Some("https://api.example.com/mcp".to_string()),
+            target: None,
+            extra: None,
+        }];
+
+        let result = OpencodeAdapter.render(&servers);
+        let server = result.get("remote").unwrap();
+        assert_eq!(server.get("type").unwrap().as_str().unwrap(), "remote");
+        assert_eq!(
+            server.get("url").unwrap().as_str().unwrap(),
+            "https://api.example.com/mcp"
+        );
+    }
+
+    #[test]
+    fn test_opencode_roundtrip() {
+        let original = vec![McpServer {
+            name: "roundtrip-test".to_string(),
+            command: "npx".to_string(),
+            args: Some(vec![
+                "-y".to_string(),
+                "@modelcontextprotocol/server".to_string(),
+            ]),
+            env: Some(
+                [("TOKEN".to_string(), "abc123".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            disabled: Some(false),
+            url: None,
+            target: None,
+            extra: None,
+        }];
+
+        let parsed = OpencodeAdapter
+            .parse(&OpencodeAdapter.render(&original))
+            .unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "roundtrip-test");
+        assert_eq!(parsed[0].command, "npx");
+        assert_eq!(
+            parsed[0].args,
+            Some(vec![
+                "-y".to_string(),
+                "@modelcontextprotocol/server".to_string()
+            ])
+        );
+        assert!(parsed[0].env.as_ref().unwrap().contains_key("TOKEN"));
+    }
+}
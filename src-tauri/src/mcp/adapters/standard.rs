@@ -0,0 +1,233 @@
+use super::{
+    server_map, string_list, string_list_value, string_map, string_map_value, McpFormatAdapter,
+};
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// `mcpServers: { name: { command, args, env, url } }`, used by Claude Code,
+/// Gemini CLI and most tools that copied its layout.
+pub struct StandardAdapter;
+
+/// The standard layout under Amp's literal `"amp.mcpServers"` settings key.
+pub struct AmpAdapter;
+
+const AMP_KEY: &str = "amp.mcpServers";
+
+// Known fields that we handle explicitly
+const KNOWN_FIELDS: [&str; 6] = ["command", "args", "env", "disabled", "url", "_target"];
+
+pub(super) fn parse_standard(
+    adapter: &dyn McpFormatAdapter,
+    servers: &Value,
+) -> Result<Vec<McpServer>, McpError> {
+    let mut result = Vec::new();
+
+    for (name, config) in server_map(servers, adapter)? {
+        if let Value::Object(server_config) = config {
+            let command = server_config
+                .get("command")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            let disabled = server_config.get("disabled").and_then(|v| v.as_bool());
+
+            let url = server_config
+                .get("url")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let target = server_config
+                .get("_target")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            // Capture any extra fields we don't explicitly handle
+            let extra: HashMap<String, Value> = server_config
+                .iter()
+                .filter(|(k, _)| !KNOWN_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+            let extra = if extra.is_empty() { None } else { Some(extra) };
+
+            result.push(McpServer {
+                name: name.clone(),
+                command,
+                args: string_list(server_config.get("args")),
+                env: string_map(server_config.get("env")),
+                disabled,
+                url,
+                target,
+                extra,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn render_standard(servers: &[McpServer]) -> Value {
+    let mut map = serde_json::Map::new();
+
+    for server in servers {
+        let mut server_obj = serde_json::Map::new();
+
+        // Only add command if it's not empty (for URL-based servers)
+        if !server.command.is_empty() {
+            server_obj.insert("command".to_string(), Value::String(server.command.clone()));
+        }
+
+        if let Some(args) = &server.args {
+            server_obj.insert("args".to_string(), string_list_value(args.iter().cloned()));
+        }
+
+        if let Some(env) = &server.env {
+            server_obj.insert("env".to_string(), string_map_value(env));
+        }
+
+        if server.disabled == Some(true) {
+            server_obj.insert("disabled".to_string(), Value::Bool(true));
+        }
+
+        if let Some(url) = &server.url {
+            server_obj.insert("url".to_string(), Value::String(url.clone()));
+        }
+
+        if let Some(target) = &server.target {
+            server_obj.insert("_target".to_string(), Value::String(target.clone()));
+        }
+
+        // Write any extra fields we captured
+        if let Some(extra) = &server.extra {
+            for (k, v) in extra {
+                server_obj.insert(k.clone(), v.clone());
+            }
+        }
+
+        map.insert(server.name.clone(), Value::Object(server_obj));
+    }
+
+    Value::Object(map)
+}
+
+impl McpFormatAdapter for StandardAdapter {
+    fn name(&self) -> &'static str {
+        "standard"
+    }
+
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        root.get("mcpServers")
+            .filter(|v| v.is_object())
+            .map(|_| vec!["mcpServers".to_string()])
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        parse_standard(self, servers)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        render_standard(servers)
+    }
+}
+
+impl McpFormatAdapter for AmpAdapter {
+    fn name(&self) -> &'static str {
+        "amp"
+    }
+
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        root.get(AMP_KEY)
+            .filter(|v| v.is_object())
+            .map(|_| vec![AMP_KEY.to_string()])
+    }
+
+    /// Amp settings are flat: the dotted path is always one literal key
+    fn locate(&self, _root: &Value, json_path: &str) -> Vec<String> {
+        vec![json_path.to_string()]
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        parse_standard(self, servers)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        render_standard(servers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_standard_mcp_servers() {
+        let json = serde_json::json!({
+            "filesystem": {
+                "command": "npx",
+                "args": ["-y", "@anthropic/mcp-filesystem-server"],
+                "env": {
+                    "HOME": "/home/user"
+                }
+            },
+            "git": {
+                "command": "git-mcp",
+                "disabled": true
+            }
+        });
+
+        let servers = StandardAdapter.parse(&json).unwrap();
+        assert_eq!(servers.len(), 2);
+
+        let fs_server = servers.iter().find(|s| s.name == "filesystem").unwrap();
+        assert_eq!(fs_server.command, "npx");
+        assert_eq!(
+            fs_server.args,
+            Some(vec![
+                "-y".to_string(),
+                "@anthropic/mcp-filesystem-server".to_string()
+            ])
+        );
+        assert!(fs_server.env.is_some());
+
+        let git_server = servers.iter().find(|s| s.name == "git").unwrap();
+        assert_eq!(git_server.command, "git-mcp");
+        assert_eq!(git_server.disabled, Some(true));
+    }
+
+    #[test]
+    fn test_servers_to_standard_format() {
+        let servers = vec![McpServer {
+            name: "test".to_string(),
+            command: "test-cmd".to_string(),
+            args: Some(vec!["arg1".to_string()]),
+            env: None,
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }];
+
+        let result = StandardAdapter.render(&servers);
+        assert!(result.is_object());
+
+        let obj = result.as_object().unwrap();
+        assert!(obj.contains_key("test"));
+
+        let test_server = obj.get("test").unwrap().as_object().unwrap();
+        assert_eq!(
+            test_server.get("command").unwrap().as_str().unwrap(),
+            "test-cmd"
+        );
+    }
+
+    #[test]
+    fn test_parse_standard_rejects_non_object() {
+        assert!(StandardAdapter.parse(&serde_json::json!([])).is_err());
+        assert!(StandardAdapter
+            .parse(&serde_json::json!({}))
+            .unwrap()
+            .is_empty());
+    }
+}
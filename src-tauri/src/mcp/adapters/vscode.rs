@@ -0,0 +1,159 @@
+use super::{
+    server_map, string_list, string_list_value, string_map, string_map_value, McpFormatAdapter,
+};
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// VS Code user settings: `"mcp": { "servers": { name: { type, command, args, env } } }`.
+/// Remote servers use `type: "http"` or `"sse"` with a `url`.
+pub struct VscodeAdapter;
+
+const KNOWN_FIELDS: [&str; 4] = ["command", "args", "env", "url"];
+
+impl McpFormatAdapter for VscodeAdapter {
+    fn name(&self) -> &'static str {
+        "vscode"
+    }
+
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        if root.get("mcp.servers").is_some_and(Value::is_object) {
+            return Some(vec!["mcp.servers".to_string()]);
+        }
+        root.get("mcp")?
+            .get("servers")
+            .filter(|v| v.is_object())
+            .map(|_| vec!["mcp".to_string(), "servers".to_string()])
+    }
+
+    /// `mcp` is a single setting holding an object, so the path is nested
+    /// unless the file already spells it as one dotted key
+    fn locate(&self, root: &Value, json_path: &str) -> Vec<String> {
+        if root.get(json_path).is_some() {
+            return vec![json_path.to_string()];
+        }
+        json_path.split('.').map(String::from).collect()
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        let mut result = Vec::new();
+
+        for (name, config) in server_map(servers, self)? {
+            let Value::Object(server_config) = config else {
+                continue;
+            };
+
+            // `type` stays in extra; the standard layout uses the same values
+            let extra: HashMap<String, Value> = server_config
+                .iter()
+                .filter(|(k, _)| !KNOWN_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+            result.push(McpServer {
+                name: name.clone(),
+                command: server_config
+                    .get("command")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                args: string_list(server_config.get("args")),
+                env: string_map(server_config.get("env")),
+                disabled: None,
+                url: server_config
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                target: None,
+                extra: if extra.is_empty() { None } else { Some(extra) },
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        let mut map = Map::new();
+
+        for server in servers {
+            let mut server_obj = Map::new();
+
+            let transport = if server.url.is_some() {
+                "http"
+            } else {
+                "stdio"
+            };
+            server_obj.insert("type".to_string(), Value::String(transport.to_string()));
+
+            if !server.command.is_empty() {
+                server_obj.insert("command".to_string(), Value::String(server.command.clone()));
+            }
+
+            if let Some(args) = &server.args {
+                server_obj.insert("args".to_string(), string_list_value(args.iter().cloned()));
+            }
+
+            if let Some(env) = &server.env {
+                server_obj.insert("env".to_string(), string_map_value(env));
+            }
+
+            if let Some(url) = &server.url {
+                server_obj.insert("url".to_string(), Value::String(url.clone()));
+            }
+
+            // An explicit `type` (e.g. "sse") overrides the inferred one
+            for (k, v) in server.extra.iter().flatten() {
+                server_obj.insert(k.clone(), v.clone());
+            }
+
+            map.insert(server.name.clone(), Value::Object(server_obj));
+        }
+
+        Value::Object(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_vscode_servers_round_trip() {
+        let settings = json!({
+            "editor.fontSize": 14,
+            "mcp": {
+                "inputs": [],
+                "servers": {
+                    "github": { "type": "stdio", "command": "npx", "args": ["-y", "github"], "env": { "T": "1" } },
+                    "remote": { "type": "sse", "url": "https://mcp.example.com/sse" }
+                }
+            }
+        });
+        let keys = VscodeAdapter.detect(&settings).unwrap();
+        assert_eq!(keys, vec!["mcp", "servers"]);
+        assert_eq!(VscodeAdapter.locate(&settings, "mcp.servers"), keys);
+        // A new settings file gets the nested layout too
+        assert_eq!(
+            VscodeAdapter.locate(&json!({ "mcp.enabled": true }), "mcp.servers"),
+            keys
+        );
+
+        let servers = VscodeAdapter.parse(&settings["mcp"]["servers"]).unwrap();
+        let rendered = VscodeAdapter.render(&servers);
+        assert_eq!(rendered, settings["mcp"]["servers"]);
+
+        // Servers from other tools get a type inferred
+        let plain = McpServer {
+            name: "plain".to_string(),
+            command: String::new(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: Some("https://mcp.example.com/mcp".to_string()),
+            target: None,
+            extra: None,
+        };
+        assert_eq!(VscodeAdapter.render(&[plain])["plain"]["type"], "http");
+    }
+}
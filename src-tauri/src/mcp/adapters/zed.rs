@@ -0,0 +1,157 @@
+use super::{
+    server_map, string_list, string_list_value, string_map, string_map_value, McpFormatAdapter,
+};
+use crate::mcp::types::{McpError, McpServer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Zed: `context_servers: { name: { source, command, args, env } }` in
+/// settings.json. Older Zed versions nest `{ path, args, env }` under
+/// `command`; both are read, the flat form is written.
+pub struct ZedAdapter;
+
+const KEY: &str = "context_servers";
+const KNOWN_FIELDS: [&str; 5] = ["command", "args", "env", "url", "source"];
+
+impl McpFormatAdapter for ZedAdapter {
+    fn name(&self) -> &'static str {
+        "zed"
+    }
+
+    fn detect(&self, root: &Value) -> Option<Vec<String>> {
+        root.get(KEY)
+            .filter(|v| v.is_object())
+            .map(|_| vec![KEY.to_string()])
+    }
+
+    fn parse(&self, servers: &Value) -> Result<Vec<McpServer>, McpError> {
+        let mut result = Vec::new();
+
+        for (name, config) in server_map(servers, self)? {
+            let Value::Object(server_config) = config else {
+                continue;
+            };
+
+            // Legacy layout: the command is an object of its own
+            let launch = match server_config.get("command") {
+                Some(Value::Object(legacy)) => legacy,
+                _ => server_config,
+            };
+            let command = match launch.get("path").or_else(|| launch.get("command")) {
+                Some(Value::String(command)) => command.clone(),
+                _ => String::new(),
+            };
+
+            // Extension-provided servers keep their source; `custom` is implied
+            let mut extra: HashMap<String, Value> = server_config
+                .iter()
+                .filter(|(k, _)| !KNOWN_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            if let Some(source) = server_config
+                .get("source")
+                .filter(|s| s.as_str() != Some("custom"))
+            {
+                extra.insert("source".to_string(), source.clone());
+            }
+
+            result.push(McpServer {
+                name: name.clone(),
+                command,
+                args: string_list(launch.get("args")),
+                env: string_map(launch.get("env")),
+                disabled: None,
+                url: server_config
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                target: None,
+                extra: if extra.is_empty() { None } else { Some(extra) },
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn render(&self, servers: &[McpServer]) -> Value {
+        let mut map = Map::new();
+
+        for server in servers {
+            let mut server_obj = Map::new();
+
+            if !server.command.is_empty() {
+                server_obj.insert("source".to_string(), Value::String("custom".to_string()));
+                server_obj.insert("command".to_string(), Value::String(server.command.clone()));
+                server_obj.insert(
+                    "args".to_string(),
+                    string_list_value(server.args.iter().flatten().cloned()),
+                );
+                let env = server.env.clone().unwrap_or_default();
+                server_obj.insert("env".to_string(), string_map_value(&env));
+            }
+
+            if let Some(url) = &server.url {
+                server_obj.insert("url".to_string(), Value::String(url.clone()));
+            }
+
+            for (k, v) in server.extra.iter().flatten() {
+                server_obj.insert(k.clone(), v.clone());
+            }
+
+            map.insert(server.name.clone(), Value::Object(server_obj));
+        }
+
+        Value::Object(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_zed_context_servers() {
+        let servers = ZedAdapter
+            .parse(&json!({
+                "flat": { "source": "custom", "command": "npx", "args": ["-y", "flat"], "env": { "A": "1" } },
+                "legacy": { "command": { "path": "/usr/bin/legacy", "args": ["--stdio"], "env": {} }, "settings": {} },
+                "remote": { "url": "https://mcp.example.com", "headers": { "Authorization": "Bearer x" } },
+                "from-extension": { "source": "extension", "settings": { "token": "t" } }
+            }))
+            .unwrap();
+        let find = |name: &str| servers.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("flat").command, "npx");
+        assert_eq!(
+            find("flat").args,
+            Some(vec!["-y".to_string(), "flat".to_string()])
+        );
+        assert!(find("flat").extra.is_none());
+        assert_eq!(find("legacy").command, "/usr/bin/legacy");
+        assert_eq!(find("legacy").args, Some(vec!["--stdio".to_string()]));
+        assert_eq!(
+            find("remote").url.as_deref(),
+            Some("https://mcp.example.com")
+        );
+        assert!(find("remote")
+            .extra
+            .as_ref()
+            .unwrap()
+            .contains_key("headers"));
+
+        // Rendering keeps what Zed needs to start each server again
+        let rendered = ZedAdapter.render(&servers);
+        assert_eq!(rendered["flat"]["source"], "custom");
+        assert_eq!(rendered["legacy"]["command"], "/usr/bin/legacy");
+        assert_eq!(
+            rendered["remote"],
+            json!({ "url": "https://mcp.example.com", "headers": { "Authorization": "Bearer x" } })
+        );
+        assert_eq!(
+            rendered["from-extension"],
+            json!({ "source": "extension", "settings": { "token": "t" } })
+        );
+        assert_eq!(ZedAdapter.parse(&rendered).unwrap().len(), 4);
+    }
+}
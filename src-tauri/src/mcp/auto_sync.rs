@@ -0,0 +1,204 @@
+use crate::app_paths::AppPaths;
+use crate::config::tool_registry;
+use crate::mcp::storage::{
+    load_mcp_config_internal, load_sync_log, save_mcp_config_internal, save_sync_log,
+};
+use crate::mcp::sync::{preview_mcp_sync_all_internal, sync_mcp_to_all_internal};
+use crate::mcp::types::{McpAutoSyncAction, McpConfig, McpError, McpSyncLog, McpSyncLogEntry};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Push a source change to the enabled tools, if auto-sync is on.
+///
+/// Tools without conflicts are synced in one transaction; tools with
+/// conflicts are left alone and queued for review. Returns what was logged,
+/// which is empty when auto-sync is off or there was nothing to do.
+pub fn auto_sync_internal(app: &impl AppPaths) -> Result<Vec<McpSyncLogEntry>, McpError> {
+    if !load_mcp_config_internal(app)?.auto_sync {
+        return Ok(Vec::new());
+    }
+
+    let previews = preview_mcp_sync_all_internal(app)?;
+    let registry = tool_registry(app);
+    let entry = |tool_id: &str, action, message: String| McpSyncLogEntry {
+        timestamp: now(),
+        tool_id: tool_id.to_string(),
+        config_path: registry
+            .mcp_tool(tool_id)
+            .map(|t| t.config_path)
+            .unwrap_or_default(),
+        action,
+        message,
+    };
+
+    let mut entries = Vec::new();
+    let mut queued = Vec::new();
+    for preview in previews
+        .iter()
+        .filter(|p| !p.merge_result.conflicts.is_empty())
+    {
+        let names: Vec<&str> = preview
+            .merge_result
+            .conflicts
+            .iter()
+            .map(|c| c.server_name.as_str())
+            .collect();
+        entries.push(entry(
+            &preview.tool_id,
+            McpAutoSyncAction::Queued,
+            format!("Conflicts on {}", names.join(", ")),
+        ));
+        queued.push(preview.tool_id.clone());
+    }
+
+    let ready: Vec<&str> = previews
+        .iter()
+        .filter(|p| p.has_changes && p.merge_result.conflicts.is_empty())
+        .map(|p| p.tool_id.as_str())
+        .collect();
+    if !ready.is_empty() {
+        // Abort if a tool's file moved on since the preview
+        let expected: HashMap<String, String> = previews
+            .iter()
+            .map(|p| (p.tool_id.clone(), p.fingerprint.clone()))
+            .collect();
+        for result in sync_mcp_to_all_internal(app, Some(expected), None)? {
+            if ready.contains(&result.tool_id.as_str()) {
+                let action = if result.success {
+                    McpAutoSyncAction::Applied
+                } else {
+                    McpAutoSyncAction::Failed
+                };
+                entries.push(entry(&result.tool_id, action, result.message));
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        return Ok(entries);
+    }
+
+    let mut log = load_sync_log(app)?;
+    for tool_id in queued {
+        if !log.review_queue.contains(&tool_id) {
+            log.review_queue.push(tool_id);
+        }
+    }
+    log.entries.extend(entries.iter().cloned());
+    save_sync_log(app, &mut log)?;
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn set_mcp_auto_sync(app: AppHandle, enabled: bool) -> Result<McpConfig, McpError> {
+    let mut config = load_mcp_config_internal(&app)?;
+    config.auto_sync = enabled;
+    save_mcp_config_internal(&app, &config)?;
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn get_mcp_sync_log(app: AppHandle) -> Result<McpSyncLog, McpError> {
+    load_sync_log(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use crate::mcp::{McpServer, McpSourceMode};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn server(name: &str, command: &str) -> McpServer {
+        McpServer {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }
+    }
+
+    fn setup() -> (PathBuf, StandalonePaths) {
+        let dir = std::env::temp_dir().join(format!("auto-sync-test-{}", uuid::Uuid::new_v4()));
+        let paths = StandalonePaths {
+            data_dir: Some(dir.join("data")),
+            config_dir: Some(dir.join("config")),
+        };
+        fs::create_dir_all(dir.join("config/tools")).unwrap();
+        fs::write(
+            dir.join("config/tools/acme.json"),
+            serde_json::json!({
+                "tools": [{
+                    "id": "acme-agent",
+                    "name": "Acme Agent",
+                    "mcp": { "configPath": dir.join("acme.json").to_string_lossy() }
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(dir.join("acme.json"), "{}").unwrap();
+        (dir, paths)
+    }
+
+    fn save_config(paths: &StandalonePaths, servers: Vec<McpServer>, auto_sync: bool) {
+        let config = McpConfig {
+            source_mode: McpSourceMode::AppManaged,
+            servers,
+            enabled_tools: vec!["acme-agent".to_string()],
+            auto_sync,
+        };
+        save_mcp_config_internal(paths, &config).unwrap();
+    }
+
+    #[test]
+    fn test_auto_sync_applies_or_queues() {
+        let (dir, paths) = setup();
+
+        save_config(&paths, vec![server("github", "gh-mcp")], false);
+        assert!(auto_sync_internal(&paths).unwrap().is_empty());
+
+        save_config(&paths, vec![server("github", "gh-mcp")], true);
+        let entries = auto_sync_internal(&paths).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, McpAutoSyncAction::Applied);
+        assert!(fs::read_to_string(dir.join("acme.json"))
+            .unwrap()
+            .contains("gh-mcp"));
+
+        // Nothing left to do
+        assert!(auto_sync_internal(&paths).unwrap().is_empty());
+
+        // Both sides edit the same server
+        fs::write(
+            dir.join("acme.json"),
+            r#"{"mcpServers": {"github": {"command": "tool-edit"}}}"#,
+        )
+        .unwrap();
+        save_config(&paths, vec![server("github", "source-edit")], true);
+        let entries = auto_sync_internal(&paths).unwrap();
+        assert_eq!(entries[0].action, McpAutoSyncAction::Queued);
+        assert!(fs::read_to_string(dir.join("acme.json"))
+            .unwrap()
+            .contains("tool-edit"));
+
+        let log = load_sync_log(&paths).unwrap();
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.review_queue, vec!["acme-agent".to_string()]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,411 @@
+use crate::app_paths::AppPaths;
+use crate::commands::{expand_path, BackupSettings};
+use crate::config::ConfigFormat;
+use crate::formats::{jsonc, ConfigDocument};
+use crate::history::{record_history, HistoryEntry};
+use crate::mcp::adapters::{McpFormatAdapter, StandardAdapter};
+use crate::mcp::sync::CLAUDE_SOURCE_TOOL_ID;
+use crate::mcp::types::{ClaudeProject, McpError, McpServer, McpToolInfo};
+use crate::transaction::{FileTarget, Transaction};
+use crate::versions::record_write;
+use serde_json::Value;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Claude Code's user config, which also holds its per-project settings
+pub const CLAUDE_CONFIG_PATH: &str = "~/.claude.json";
+
+fn claude_target(claude_config: &str) -> Result<FileTarget, McpError> {
+    let path = expand_path(claude_config)
+        .ok_or_else(|| McpError::NotFound(format!("Could not expand {}", claude_config)))?;
+    Ok(FileTarget::Local(path))
+}
+
+fn read_claude_root(claude_config: &str) -> Result<Value, McpError> {
+    match claude_target(claude_config)?.read()? {
+        Some(content) => Ok(jsonc::parse(&content)?),
+        None => Ok(Value::Null),
+    }
+}
+
+fn project_entry<'a>(root: &'a Value, project_path: &str) -> Result<&'a Value, McpError> {
+    root.get("projects")
+        .and_then(|projects| projects.get(project_path))
+        .ok_or_else(|| {
+            McpError::NotFound(format!(
+                "Project '{}' not found in ~/.claude.json",
+                project_path
+            ))
+        })
+}
+
+/// Projects Claude Code has recorded, in path order
+pub fn list_claude_projects_internal(claude_config: &str) -> Result<Vec<ClaudeProject>, McpError> {
+    let root = read_claude_root(claude_config)?;
+    let Some(projects) = root.get("projects").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(projects
+        .iter()
+        .map(|(path, entry)| ClaudeProject {
+            path: path.clone(),
+            name: Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone()),
+            server_count: entry
+                .get("mcpServers")
+                .and_then(Value::as_object)
+                .map_or(0, |servers| servers.len() as u32),
+            exists: Path::new(path).is_dir(),
+        })
+        .collect())
+}
+
+pub fn read_claude_project_servers_internal(
+    claude_config: &str,
+    project_path: &str,
+) -> Result<Vec<McpServer>, McpError> {
+    let root = read_claude_root(claude_config)?;
+    match project_entry(&root, project_path)?.get("mcpServers") {
+        Some(servers) => StandardAdapter.parse(servers),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// `content` with the project's `mcpServers` replaced. Everything else in the
+/// file, including other projects, is left as it was.
+pub fn render_claude_project_servers(
+    content: &str,
+    project_path: &str,
+    servers: &[McpServer],
+) -> Result<String, McpError> {
+    let mut document = ConfigDocument::parse(content, &ConfigFormat::Json)?;
+    project_entry(&document.to_value(), project_path)?;
+
+    document
+        .set_keys(
+            &["projects", project_path, "mcpServers"],
+            StandardAdapter.render(servers),
+        )?
+        .ok_or_else(|| {
+            McpError::InvalidFormat(format!(
+                "Cannot write mcpServers for project '{}'",
+                project_path
+            ))
+        })?;
+    Ok(document.render()?)
+}
+
+/// Stage the project's servers, building on content already staged for the
+/// Claude config. Returns the staged content.
+pub(crate) fn stage_claude_project_write(
+    transaction: &mut Transaction,
+    claude_config: &str,
+    project_path: &str,
+    servers: &[McpServer],
+) -> Result<String, McpError> {
+    let target = claude_target(claude_config)?;
+    let current = match transaction.staged_content(&target) {
+        Some(content) => content.to_string(),
+        None => target
+            .read()?
+            .ok_or_else(|| McpError::NotFound(format!("{} does not exist", claude_config)))?,
+    };
+
+    let content = render_claude_project_servers(&current, project_path, servers)?;
+    transaction.stage(target, content.clone(), None);
+    Ok(content)
+}
+
+/// Snapshot and mirror a committed write to the Claude config
+pub(crate) fn record_claude_write(
+    app: &impl AppPaths,
+    claude_config: &str,
+    written: &str,
+    origin: &str,
+) {
+    record_write(app, claude_config, None, written);
+    record_history(
+        app,
+        &HistoryEntry {
+            path: claude_config,
+            previous: None,
+            content: written,
+            origin,
+            json_path: None,
+            tool_id: Some(CLAUDE_SOURCE_TOOL_ID),
+        },
+    );
+}
+
+pub fn save_claude_project_servers_internal(
+    app: &impl AppPaths,
+    claude_config: &str,
+    project_path: &str,
+    servers: Vec<McpServer>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<Vec<McpServer>, McpError> {
+    for (index, server) in servers.iter().enumerate() {
+        if servers[..index].iter().any(|s| s.name == server.name) {
+            return Err(McpError::InvalidFormat(format!(
+                "Server with name '{}' already exists",
+                server.name
+            )));
+        }
+    }
+
+    let mut transaction = Transaction::new(app, backup_settings)?;
+    let written =
+        stage_claude_project_write(&mut transaction, claude_config, project_path, &servers)?;
+    transaction.commit()?;
+    record_claude_write(app, claude_config, &written, "save_claude_project_servers");
+    Ok(servers)
+}
+
+/// Project-level MCP targets (`<tool>@<project path>`) inside a Claude
+/// project, for every tool with a project config. Claude Code's own
+/// `.mcp.json` is left out, since Claude would load those servers twice.
+pub fn claude_project_targets(mcp_tools: &[McpToolInfo], project_path: &str) -> Vec<McpToolInfo> {
+    let project_name = Path::new(project_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string());
+
+    mcp_tools
+        .iter()
+        .filter(|tool| tool.workspace.is_none() && tool.tool_id != CLAUDE_SOURCE_TOOL_ID)
+        .filter_map(|tool| {
+            let relative = tool.project_config_path.as_ref()?;
+            Some(McpToolInfo {
+                tool_id: format!("{}@{}", tool.tool_id, project_path),
+                config_path: Path::new(project_path)
+                    .join(relative)
+                    .to_string_lossy()
+                    .to_string(),
+                json_path: tool.json_path.clone(),
+                format: tool.format.clone(),
+                name: format!("{} ({})", tool.name, project_name),
+                project_config_path: Some(relative.clone()),
+                workspace: None,
+            })
+        })
+        .collect()
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn list_claude_projects() -> Result<Vec<ClaudeProject>, McpError> {
+    list_claude_projects_internal(CLAUDE_CONFIG_PATH)
+}
+
+#[tauri::command]
+pub fn get_claude_project_servers(project_path: String) -> Result<Vec<McpServer>, McpError> {
+    read_claude_project_servers_internal(CLAUDE_CONFIG_PATH, &project_path)
+}
+
+#[tauri::command]
+pub fn save_claude_project_servers(
+    app: AppHandle,
+    project_path: String,
+    servers: Vec<McpServer>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<Vec<McpServer>, McpError> {
+    save_claude_project_servers_internal(
+        &app,
+        CLAUDE_CONFIG_PATH,
+        &project_path,
+        servers,
+        backup_settings,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use crate::mcp::converters::read_tool_mcp_servers;
+    use crate::mcp::sync::sync_claude_project_internal;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(name: &str) -> (StandalonePaths, PathBuf, String, String) {
+        let dir = std::env::temp_dir().join(format!(
+            "mcp_claude_projects_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let project = dir.join("app");
+        fs::create_dir_all(&project).unwrap();
+        let project = project.to_string_lossy().to_string();
+
+        let claude = dir.join(".claude.json");
+        let content = serde_json::json!({
+            "numStartups": 12,
+            "mcpServers": { "global": { "command": "global-mcp" } },
+            "projects": {
+                project.clone(): {
+                    "allowedTools": [],
+                    "mcpServers": {
+                        "github": { "command": "npx", "args": ["-y", "github"], "env": { "TOKEN": "t" } }
+                    }
+                },
+                "/gone/project": { "allowedTools": [] }
+            }
+        });
+        fs::write(&claude, serde_json::to_string_pretty(&content).unwrap()).unwrap();
+
+        let paths = StandalonePaths {
+            data_dir: Some(dir.join("data")),
+            config_dir: Some(dir.join("config")),
+        };
+        (paths, dir, claude.to_string_lossy().to_string(), project)
+    }
+
+    fn server(name: &str, command: &str) -> McpServer {
+        McpServer {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_list_and_read_projects() {
+        let (_, dir, claude, project) = setup("list");
+
+        let projects = list_claude_projects_internal(&claude).unwrap();
+        assert_eq!(projects.len(), 2);
+        let app = projects.iter().find(|p| p.path == project).unwrap();
+        assert_eq!(app.name, "app");
+        assert_eq!(app.server_count, 1);
+        assert!(app.exists);
+        let gone = projects.iter().find(|p| p.path == "/gone/project").unwrap();
+        assert_eq!(gone.server_count, 0);
+        assert!(!gone.exists);
+
+        let servers = read_claude_project_servers_internal(&claude, &project).unwrap();
+        assert_eq!(servers[0].name, "github");
+        assert!(
+            read_claude_project_servers_internal(&claude, "/gone/project")
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            read_claude_project_servers_internal(&claude, "/unknown"),
+            Err(McpError::NotFound(_))
+        ));
+
+        let missing = dir.join("missing.json").to_string_lossy().to_string();
+        assert!(list_claude_projects_internal(&missing).unwrap().is_empty());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_save_project_servers_keeps_rest_of_file() {
+        let (paths, dir, claude, project) = setup("save");
+
+        let saved = vec![server("docs", "docs-mcp")];
+        save_claude_project_servers_internal(&paths, &claude, &project, saved, None).unwrap();
+
+        let root: Value = serde_json::from_str(&fs::read_to_string(&claude).unwrap()).unwrap();
+        assert_eq!(root["numStartups"], 12);
+        assert_eq!(root["mcpServers"]["global"]["command"], "global-mcp");
+        assert_eq!(
+            root["projects"][&project]["allowedTools"],
+            serde_json::json!([])
+        );
+        assert_eq!(
+            root["projects"][&project]["mcpServers"],
+            serde_json::json!({ "docs": { "command": "docs-mcp" } })
+        );
+
+        let duplicate = vec![server("a", "x"), server("a", "y")];
+        assert!(
+            save_claude_project_servers_internal(&paths, &claude, &project, duplicate, None)
+                .is_err()
+        );
+        let unknown =
+            save_claude_project_servers_internal(&paths, &claude, "/unknown", Vec::new(), None);
+        assert!(matches!(unknown, Err(McpError::NotFound(_))));
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_sync_project_to_tool_project_files() {
+        let (paths, dir, claude, project) = setup("sync");
+        let only = Some(vec!["cursor".to_string(), "opencode".to_string()]);
+
+        let results =
+            sync_claude_project_internal(&paths, &claude, &project, only.clone(), None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(
+            results.iter().all(|r| r.success && r.servers_written == 1),
+            "{:?}",
+            results
+        );
+        assert_eq!(results[0].tool_id, format!("opencode@{}", project));
+
+        let targets = claude_project_targets(&crate::mcp::get_mcp_tool_definitions(), &project);
+        assert!(!targets
+            .iter()
+            .any(|t| t.tool_id.starts_with("claude-code@")));
+        let cursor = targets
+            .iter()
+            .find(|t| t.tool_id.starts_with("cursor@"))
+            .unwrap();
+        assert_eq!(
+            cursor.config_path,
+            Path::new(&project)
+                .join(".cursor/mcp.json")
+                .to_string_lossy()
+        );
+        let written = read_tool_mcp_servers(cursor).unwrap();
+        assert_eq!(written[0].name, "github");
+        assert_eq!(
+            written[0].args,
+            Some(vec!["-y".to_string(), "github".to_string()])
+        );
+        let opencode = fs::read_to_string(Path::new(&project).join("opencode.json")).unwrap();
+        assert!(opencode.contains("\"github\""), "{}", opencode);
+
+        // A server added in Cursor's project file is pulled back into the project
+        let mut edited = written.clone();
+        edited.push(server("local", "local-mcp"));
+        let content = crate::mcp::render_tool_config(cursor, None, &edited).unwrap();
+        fs::write(&cursor.config_path, content).unwrap();
+
+        let results =
+            sync_claude_project_internal(&paths, &claude, &project, only.clone(), None).unwrap();
+        assert!(
+            results[1].message.contains("1 pulled back"),
+            "{:?}",
+            results
+        );
+        let servers = read_claude_project_servers_internal(&claude, &project).unwrap();
+        assert!(servers.iter().any(|s| s.name == "local"));
+
+        // Tools synced earlier in the run pick it up on the next one
+        let results = sync_claude_project_internal(&paths, &claude, &project, only, None).unwrap();
+        assert!(
+            results.iter().all(|r| r.success && r.servers_written == 2),
+            "{:?}",
+            results
+        );
+
+        let missing = sync_claude_project_internal(&paths, &claude, "/gone/project", None, None);
+        assert!(matches!(missing, Err(McpError::NotFound(_))));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -1,244 +1,61 @@
-use crate::commands::expand_path;
-use crate::mcp::storage::{parse_standard_mcp_servers, servers_to_standard_format};
-use crate::mcp::types::{McpError, McpServer, McpToolFormat, McpToolInfo, get_mcp_tool_info};
-use serde_json::Value;
-use std::collections::HashMap;
-use std::fs;
-
+use crate::config::tool_registry;
+use crate::formats::ConfigDocument;
+use crate::mcp::adapters::value_at;
+use crate::mcp::location::McpToolLocation;
+use crate::mcp::types::{McpError, McpServer, McpToolInfo};
+use tauri::AppHandle;
+
+/// Servers in a tool's config file, read through its format adapter.
 pub fn read_tool_mcp_servers(tool_info: &McpToolInfo) -> Result<Vec<McpServer>, McpError> {
-    let path = expand_path(&tool_info.config_path)
-        .ok_or_else(|| McpError::NotFound(format!("Could not expand {}", tool_info.config_path)))?;
-
-    if !path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = fs::read_to_string(&path)?;
-    let root: Value = serde_json::from_str(&content)?;
-
-    match tool_info.format {
-        McpToolFormat::Standard => {
-            let json_path = &tool_info.json_path;
-            
-            // First try as literal key (e.g., "amp.mcpServers" is a literal key in Amp settings)
-            if let Some(servers_value) = root.get(json_path) {
-                if servers_value.is_object() {
-                    return parse_standard_mcp_servers(&root, json_path);
-                }
-            }
-            
-            // Then try as nested path
-            if json_path.contains('.') {
-                let parts: Vec<&str> = json_path.split('.').collect();
-                let mut current = &root;
-                for (i, part) in parts.iter().enumerate() {
-                    if i == parts.len() - 1 {
-                        return parse_standard_mcp_servers_from_value(current, part);
-                    }
-                    match current.get(part) {
-                        Some(v) => current = v,
-                        None => return Ok(Vec::new()),
-                    }
-                }
-                Ok(Vec::new())
-            } else {
-                parse_standard_mcp_servers(&root, json_path)
-            }
-        }
-        McpToolFormat::Copilot => parse_copilot_mcp_servers(&root),
-        McpToolFormat::Opencode => parse_opencode_mcp_servers(&root),
-    }
-}
-
-fn parse_standard_mcp_servers_from_value(root: &Value, key: &str) -> Result<Vec<McpServer>, McpError> {
-    parse_standard_mcp_servers(root, key)
-}
-
-fn parse_copilot_mcp_servers(root: &Value) -> Result<Vec<McpServer>, McpError> {
-    let servers = match root.get("servers") {
-        Some(Value::Object(servers)) => servers,
-        Some(_) => return Err(McpError::InvalidFormat("servers is not an object".to_string())),
+    let location = McpToolLocation::of(tool_info)?;
+    let content = match location.read()? {
+        Some(content) => content,
         None => return Ok(Vec::new()),
     };
+    let root = ConfigDocument::parse(&content, &location.format())?.to_value();
 
-    let mut result = Vec::new();
-
-    for (name, config) in servers {
-        if let Value::Object(server_config) = config {
-            let command = server_config
-                .get("command")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let args = server_config.get("args").and_then(|v| {
-                v.as_array().map(|arr| {
-                    arr.iter()
-                        .filter_map(|item| item.as_str().map(|s| s.to_string()))
-                        .collect()
-                })
-            });
-
-            let env = server_config.get("env").and_then(|v| {
-                v.as_object().map(|obj| {
-                    obj.iter()
-                        .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                        .collect::<HashMap<String, String>>()
-                })
-            });
-
-            result.push(McpServer {
-                name: name.clone(),
-                command,
-                args,
-                env,
-                disabled: None,
-                url: None,
-                target: None,
-                extra: None,
-            });
-        }
+    let adapter = tool_info.format.adapter()?;
+    match value_at(&root, &adapter.locate(&root, &tool_info.json_path)) {
+        Some(servers) => adapter.parse(servers),
+        None => Ok(Vec::new()),
     }
-
-    Ok(result)
 }
 
-fn parse_opencode_mcp_servers(root: &Value) -> Result<Vec<McpServer>, McpError> {
-    let mcp = match root.get("mcp") {
-        Some(Value::Object(mcp)) => mcp,
-        Some(_) => return Err(McpError::InvalidFormat("mcp is not an object".to_string())),
-        None => return Ok(Vec::new()),
+/// The tool's config file with `servers` written in. Edits `current` in place
+/// (comments and key order are preserved where the format allows) or starts
+/// from an empty document.
+pub fn render_tool_config(
+    tool_info: &McpToolInfo,
+    current: Option<&str>,
+    servers: &[McpServer],
+) -> Result<String, McpError> {
+    let format = McpToolLocation::of(tool_info)?.format();
+    let mut document = match current {
+        Some(content) => ConfigDocument::parse(content, &format)?,
+        None => ConfigDocument::empty(&format)?,
     };
 
-    let mut result = Vec::new();
-
-    for (name, config) in mcp {
-        if let Value::Object(server_config) = config {
-            let (command, args) = if let Some(cmd_array) = server_config.get("command").and_then(|v| v.as_array()) {
-                let cmd_parts: Vec<String> = cmd_array
-                    .iter()
-                    .filter_map(|item| item.as_str().map(|s| s.to_string()))
-                    .collect();
-                if cmd_parts.is_empty() {
-                    (String::new(), None)
-                } else {
-                    let command = cmd_parts[0].clone();
-                    let args = if cmd_parts.len() > 1 {
-                        Some(cmd_parts[1..].to_vec())
-                    } else {
-                        None
-                    };
-                    (command, args)
-                }
-            } else {
-                (String::new(), None)
-            };
-
-            let env = server_config.get("environment").and_then(|v| {
-                v.as_object().map(|obj| {
-                    obj.iter()
-                        .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                        .collect::<HashMap<String, String>>()
-                })
-            });
-
-            let url = server_config.get("url").and_then(|v| v.as_str()).map(|s| s.to_string());
-
-            let disabled = server_config.get("enabled").and_then(|v| v.as_bool()).map(|enabled| !enabled);
-
-            result.push(McpServer {
-                name: name.clone(),
-                command,
-                args,
-                env,
-                disabled,
-                url,
-                target: None,
-                extra: None,
-            });
-        }
-    }
-
-    Ok(result)
-}
-
-pub fn servers_to_tool_format(servers: &[McpServer], format: &McpToolFormat) -> Value {
-    match format {
-        McpToolFormat::Standard => servers_to_standard_format(servers),
-        McpToolFormat::Copilot => servers_to_copilot_format(servers),
-        McpToolFormat::Opencode => servers_to_opencode_format(servers),
-    }
-}
-
-fn servers_to_copilot_format(servers: &[McpServer]) -> Value {
-    let mut map = serde_json::Map::new();
-
-    for server in servers {
-        let mut server_obj = serde_json::Map::new();
-        server_obj.insert("command".to_string(), Value::String(server.command.clone()));
-
-        if let Some(args) = &server.args {
-            server_obj.insert(
-                "args".to_string(),
-                Value::Array(args.iter().map(|s| Value::String(s.clone())).collect()),
-            );
-        }
-
-        if let Some(env) = &server.env {
-            let env_obj: serde_json::Map<String, Value> = env
-                .iter()
-                .map(|(k, v)| (k.clone(), Value::String(v.clone())))
-                .collect();
-            server_obj.insert("env".to_string(), Value::Object(env_obj));
-        }
-
-        map.insert(server.name.clone(), Value::Object(server_obj));
-    }
-
-    Value::Object(map)
-}
-
-fn servers_to_opencode_format(servers: &[McpServer]) -> Value {
-    let mut mcp_map = serde_json::Map::new();
-
-    for server in servers {
-        let mut server_obj = serde_json::Map::new();
-
-        let mut cmd_array: Vec<Value> = vec![Value::String(server.command.clone())];
-        if let Some(args) = &server.args {
-            cmd_array.extend(args.iter().map(|s| Value::String(s.clone())));
-        }
-        server_obj.insert("command".to_string(), Value::Array(cmd_array));
-
-        if let Some(env) = &server.env {
-            let env_obj: serde_json::Map<String, Value> = env
-                .iter()
-                .map(|(k, v)| (k.clone(), Value::String(v.clone())))
-                .collect();
-            server_obj.insert("environment".to_string(), Value::Object(env_obj));
-        }
-
-        if let Some(url) = &server.url {
-            server_obj.insert("url".to_string(), Value::String(url.clone()));
-            server_obj.insert("type".to_string(), Value::String("remote".to_string()));
-        } else {
-            server_obj.insert("type".to_string(), Value::String("local".to_string()));
-        }
-
-        let enabled = !server.disabled.unwrap_or(false);
-        server_obj.insert("enabled".to_string(), Value::Bool(enabled));
-
-        mcp_map.insert(server.name.clone(), Value::Object(server_obj));
-    }
-
-    Value::Object(mcp_map)
+    let adapter = tool_info.format.adapter()?;
+    let keys = adapter.locate(&document.to_value(), &tool_info.json_path);
+    let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+    adapter
+        .write(&mut document, &keys, servers)?
+        .ok_or_else(|| {
+            McpError::InvalidFormat(format!(
+                "Cannot write {} in {}",
+                tool_info.json_path, tool_info.config_path
+            ))
+        })?;
+
+    Ok(document.render()?)
 }
 
 // Tauri command
 
 #[tauri::command]
-pub fn get_tool_mcp_servers(tool_id: String) -> Result<Vec<McpServer>, McpError> {
-    let tool_info = get_mcp_tool_info(&tool_id)
+pub fn get_tool_mcp_servers(app: AppHandle, tool_id: String) -> Result<Vec<McpServer>, McpError> {
+    let tool_info = tool_registry(&app)
+        .mcp_tool(&tool_id)
         .ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
     
     read_tool_mcp_servers(&tool_info)
@@ -247,86 +64,28 @@ pub fn get_tool_mcp_servers(tool_id: String) -> Result<Vec<McpServer>, McpError>
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_parse_copilot_format() {
-        let json = serde_json::json!({
-            "servers": {
-                "test-server": {
-                    "command": "test-cmd",
-                    "args": ["arg1"]
-                }
-            }
-        });
-
-        let servers = parse_copilot_mcp_servers(&json).unwrap();
-        assert_eq!(servers.len(), 1);
-        assert_eq!(servers[0].name, "test-server");
-        assert_eq!(servers[0].command, "test-cmd");
-    }
-
-    #[test]
-    fn test_parse_opencode_format() {
-        let json = serde_json::json!({
-            "mcp": {
-                "test-server": {
-                    "command": ["test-cmd", "arg1", "arg2"],
-                    "environment": {
-                        "API_KEY": "secret"
-                    },
-                    "type": "local",
-                    "enabled": true
-                }
-            }
-        });
-
-        let servers = parse_opencode_mcp_servers(&json).unwrap();
-        assert_eq!(servers.len(), 1);
-        assert_eq!(servers[0].name, "test-server");
-        assert_eq!(servers[0].command, "test-cmd");
-        assert_eq!(servers[0].args, Some(vec!["arg1".to_string(), "arg2".to_string()]));
-        assert!(servers[0].env.as_ref().unwrap().contains_key("API_KEY"));
-        assert_eq!(servers[0].disabled, Some(false));
-    }
-
-    #[test]
-    fn test_parse_opencode_disabled_server() {
-        let json = serde_json::json!({
-            "mcp": {
-                "disabled-server": {
-                    "command": ["cmd"],
-                    "enabled": false
-                }
-            }
-        });
-
-        let servers = parse_opencode_mcp_servers(&json).unwrap();
-        assert_eq!(servers[0].disabled, Some(true));
-    }
-
-    #[test]
-    fn test_parse_opencode_remote_server() {
-        let json = serde_json::json!({
-            "mcp": {
-                "remote-server": {
-                    "command": [],
-                    "url": "https://api.example.com/mcp",
-                    "type": "remote",
-                    "enabled": true
-                }
-            }
-        });
-
-        let servers = parse_opencode_mcp_servers(&json).unwrap();
-        assert_eq!(servers[0].url, Some("https://api.example.com/mcp".to_string()));
+    use crate::mcp::types::McpToolFormat;
+
+    fn tool(config_path: &str, json_path: &str, format: &str) -> McpToolInfo {
+        McpToolInfo {
+            tool_id: "test".to_string(),
+            config_path: config_path.to_string(),
+            json_path: json_path.to_string(),
+            format: McpToolFormat::new(format),
+            name: "Test".to_string(),
+            project_config_path: None,
+            workspace: None,
+        }
     }
 
     #[test]
-    fn test_servers_to_copilot_format() {
+    fn test_render_and_read_through_adapter() {
+        let dir = std::env::temp_dir().join(format!("mcp_converters_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
         let servers = vec![McpServer {
-            name: "test".to_string(),
-            command: "cmd".to_string(),
-            args: None,
+            name: "files".to_string(),
+            command: "npx".to_string(),
+            args: Some(vec!["-y".to_string(), "files".to_string()]),
             env: None,
             disabled: None,
             url: None,
@@ -334,94 +93,129 @@ mod tests {
             extra: None,
         }];
 
-        let result = servers_to_copilot_format(&servers);
-        assert!(result.is_object());
-        assert!(result.get("test").is_some());
-    }
-
-    #[test]
-    fn test_servers_to_opencode_format() {
-        let servers = vec![McpServer {
-            name: "test".to_string(),
-            command: "cmd".to_string(),
-            args: Some(vec!["arg1".to_string()]),
-            env: Some([("KEY".to_string(), "value".to_string())].into_iter().collect()),
-            disabled: None,
-            url: None,
-            target: None,
-            extra: None,
-        }];
-
-        let result = servers_to_opencode_format(&servers);
-        let server = result.get("test").unwrap();
-        
-        let cmd = server.get("command").unwrap().as_array().unwrap();
-        assert_eq!(cmd[0].as_str().unwrap(), "cmd");
-        assert_eq!(cmd[1].as_str().unwrap(), "arg1");
-        
-        assert!(server.get("environment").unwrap().get("KEY").is_some());
-        assert_eq!(server.get("type").unwrap().as_str().unwrap(), "local");
-        assert_eq!(server.get("enabled").unwrap().as_bool().unwrap(), true);
-    }
-
-    #[test]
-    fn test_servers_to_opencode_format_disabled() {
-        let servers = vec![McpServer {
-            name: "disabled".to_string(),
-            command: "cmd".to_string(),
-            args: None,
-            env: None,
-            disabled: Some(true),
-            url: None,
-            target: None,
-            extra: None,
-        }];
+        // The file format follows the extension; other settings are kept
+        let yaml = dir.join("settings.yaml");
+        let info = tool(&yaml.to_string_lossy(), "mcpServers", "standard");
+        let content = render_tool_config(&info, Some("theme: dark\n"), &servers).unwrap();
+        assert!(content.contains("theme: dark"));
+        std::fs::write(&yaml, &content).unwrap();
+        assert_eq!(read_tool_mcp_servers(&info).unwrap(), servers);
 
-        let result = servers_to_opencode_format(&servers);
-        let server = result.get("disabled").unwrap();
-        assert_eq!(server.get("enabled").unwrap().as_bool().unwrap(), false);
-    }
+        // Amp keeps the dotted key literal even in a new file
+        let amp = dir.join("amp.json");
+        let info = tool(&amp.to_string_lossy(), "amp.mcpServers", "amp");
+        let content = render_tool_config(&info, None, &servers).unwrap();
+        assert!(content.contains("\"amp.mcpServers\""));
+        std::fs::write(&amp, &content).unwrap();
+        assert_eq!(read_tool_mcp_servers(&info).unwrap(), servers);
 
-    #[test]
-    fn test_servers_to_opencode_format_remote() {
-        let servers = vec![McpServer {
-            name: "remote".to_string(),
-            command: "".to_string(),
-            args: None,
-            env: None,
-            disabled: None,
-            url: Some("https://api.example.com/mcp".to_string()),
-            target: None,
-            extra: None,
-        }];
+        let info = tool(&amp.to_string_lossy(), "mcp", "xml");
+        assert!(read_tool_mcp_servers(&info).is_err());
 
-        let result = servers_to_opencode_format(&servers);
-        let server = result.get("remote").unwrap();
-        assert_eq!(server.get("type").unwrap().as_str().unwrap(), "remote");
-        assert_eq!(server.get("url").unwrap().as_str().unwrap(), "https://api.example.com/mcp");
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_opencode_roundtrip() {
-        let original = vec![McpServer {
-            name: "roundtrip-test".to_string(),
-            command: "npx".to_string(),
-            args: Some(vec!["-y".to_string(), "@modelcontextprotocol/server".to_string()]),
-            env: Some([("TOKEN".to_string(), "abc123".to_string())].into_iter().collect()),
-            disabled: Some(false),
-            url: None,
-            target: None,
-            extra: None,
-        }];
-
-        let serialized = servers_to_opencode_format(&original);
-        let wrapped = serde_json::json!({ "mcp": serialized });
-        let parsed = parse_opencode_mcp_servers(&wrapped).unwrap();
+    fn test_new_sync_targets_round_trip() {
+        let dir = std::env::temp_dir().join(format!("mcp_targets_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let servers = vec![
+            McpServer {
+                name: "github".to_string(),
+                command: "npx".to_string(),
+                args: Some(vec![
+                    "-y".to_string(),
+                    "@modelcontextprotocol/server-github".to_string(),
+                ]),
+                env: Some(
+                    [("GITHUB_TOKEN".to_string(), "t".to_string())]
+                        .into_iter()
+                        .collect(),
+                ),
+                disabled: None,
+                url: None,
+                target: None,
+                extra: None,
+            },
+            McpServer {
+                name: "remote".to_string(),
+                command: String::new(),
+                args: None,
+                env: None,
+                disabled: None,
+                url: Some("https://mcp.example.com/mcp".to_string()),
+                target: None,
+                extra: None,
+            },
+        ];
+        // Existing content each tool keeps next to its servers
+        let existing = [
+            ("cursor", "{ \"mcpServers\": {} }", "mcpServers"),
+            (
+                "kiro-cli",
+                "{ \"mcpServers\": {}, \"powers\": [] }",
+                "powers",
+            ),
+            ("kilo-code-cli", "{}", "mcpServers"),
+            (
+                "continue",
+                "name: Assistant\nversion: 1.0.0\nschema: v1\nmodels: []\n",
+                "schema: v1",
+            ),
+            (
+                "zed",
+                "{\n  // Editor theme\n  \"theme\": \"One Dark\"\n}",
+                "// Editor theme",
+            ),
+            (
+                "vscode",
+                "{\n  // Fonts\n  \"editor.fontSize\": 14\n}",
+                "\"editor.fontSize\": 14",
+            ),
+            (
+                "codex",
+                "# Codex settings\nmodel = \"o3\"\n",
+                "# Codex settings",
+            ),
+        ];
+
+        for (tool_id, content, kept) in existing {
+            let mut info = crate::mcp::get_mcp_tool_definitions()
+                .into_iter()
+                .find(|t| t.tool_id == tool_id)
+                .unwrap();
+            let file_name = std::path::Path::new(&info.config_path)
+                .file_name()
+                .unwrap()
+                .to_owned();
+            let path = dir.join(tool_id).join(file_name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            info.config_path = path.to_string_lossy().to_string();
+
+            let rendered = render_tool_config(&info, Some(content), &servers).unwrap();
+            assert!(rendered.contains(kept), "{}: {}", tool_id, rendered);
+            std::fs::write(&path, &rendered).unwrap();
+
+            let mut read = read_tool_mcp_servers(&info).unwrap();
+            read.sort_by(|a, b| a.name.cmp(&b.name));
+            assert_eq!(read.len(), 2, "{}", tool_id);
+            for (read, written) in read.iter().zip(&servers) {
+                assert_eq!(read.name, written.name, "{}", tool_id);
+                assert_eq!(read.command, written.command, "{}", tool_id);
+                assert_eq!(read.env, written.env, "{}", tool_id);
+                assert_eq!(read.url, written.url, "{}", tool_id);
+            }
+            assert_eq!(read[0].args, servers[0].args, "{}", tool_id);
+
+            // Writing what was read back changes nothing
+            assert_eq!(
+                render_tool_config(&info, Some(&rendered), &read).unwrap(),
+                rendered,
+                "{}",
+                tool_id
+            );
+        }
 
-        assert_eq!(parsed.len(), 1);
-        assert_eq!(parsed[0].name, "roundtrip-test");
-        assert_eq!(parsed[0].command, "npx");
-        assert_eq!(parsed[0].args, Some(vec!["-y".to_string(), "@modelcontextprotocol/server".to_string()]));
-        assert!(parsed[0].env.as_ref().unwrap().contains_key("TOKEN"));
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
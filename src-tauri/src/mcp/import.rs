@@ -1,96 +1,43 @@
-use crate::mcp::storage::parse_standard_mcp_servers;
+use crate::config::ConfigFormat;
+use crate::formats::{resolve_format, ConfigDocument};
+use crate::mcp::adapters::detect_servers;
 use crate::mcp::types::{McpError, McpServer};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "kebab-case")]
-pub enum DetectedFormat {
-    Standard,     // mcpServers key
-    Amp,          // amp.mcpServers literal key
-    Copilot,      // servers key
-    Opencode,     // mcp.<server-name> (native OpenCode format)
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportResult {
     pub servers: Vec<McpServer>,
     pub source_path: String,
-    pub detected_format: DetectedFormat,
+    /// Name of the format adapter that recognized the file
+    pub detected_format: String,
 }
 
-pub fn parse_mcp_config_from_content(content: &str) -> Result<(Vec<McpServer>, DetectedFormat), McpError> {
-    let root: Value = serde_json::from_str(content)?;
-    
-    // Try each format in order of specificity
-    
-    // 1. Try Amp format (amp.mcpServers literal key)
-    if let Some(servers_value) = root.get("amp.mcpServers") {
-        if servers_value.is_object() {
-            let servers = parse_standard_mcp_servers(&root, "amp.mcpServers")?;
-            if !servers.is_empty() {
-                return Ok((servers, DetectedFormat::Amp));
-            }
-        }
-    }
-    
-    // 2. Try OpenCode format (servers directly under mcp.<server-name>)
-    if let Some(mcp_value) = root.get("mcp") {
-        if let Some(mcp_obj) = mcp_value.as_object() {
-            let has_server_configs = mcp_obj.values().any(|v| {
-                v.as_object().is_some_and(|obj| {
-                    obj.contains_key("command") || obj.contains_key("url")
-                })
-            });
-            if has_server_configs {
-                let servers = parse_opencode_mcp_servers(&root)?;
-                if !servers.is_empty() {
-                    return Ok((servers, DetectedFormat::Opencode));
-                }
-            }
-        }
-    }
-    
-    // 3. Try Copilot format (servers key, but NOT mcp.servers)
-    if root.get("mcp").is_none() {
-        if let Some(servers_value) = root.get("servers") {
-            if servers_value.is_object() {
-                let servers = parse_copilot_mcp_servers(&root)?;
-                if !servers.is_empty() {
-                    return Ok((servers, DetectedFormat::Copilot));
-                }
-            }
-        }
-    }
-    
-    // 4. Try Standard format (mcpServers key)
-    if let Some(servers_value) = root.get("mcpServers") {
-        if servers_value.is_object() {
-            let servers = parse_standard_mcp_servers(&root, "mcpServers")?;
-            if !servers.is_empty() {
-                return Ok((servers, DetectedFormat::Standard));
-            }
-        }
+/// Servers in `content`, with the name of the adapter that recognized them.
+pub fn parse_mcp_config_from_content(
+    content: &str,
+    format: &ConfigFormat,
+) -> Result<(Vec<McpServer>, String), McpError> {
+    let root = ConfigDocument::parse(content, format)?.to_value();
+    match detect_servers(&root)? {
+        Some((adapter, servers)) => Ok((servers, adapter.name().to_string())),
+        None => Err(McpError::InvalidFormat(
+            "No recognized MCP config format found. Expected one of: mcpServers, amp.mcpServers, servers, mcp.<server-name>".to_string()
+        )),
     }
-    
-    // No recognized format found
-    Err(McpError::InvalidFormat(
-        "No recognized MCP config format found. Expected one of: mcpServers, amp.mcpServers, servers, mcp.<server-name>".to_string()
-    ))
 }
 
 pub fn parse_mcp_config_file(path: &Path) -> Result<ImportResult, McpError> {
     if !path.exists() {
         return Err(McpError::NotFound(format!("File not found: {}", path.display())));
     }
-    
+
     let content = fs::read_to_string(path)?;
-    let (servers, detected_format) = parse_mcp_config_from_content(&content)?;
-    
+    let (servers, detected_format) =
+        parse_mcp_config_from_content(&content, &resolve_format(None, path))?;
+
     Ok(ImportResult {
         servers,
         source_path: path.to_string_lossy().to_string(),
@@ -98,136 +45,6 @@ pub fn parse_mcp_config_file(path: &Path) -> Result<ImportResult, McpError> {
     })
 }
 
-fn parse_copilot_mcp_servers(root: &Value) -> Result<Vec<McpServer>, McpError> {
-    let servers = match root.get("servers") {
-        Some(Value::Object(servers)) => servers,
-        Some(_) => return Err(McpError::InvalidFormat("servers is not an object".to_string())),
-        None => return Ok(Vec::new()),
-    };
-
-    Ok(parse_servers_from_object(servers))
-}
-
-fn parse_opencode_mcp_servers(root: &Value) -> Result<Vec<McpServer>, McpError> {
-    let mcp = match root.get("mcp") {
-        Some(Value::Object(mcp)) => mcp,
-        Some(_) => return Err(McpError::InvalidFormat("mcp is not an object".to_string())),
-        None => return Ok(Vec::new()),
-    };
-
-    let mut result = Vec::new();
-
-    for (name, config) in mcp {
-        if let Value::Object(server_config) = config {
-            let (command, args) = if let Some(cmd_array) = server_config.get("command").and_then(|v| v.as_array()) {
-                let cmd_parts: Vec<String> = cmd_array
-                    .iter()
-                    .filter_map(|item| item.as_str().map(|s| s.to_string()))
-                    .collect();
-                if cmd_parts.is_empty() {
-                    (String::new(), None)
-                } else {
-                    let command = cmd_parts[0].clone();
-                    let args = if cmd_parts.len() > 1 {
-                        Some(cmd_parts[1..].to_vec())
-                    } else {
-                        None
-                    };
-                    (command, args)
-                }
-            } else {
-                (String::new(), None)
-            };
-
-            let env = server_config.get("environment").and_then(|v| {
-                v.as_object().map(|obj| {
-                    obj.iter()
-                        .filter_map(|(k, v)| value_to_string(v).map(|s| (k.clone(), s)))
-                        .collect::<HashMap<String, String>>()
-                })
-            });
-
-            let url = server_config.get("url").and_then(|v| v.as_str()).map(|s| s.to_string());
-            let disabled = server_config.get("enabled").and_then(|v| v.as_bool()).map(|enabled| !enabled);
-
-            result.push(McpServer {
-                name: name.clone(),
-                command,
-                args,
-                env,
-                disabled,
-                url,
-                target: None,
-                extra: None,
-            });
-        }
-    }
-
-    Ok(result)
-}
-
-fn parse_servers_from_object(servers: &serde_json::Map<String, Value>) -> Vec<McpServer> {
-    let mut result = Vec::new();
-
-    for (name, config) in servers {
-        if let Value::Object(server_config) = config {
-            result.push(parse_single_server_config(name, server_config));
-        }
-    }
-
-    result
-}
-
-fn parse_single_server_config(name: &str, server_config: &serde_json::Map<String, Value>) -> McpServer {
-    let command = server_config
-        .get("command")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-
-    let args = server_config.get("args").and_then(|v| {
-        v.as_array().map(|arr| {
-            arr.iter()
-                .filter_map(|item| item.as_str().map(|s| s.to_string()))
-                .collect()
-        })
-    });
-
-    let env = server_config.get("env").and_then(|v| {
-        v.as_object().map(|obj| {
-            obj.iter()
-                .filter_map(|(k, v)| value_to_string(v).map(|s| (k.clone(), s)))
-                .collect::<HashMap<String, String>>()
-        })
-    });
-
-    let url = server_config
-        .get("url")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-
-    McpServer {
-        name: name.to_string(),
-        command,
-        args,
-        env,
-        disabled: None,
-        url,
-        target: None,
-        extra: None,
-    }
-}
-
-fn value_to_string(v: &Value) -> Option<String> {
-    match v {
-        Value::String(s) => Some(s.clone()),
-        Value::Number(n) => Some(n.to_string()),
-        Value::Bool(b) => Some(b.to_string()),
-        Value::Null => None,
-        Value::Array(_) | Value::Object(_) => None,
-    }
-}
-
 // Tauri command
 #[tauri::command]
 pub fn import_mcp_config_file(file_path: String) -> Result<ImportResult, McpError> {
@@ -250,8 +67,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Standard);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "standard");
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].name, "filesystem");
         assert_eq!(servers[0].command, "npx");
@@ -268,8 +85,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Amp);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "amp");
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].name, "my-server");
     }
@@ -285,8 +102,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Copilot);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "copilot");
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].name, "copilot-server");
     }
@@ -306,8 +123,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Opencode);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "opencode");
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].name, "opencode-server");
         assert_eq!(servers[0].command, "opencode-mcp");
@@ -317,14 +134,14 @@ mod tests {
     #[test]
     fn test_parse_invalid_json() {
         let json = "not valid json";
-        let result = parse_mcp_config_from_content(json);
+        let result = parse_mcp_config_from_content(json, &ConfigFormat::Json);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_parse_no_mcp_config() {
         let json = r#"{"someOtherKey": "value"}"#;
-        let result = parse_mcp_config_from_content(json);
+        let result = parse_mcp_config_from_content(json, &ConfigFormat::Json);
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(err.contains("No recognized MCP config format found"));
@@ -333,7 +150,7 @@ mod tests {
     #[test]
     fn test_parse_empty_servers() {
         let json = r#"{"mcpServers": {}}"#;
-        let result = parse_mcp_config_from_content(json);
+        let result = parse_mcp_config_from_content(json, &ConfigFormat::Json);
         assert!(result.is_err());
     }
 
@@ -351,7 +168,7 @@ mod tests {
             }
         }"#;
 
-        let (servers, _) = parse_mcp_config_from_content(json).unwrap();
+        let (servers, _) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
         assert_eq!(servers.len(), 1);
         let env = servers[0].env.as_ref().unwrap();
         assert_eq!(env.get("API_KEY"), Some(&"secret".to_string()));
@@ -374,8 +191,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Copilot);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "copilot");
         assert_eq!(servers.len(), 1);
         let env = servers[0].env.as_ref().unwrap();
         assert_eq!(env.get("PORT"), Some(&"3000".to_string()));
@@ -394,7 +211,7 @@ mod tests {
             }
         }"#;
 
-        let (servers, _) = parse_mcp_config_from_content(json).unwrap();
+        let (servers, _) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
         assert_eq!(servers.len(), 3);
     }
 
@@ -408,8 +225,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Copilot);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "copilot");
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].url, Some("http://localhost:3000/sse".to_string()));
         assert_eq!(servers[0].command, "");
@@ -428,8 +245,8 @@ mod tests {
             }
         }"#;
 
-        let (servers, format) = parse_mcp_config_from_content(json).unwrap();
-        assert_eq!(format, DetectedFormat::Opencode);
+        let (servers, format) = parse_mcp_config_from_content(json, &ConfigFormat::Json).unwrap();
+        assert_eq!(format, "opencode");
         assert_eq!(servers.len(), 1);
         assert_eq!(servers[0].url, Some("https://api.example.com/mcp".to_string()));
     }
@@ -0,0 +1,92 @@
+use crate::commands::expand_path;
+use crate::config::ConfigFormat;
+use crate::formats::resolve_format;
+use crate::mcp::types::{McpError, McpToolInfo};
+use crate::ssh::{ssh_file_exists, ssh_read_file, SshConnection, SshError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where a tool's MCP config lives. A `config_path` of the form
+/// `user@host:path` targets the tool on a remote dev box over SSH.
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpToolLocation {
+    Local(PathBuf),
+    Remote(SshConnection),
+}
+
+impl McpToolLocation {
+    pub fn of(tool_info: &McpToolInfo) -> Result<Self, McpError> {
+        if let Some(conn) = SshConnection::parse_remote(&tool_info.config_path) {
+            return Ok(McpToolLocation::Remote(conn));
+        }
+
+        expand_path(&tool_info.config_path)
+            .map(McpToolLocation::Local)
+            .ok_or_else(|| {
+                McpError::NotFound(format!("Could not expand {}", tool_info.config_path))
+            })
+    }
+
+    /// File format, from the extension; JSON when there is none
+    pub fn format(&self) -> ConfigFormat {
+        match self {
+            McpToolLocation::Local(path) => resolve_format(None, path),
+            McpToolLocation::Remote(conn) => resolve_format(None, Path::new(&conn.path)),
+        }
+    }
+
+    pub fn exists(&self) -> Result<bool, McpError> {
+        match self {
+            McpToolLocation::Local(path) => Ok(path.exists()),
+            McpToolLocation::Remote(conn) => Ok(ssh_file_exists(conn)?),
+        }
+    }
+
+    /// Current file content, or `None` if the file does not exist yet.
+    pub fn read(&self) -> Result<Option<String>, McpError> {
+        match self {
+            McpToolLocation::Local(path) if path.exists() => Ok(Some(fs::read_to_string(path)?)),
+            McpToolLocation::Local(_) => Ok(None),
+            McpToolLocation::Remote(conn) => match ssh_read_file(conn) {
+                Ok(content) => Ok(Some(content)),
+                Err(SshError::FileNotFound(_)) => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::McpToolFormat;
+
+    fn tool_at(config_path: &str) -> McpToolInfo {
+        McpToolInfo {
+            tool_id: "test".to_string(),
+            config_path: config_path.to_string(),
+            json_path: "mcpServers".to_string(),
+            format: McpToolFormat::default(),
+            name: "Test".to_string(),
+            project_config_path: None,
+            workspace: None,
+        }
+    }
+
+    #[test]
+    fn test_location_of_remote_and_local() {
+        match McpToolLocation::of(&tool_at("dev@box:2222:~/.claude.json")).unwrap() {
+            McpToolLocation::Remote(conn) => {
+                assert_eq!(conn.ssh_target(), "dev@box");
+                assert_eq!(conn.port, Some(2222));
+                assert_eq!(conn.path, "~/.claude.json");
+            }
+            other => panic!("expected remote, got {:?}", other),
+        }
+
+        assert!(matches!(
+            McpToolLocation::of(&tool_at("~/.claude.json")).unwrap(),
+            McpToolLocation::Local(_)
+        ));
+    }
+}
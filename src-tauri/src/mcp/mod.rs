@@ -1,17 +1,27 @@
 mod storage;
 mod types;
+mod adapters;
 mod converters;
 mod sync;
 mod import;
+mod location;
+mod auto_sync;
+mod probe;
+mod claude_projects;
 
 pub use storage::*;
 pub use converters::*;
 pub use sync::*;
 pub use import::*;
+pub use auto_sync::*;
+pub use probe::*;
+pub use claude_projects::*;
 
 #[allow(unused_imports)]
 pub use types::{
-    McpServer, McpSourceMode, McpConfig, McpToolFormat, McpToolInfo,
+    McpError, McpServer, McpSourceMode, McpConfig, McpToolFormat, McpToolInfo,
     McpSyncPreview, McpServerConflict, McpMergeResult, McpConfigPreview,
     McpConflictResolution, McpSyncResult, McpToolStatus, McpSyncStatus,
+    McpAutoSyncAction, McpSyncLog, McpSyncLogEntry, ClaudeProject,
+    get_mcp_tool_definitions,
 };
@@ -0,0 +1,964 @@
+use crate::app_paths::AppPaths;
+use crate::mcp::types::{McpError, McpServer};
+use crate::secrets::resolve_server_secrets;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const PROTOCOL_VERSION: &str = "2025-03-26";
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+/// Cap on captured stderr so a chatty server can't grow the result unbounded
+const MAX_STDERR_BYTES: usize = 16 * 1024;
+/// Stop following `tools/list` cursors after this many pages
+const MAX_TOOL_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpProbeTransport {
+    Stdio,
+    /// Streamable HTTP: JSON-RPC POSTed to the server URL
+    Http,
+    /// Legacy HTTP+SSE: an event stream that announces a POST endpoint
+    Sse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProbeTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Outcome of starting a server and running the MCP handshake against it.
+/// A server that fails to start or answer is reported with `ok: false` and
+/// an `error`, together with whatever it printed to stderr.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProbeResult {
+    pub server_name: String,
+    pub transport: McpProbeTransport,
+    pub ok: bool,
+    /// Time from launch until the handshake finished or failed
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_info_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    #[serde(default)]
+    pub capabilities: Value,
+    #[serde(default)]
+    pub tools: Vec<McpProbeTool>,
+    #[serde(default)]
+    pub stderr: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A JSON-RPC channel to a running server.
+trait Transport {
+    fn request(
+        &mut self,
+        id: u64,
+        method: &str,
+        params: Value,
+        deadline: Instant,
+    ) -> Result<Value, String>;
+    fn notify(&mut self, method: &str, params: Value, deadline: Instant) -> Result<(), String>;
+    /// Shut the server down and return what it wrote to stderr.
+    fn finish(&mut self) -> String {
+        String::new()
+    }
+}
+
+fn message(id: Option<u64>, method: &str, params: Value) -> Value {
+    let mut message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+    if let Some(id) = id {
+        message["id"] = json!(id);
+    }
+    message
+}
+
+/// The `result` of a response, or its `error` as text.
+fn response_result(response: Value) -> Result<Value, String> {
+    if let Some(error) = response.get("error") {
+        let text = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(match error.get("code") {
+            Some(code) => format!("Server error {}: {}", code, text),
+            None => format!("Server error: {}", text),
+        });
+    }
+    Ok(response.get("result").cloned().unwrap_or(Value::Null))
+}
+
+fn is_response_to(message: &Value, id: u64) -> bool {
+    message.get("method").is_none() && message.get("id").and_then(Value::as_u64) == Some(id)
+}
+
+fn remaining(deadline: Instant) -> Result<Duration, String> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| "Timed out waiting for the server".to_string())
+}
+
+/// Wait on `messages` for the response to request `id`.
+fn await_response(
+    messages: &Receiver<Value>,
+    id: u64,
+    deadline: Instant,
+    closed: &str,
+) -> Result<Value, String> {
+    loop {
+        match messages.recv_timeout(remaining(deadline)?) {
+            Ok(message) if is_response_to(&message, id) => return response_result(message),
+            // Notifications and server-initiated requests are not needed for a probe
+            Ok(_) => continue,
+            Err(RecvTimeoutError::Timeout) => {
+                return Err("Timed out waiting for the server".to_string())
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err(closed.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// stdio
+// ============================================================================
+
+struct StdioTransport {
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<Value>,
+    stderr: Arc<Mutex<String>>,
+    stderr_done: Receiver<()>,
+}
+
+impl StdioTransport {
+    fn spawn(server: &McpServer) -> Result<Self, String> {
+        let mut command = if cfg!(windows) {
+            // npx, uvx and friends are batch files that need the shell to resolve
+            let mut command = Command::new("cmd");
+            command.arg("/C").arg(&server.command);
+            command
+        } else {
+            Command::new(&server.command)
+        };
+        command
+            .args(server.args.iter().flatten())
+            .envs(server.env.iter().flatten())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to start '{}': {}", server.command, e))?;
+
+        let stdin = child.stdin.take().ok_or("Server stdin is unavailable")?;
+        let stdout = child.stdout.take().ok_or("Server stdout is unavailable")?;
+        let stderr_pipe = child.stderr.take().ok_or("Server stderr is unavailable")?;
+
+        let (sender, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                // Servers that log to stdout break the protocol; skip what isn't JSON
+                if let Ok(message) = serde_json::from_str::<Value>(line.trim()) {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let stderr = Arc::new(Mutex::new(String::new()));
+        let captured = Arc::clone(&stderr);
+        let (done, stderr_done) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stderr_pipe);
+            let mut buffer = [0u8; 4096];
+            while let Ok(read) = reader.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                let mut captured = captured.lock().unwrap_or_else(|e| e.into_inner());
+                if captured.len() < MAX_STDERR_BYTES {
+                    captured.push_str(&String::from_utf8_lossy(&buffer[..read]));
+                }
+            }
+            let _ = done.send(());
+        });
+
+        Ok(StdioTransport {
+            child,
+            stdin,
+            messages,
+            stderr,
+            stderr_done,
+        })
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        writeln!(self.stdin, "{}", message)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("Failed to write to the server: {}", e))
+    }
+}
+
+impl Transport for StdioTransport {
+    fn request(
+        &mut self,
+        id: u64,
+        method: &str,
+        params: Value,
+        deadline: Instant,
+    ) -> Result<Value, String> {
+        self.send(&message(Some(id), method, params))?;
+        await_response(
+            &self.messages,
+            id,
+            deadline,
+            "Server exited before responding",
+        )
+    }
+
+    fn notify(&mut self, method: &str, params: Value, _deadline: Instant) -> Result<(), String> {
+        self.send(&message(None, method, params))
+    }
+
+    fn finish(&mut self) -> String {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        // Give the reader a moment to drain the pipe; a grandchild holding it
+        // open must not stall the probe
+        let _ = self.stderr_done.recv_timeout(Duration::from_millis(200));
+        let mut stderr = self
+            .stderr
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if stderr.len() > MAX_STDERR_BYTES {
+            let mut end = MAX_STDERR_BYTES;
+            while !stderr.is_char_boundary(end) {
+                end -= 1;
+            }
+            stderr.truncate(end);
+        }
+        stderr
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+/// Dispatch `(event, data)` pairs from a `text/event-stream` body until the
+/// stream ends or `on_event` returns false.
+fn read_sse_events(reader: impl BufRead, mut on_event: impl FnMut(&str, &str) -> bool) {
+    let mut event = String::new();
+    let mut data: Vec<String> = Vec::new();
+    for line in reader.lines() {
+        let Ok(line) = line else { return };
+        if line.is_empty() {
+            if !data.is_empty() {
+                let name = if event.is_empty() {
+                    "message"
+                } else {
+                    event.as_str()
+                };
+                if !on_event(name, &data.join("\n")) {
+                    return;
+                }
+            }
+            event.clear();
+            data.clear();
+        } else if let Some(value) = line.strip_prefix("event:") {
+            event = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+        }
+    }
+}
+
+fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .http_status_as_error(false)
+        .build()
+        .into()
+}
+
+/// Headers configured on the server entry (`"headers": {..}`).
+fn server_headers(server: &McpServer) -> Vec<(String, String)> {
+    server
+        .extra
+        .as_ref()
+        .and_then(|extra| extra.get("headers"))
+        .and_then(Value::as_object)
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn http_error(status: u16, body: &str) -> String {
+    let body = body.trim();
+    if body.is_empty() {
+        format!("HTTP {}", status)
+    } else {
+        format!(
+            "HTTP {}: {}",
+            status,
+            body.chars().take(500).collect::<String>()
+        )
+    }
+}
+
+/// Resolve an endpoint announced by a legacy SSE server against the stream URL.
+fn resolve_endpoint(base: &str, endpoint: &str) -> String {
+    if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+        return endpoint.to_string();
+    }
+    let origin_end = base
+        .find("://")
+        .map(|scheme| {
+            base[scheme + 3..]
+                .find('/')
+                .map(|i| scheme + 3 + i)
+                .unwrap_or(base.len())
+        })
+        .unwrap_or(0);
+    if endpoint.starts_with('/') {
+        return format!("{}{}", &base[..origin_end], endpoint);
+    }
+    let path = base.split(['?', '#']).next().unwrap_or(base);
+    let dir_end = path.rfind('/').filter(|&i| i >= origin_end).map(|i| i + 1);
+    match dir_end {
+        Some(end) => format!("{}{}", &path[..end], endpoint),
+        None => format!("{}/{}", path, endpoint),
+    }
+}
+
+/// Streamable HTTP: every message is a POST, answered with JSON or an SSE stream.
+struct HttpTransport {
+    agent: ureq::Agent,
+    url: String,
+    headers: Vec<(String, String)>,
+    session_id: Option<String>,
+}
+
+impl HttpTransport {
+    fn post(&mut self, message: &Value) -> Result<ureq::http::Response<ureq::Body>, String> {
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream");
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        if let Some(session_id) = &self.session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        let mut response = request
+            .send(message.to_string())
+            .map_err(|e| e.to_string())?;
+
+        let status = response.status().as_u16();
+        if !(200..300).contains(&status) {
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            return Err(http_error(status, &body));
+        }
+        if let Some(session_id) = response
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|v| v.to_str().ok())
+        {
+            self.session_id = Some(session_id.to_string());
+        }
+        Ok(response)
+    }
+}
+
+impl Transport for HttpTransport {
+    fn request(
+        &mut self,
+        id: u64,
+        method: &str,
+        params: Value,
+        _deadline: Instant,
+    ) -> Result<Value, String> {
+        let mut response = self.post(&message(Some(id), method, params))?;
+        let is_stream = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+
+        if is_stream {
+            let mut found = None;
+            read_sse_events(
+                BufReader::new(response.into_body().into_reader()),
+                |_, data| match serde_json::from_str::<Value>(data) {
+                    Ok(message) if is_response_to(&message, id) => {
+                        found = Some(message);
+                        false
+                    }
+                    _ => true,
+                },
+            );
+            return found
+                .ok_or_else(|| format!("Stream ended without a response to '{}'", method))
+                .and_then(response_result);
+        }
+
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| e.to_string())?;
+        let parsed: Value = serde_json::from_str(&body)
+            .map_err(|e| format!("Invalid JSON response to '{}': {}", method, e))?;
+        // A batch response is an array; pick ours out of it
+        let found = match parsed {
+            Value::Array(messages) => messages.into_iter().find(|m| is_response_to(m, id)),
+            message => Some(message),
+        };
+        found
+            .ok_or_else(|| format!("No response to '{}'", method))
+            .and_then(response_result)
+    }
+
+    fn notify(&mut self, method: &str, params: Value, _deadline: Instant) -> Result<(), String> {
+        self.post(&message(None, method, params)).map(|_| ())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        // End the session so the server can free it; servers may not support this
+        if let Some(session_id) = &self.session_id {
+            let _ = self
+                .agent
+                .delete(&self.url)
+                .header("Mcp-Session-Id", session_id)
+                .call();
+        }
+    }
+}
+
+/// Legacy HTTP+SSE: a long-lived GET stream carries the responses to
+/// messages POSTed to the endpoint it announces first.
+struct SseTransport {
+    agent: ureq::Agent,
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    messages: Receiver<Value>,
+}
+
+impl SseTransport {
+    fn connect(
+        server: &McpServer,
+        url: &str,
+        timeout: Duration,
+        deadline: Instant,
+    ) -> Result<Self, String> {
+        let agent = agent(timeout);
+        let headers = server_headers(server);
+
+        let mut request = agent.get(url).header("Accept", "text/event-stream");
+        for (key, value) in &headers {
+            request = request.header(key, value);
+        }
+
+        let (endpoint_sender, endpoint_receiver) = mpsc::channel::<Result<String, String>>();
+        let (sender, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            let response = match request.call() {
+                Ok(response) if response.status().is_success() => response,
+                Ok(mut response) => {
+                    let body = response.body_mut().read_to_string().unwrap_or_default();
+                    let _ =
+                        endpoint_sender.send(Err(http_error(response.status().as_u16(), &body)));
+                    return;
+                }
+                Err(e) => {
+                    let _ = endpoint_sender.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            read_sse_events(
+                BufReader::new(response.into_body().into_reader()),
+                |event, data| {
+                    if event == "endpoint" {
+                        let _ = endpoint_sender.send(Ok(data.trim().to_string()));
+                        true
+                    } else if let Ok(message) = serde_json::from_str::<Value>(data) {
+                        sender.send(message).is_ok()
+                    } else {
+                        true
+                    }
+                },
+            );
+        });
+
+        let endpoint = match endpoint_receiver.recv_timeout(remaining(deadline)?) {
+            Ok(endpoint) => endpoint?,
+            Err(RecvTimeoutError::Timeout) => {
+                return Err("Timed out waiting for the SSE endpoint".to_string())
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err("SSE stream closed before announcing an endpoint".to_string())
+            }
+        };
+
+        Ok(SseTransport {
+            agent,
+            endpoint: resolve_endpoint(url, &endpoint),
+            headers,
+            messages,
+        })
+    }
+
+    fn post(&self, message: &Value) -> Result<(), String> {
+        let mut request = self
+            .agent
+            .post(&self.endpoint)
+            .header("Content-Type", "application/json");
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        let mut response = request
+            .send(message.to_string())
+            .map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        if !(200..300).contains(&status) {
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            return Err(http_error(status, &body));
+        }
+        Ok(())
+    }
+}
+
+impl Transport for SseTransport {
+    fn request(
+        &mut self,
+        id: u64,
+        method: &str,
+        params: Value,
+        deadline: Instant,
+    ) -> Result<Value, String> {
+        self.post(&message(Some(id), method, params))?;
+        await_response(
+            &self.messages,
+            id,
+            deadline,
+            "SSE stream closed before responding",
+        )
+    }
+
+    fn notify(&mut self, method: &str, params: Value, _deadline: Instant) -> Result<(), String> {
+        self.post(&message(None, method, params))
+    }
+}
+
+// ============================================================================
+// Handshake
+// ============================================================================
+
+fn transport_for(server: &McpServer) -> Result<McpProbeTransport, McpError> {
+    if let Some(url) = server.url.as_deref().filter(|u| !u.is_empty()) {
+        let declared = server
+            .extra
+            .as_ref()
+            .and_then(|e| e.get("type"))
+            .and_then(Value::as_str);
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        return Ok(
+            if declared == Some("sse") || path.trim_end_matches('/').ends_with("/sse") {
+                McpProbeTransport::Sse
+            } else {
+                McpProbeTransport::Http
+            },
+        );
+    }
+    if server.command.is_empty() {
+        return Err(McpError::InvalidFormat(format!(
+            "Server '{}' has neither a command nor a url",
+            server.name
+        )));
+    }
+    Ok(McpProbeTransport::Stdio)
+}
+
+fn handshake(
+    transport: &mut dyn Transport,
+    result: &mut McpProbeResult,
+    deadline: Instant,
+) -> Result<(), String> {
+    let init = transport.request(
+        1,
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "cli-config-editor", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        deadline,
+    )?;
+    result.protocol_version = init
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .map(String::from);
+    result.server_info_name = init
+        .pointer("/serverInfo/name")
+        .and_then(Value::as_str)
+        .map(String::from);
+    result.server_version = init
+        .pointer("/serverInfo/version")
+        .and_then(Value::as_str)
+        .map(String::from);
+    result.capabilities = init
+        .get("capabilities")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    transport.notify("notifications/initialized", json!({}), deadline)?;
+
+    if result.capabilities.get("tools").is_none() {
+        return Ok(());
+    }
+    let mut cursor: Option<String> = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let listed = transport.request(2 + page as u64, "tools/list", params, deadline)?;
+        for tool in listed
+            .get("tools")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(name) = tool.get("name").and_then(Value::as_str) {
+                result.tools.push(McpProbeTool {
+                    name: name.to_string(),
+                    description: tool
+                        .get("description")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                });
+            }
+        }
+        cursor = listed
+            .get("nextCursor")
+            .and_then(Value::as_str)
+            .map(String::from);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Start `server` (or connect to it), run `initialize` and `tools/list`, and
+/// report what it answered. Handshake failures land in the result; only a
+/// server entry that can't be probed at all is an error.
+pub fn probe_mcp_server_internal(
+    app: &impl AppPaths,
+    server: &McpServer,
+    timeout: Duration,
+) -> Result<McpProbeResult, McpError> {
+    let kind = transport_for(server)?;
+    let server = resolve_server_secrets(app, std::slice::from_ref(server))?.remove(0);
+
+    let started = Instant::now();
+    let deadline = started + timeout;
+    let mut result = McpProbeResult {
+        server_name: server.name.clone(),
+        transport: kind,
+        ok: false,
+        latency_ms: 0,
+        protocol_version: None,
+        server_info_name: None,
+        server_version: None,
+        capabilities: Value::Null,
+        tools: Vec::new(),
+        stderr: String::new(),
+        error: None,
+    };
+
+    let url = server.url.clone().unwrap_or_default();
+    let transport: Result<Box<dyn Transport>, String> = match kind {
+        McpProbeTransport::Stdio => {
+            StdioTransport::spawn(&server).map(|t| Box::new(t) as Box<dyn Transport>)
+        }
+        McpProbeTransport::Http => Ok(Box::new(HttpTransport {
+            agent: agent(timeout),
+            url,
+            headers: server_headers(&server),
+            session_id: None,
+        })),
+        McpProbeTransport::Sse => SseTransport::connect(&server, &url, timeout, deadline)
+            .map(|t| Box::new(t) as Box<dyn Transport>),
+    };
+
+    let outcome = transport.and_then(|mut transport| {
+        let outcome = handshake(transport.as_mut(), &mut result, deadline);
+        result.latency_ms = started.elapsed().as_millis() as u64;
+        result.stderr = transport.finish();
+        outcome
+    });
+    match outcome {
+        Ok(()) => result.ok = true,
+        Err(error) => {
+            if result.latency_ms == 0 {
+                result.latency_ms = started.elapsed().as_millis() as u64;
+            }
+            result.error = Some(error);
+        }
+    }
+    Ok(result)
+}
+
+/// Runs off the main thread: a probe blocks for up to its timeout, and a
+/// plain command would freeze the window for that long.
+#[tauri::command(async)]
+pub fn probe_mcp_server(
+    app: AppHandle,
+    server: McpServer,
+    timeout_ms: Option<u64>,
+) -> Result<McpProbeResult, McpError> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    probe_mcp_server_internal(&app, &server, timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use std::collections::HashMap;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+
+    fn paths(name: &str) -> (StandalonePaths, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("mcp_probe_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        (
+            StandalonePaths {
+                data_dir: Some(dir.clone()),
+                config_dir: Some(dir.clone()),
+            },
+            dir,
+        )
+    }
+
+    fn stdio_server(script: &str) -> McpServer {
+        McpServer {
+            name: "stub".to_string(),
+            command: "sh".to_string(),
+            args: Some(vec!["-c".to_string(), script.to_string()]),
+            env: Some(HashMap::from([(
+                "STUB_NAME".to_string(),
+                "stub-server".to_string(),
+            )])),
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }
+    }
+
+    /// Answers `initialize` and `tools/list` by echoing the request id back.
+    const STUB_SERVER: &str = r#"
+echo "stub starting" >&2
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *'"initialize"'*)
+      printf '{"jsonrpc":"2.0","method":"notifications/message","params":{}}\n'
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-03-26","capabilities":{"tools":{}},"serverInfo":{"name":"%s","version":"1.2.3"}}}\n' "$id" "$STUB_NAME" ;;
+    *'"tools/list"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"echo","description":"Echo input"},{"name":"ping"}]}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_stdio_server() {
+        let (paths, dir) = paths("stdio");
+
+        let result =
+            probe_mcp_server_internal(&paths, &stdio_server(STUB_SERVER), Duration::from_secs(10))
+                .unwrap();
+        assert!(result.ok, "{:?}", result.error);
+        assert_eq!(result.transport, McpProbeTransport::Stdio);
+        assert_eq!(result.server_info_name.as_deref(), Some("stub-server"));
+        assert_eq!(result.server_version.as_deref(), Some("1.2.3"));
+        assert_eq!(result.protocol_version.as_deref(), Some("2025-03-26"));
+        assert!(result.capabilities.get("tools").is_some());
+        let names: Vec<&str> = result.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["echo", "ping"]);
+        assert_eq!(result.tools[0].description.as_deref(), Some("Echo input"));
+        assert!(result.stderr.contains("stub starting"));
+
+        // A server that never answers times out with its stderr attached
+        let silent = stdio_server("echo 'waiting forever' >&2; cat > /dev/null");
+        let result =
+            probe_mcp_server_internal(&paths, &silent, Duration::from_millis(300)).unwrap();
+        assert!(!result.ok);
+        assert!(result.error.unwrap().contains("Timed out"));
+        assert!(result.stderr.contains("waiting forever"));
+
+        // A command that can't be started is reported, not raised
+        let mut missing = stdio_server("");
+        missing.command = "definitely-not-a-real-mcp-server".to_string();
+        missing.args = None;
+        let result = probe_mcp_server_internal(&paths, &missing, Duration::from_secs(1)).unwrap();
+        assert!(!result.ok);
+        assert!(result.error.unwrap().contains("Failed to start"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn read_http_request(stream: &mut std::net::TcpStream) -> (String, String) {
+        let mut data = Vec::new();
+        let mut buffer = [0u8; 1024];
+        let header_end = loop {
+            let read = stream.read(&mut buffer).unwrap();
+            data.extend_from_slice(&buffer[..read]);
+            if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+        let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+        let length: usize = head
+            .lines()
+            .find_map(|l| {
+                l.to_ascii_lowercase()
+                    .strip_prefix("content-length:")
+                    .map(|v| v.trim().parse().unwrap())
+            })
+            .unwrap_or(0);
+        while data.len() < header_end + length {
+            let read = stream.read(&mut buffer).unwrap();
+            data.extend_from_slice(&buffer[..read]);
+        }
+        (
+            head,
+            String::from_utf8_lossy(&data[header_end..header_end + length]).to_string(),
+        )
+    }
+
+    #[test]
+    fn test_probe_http_server() {
+        let (paths, dir) = paths("http");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let (head, body) = read_http_request(&mut stream);
+                let request: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+                let (status, content_type, reply) = match request
+                    .get("method")
+                    .and_then(Value::as_str)
+                {
+                    Some("initialize") => {
+                        assert!(head.contains("Bearer token"));
+                        let result = json!({
+                            "jsonrpc": "2.0", "id": request["id"],
+                            "result": {
+                                "protocolVersion": "2025-03-26",
+                                "capabilities": { "tools": { "listChanged": true } },
+                                "serverInfo": { "name": "http-stub", "version": "0.9.0" },
+                            },
+                        });
+                        ("200 OK", "application/json", result.to_string())
+                    }
+                    Some("tools/list") => {
+                        assert!(head
+                            .to_ascii_lowercase()
+                            .contains("mcp-session-id: session-1"));
+                        let result = json!({ "jsonrpc": "2.0", "id": request["id"], "result": { "tools": [{ "name": "search" }] } });
+                        (
+                            "200 OK",
+                            "text/event-stream",
+                            format!("event: message\ndata: {}\n\n", result),
+                        )
+                    }
+                    _ => ("202 Accepted", "application/json", String::new()),
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: {}\r\nMcp-Session-Id: session-1\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    content_type,
+                    reply.len(),
+                    reply
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let server = McpServer {
+            name: "remote".to_string(),
+            command: String::new(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: Some(url),
+            target: None,
+            extra: Some(HashMap::from([(
+                "headers".to_string(),
+                json!({ "Authorization": "Bearer token" }),
+            )])),
+        };
+        let result = probe_mcp_server_internal(&paths, &server, Duration::from_secs(10)).unwrap();
+        assert!(result.ok, "{:?}", result.error);
+        assert_eq!(result.transport, McpProbeTransport::Http);
+        assert_eq!(result.server_info_name.as_deref(), Some("http-stub"));
+        assert_eq!(result.server_version.as_deref(), Some("0.9.0"));
+        assert_eq!(result.tools.len(), 1);
+        assert_eq!(result.tools[0].name, "search");
+
+        // Legacy SSE servers announce where to POST relative to the stream
+        assert_eq!(
+            resolve_endpoint("http://h:1/sse", "/messages?s=1"),
+            "http://h:1/messages?s=1"
+        );
+        assert_eq!(
+            resolve_endpoint("http://h:1/mcp/sse", "messages"),
+            "http://h:1/mcp/messages"
+        );
+        assert_eq!(
+            resolve_endpoint("http://h:1/sse", "https://other/m"),
+            "https://other/m"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,20 +1,22 @@
+use crate::app_paths::AppPaths;
 use crate::commands::expand_path;
-use crate::mcp::types::{McpConfig, McpError, McpServer, McpSourceMode};
+use crate::formats::jsonc;
+use crate::mcp::adapters::{McpFormatAdapter, StandardAdapter};
+use crate::mcp::types::{
+    McpConfig, McpError, McpServer, McpSourceMode, McpSyncLog, McpSyncSnapshot,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-fn get_mcp_config_path(app: &AppHandle) -> Result<PathBuf, McpError> {
-    let app_data = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| McpError::AppDataDir)?;
+pub(crate) fn get_mcp_config_path(app: &impl AppPaths) -> Result<PathBuf, McpError> {
+    let app_data = app.app_data_dir().ok_or(McpError::AppDataDir)?;
     Ok(app_data.join("mcp-config.json"))
 }
 
-pub fn load_mcp_config_internal(app: &AppHandle) -> Result<McpConfig, McpError> {
+pub fn load_mcp_config_internal(app: &impl AppPaths) -> Result<McpConfig, McpError> {
     let config_path = get_mcp_config_path(app)?;
 
     if !config_path.exists() {
@@ -26,7 +28,7 @@ pub fn load_mcp_config_internal(app: &AppHandle) -> Result<McpConfig, McpError>
     Ok(config)
 }
 
-pub fn save_mcp_config_internal(app: &AppHandle, config: &McpConfig) -> Result<(), McpError> {
+pub fn save_mcp_config_internal(app: &impl AppPaths, config: &McpConfig) -> Result<(), McpError> {
     let config_path = get_mcp_config_path(app)?;
 
     // Ensure parent directory exists
@@ -39,146 +41,133 @@ pub fn save_mcp_config_internal(app: &AppHandle, config: &McpConfig) -> Result<(
     Ok(())
 }
 
-pub fn read_claude_mcp_servers_internal() -> Result<Vec<McpServer>, McpError> {
-    let claude_path = expand_path("~/.claude.json")
-        .ok_or_else(|| McpError::NotFound("Could not expand ~/.claude.json".to_string()))?;
+fn get_sync_state_path(app: &impl AppPaths) -> Result<PathBuf, McpError> {
+    let app_data = app.app_data_dir().ok_or(McpError::AppDataDir)?;
+    Ok(app_data.join("mcp-sync-state.json"))
+}
 
-    if !claude_path.exists() {
-        return Ok(Vec::new());
+fn load_sync_state(app: &impl AppPaths) -> Result<HashMap<String, McpSyncSnapshot>, McpError> {
+    let state_path = get_sync_state_path(app)?;
+
+    if !state_path.exists() {
+        return Ok(HashMap::new());
     }
 
-    let content = fs::read_to_string(&claude_path)?;
-    let root: Value = serde_json::from_str(&content)?;
+    let content = fs::read_to_string(&state_path)?;
+    Ok(serde_json::from_str(&content)?)
+}
 
-    parse_standard_mcp_servers(&root, "mcpServers")
+/// The servers written to `tool_id` by its last sync, if it was ever synced.
+pub fn load_sync_snapshot(
+    app: &impl AppPaths,
+    tool_id: &str,
+) -> Result<Option<McpSyncSnapshot>, McpError> {
+    Ok(load_sync_state(app)?.remove(tool_id))
 }
 
-pub fn parse_standard_mcp_servers(root: &Value, key: &str) -> Result<Vec<McpServer>, McpError> {
-    let mcp_servers = match root.get(key) {
-        Some(Value::Object(servers)) => servers,
-        Some(_) => return Err(McpError::InvalidFormat(format!("{} is not an object", key))),
-        None => return Ok(Vec::new()),
-    };
-
-    let mut result = Vec::new();
-
-    // Known fields that we handle explicitly
-    let known_fields = ["command", "args", "env", "disabled", "url", "_target"];
-
-    for (name, config) in mcp_servers {
-        if let Value::Object(server_config) = config {
-            let command = server_config
-                .get("command")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let args = server_config.get("args").and_then(|v| {
-                v.as_array().map(|arr| {
-                    arr.iter()
-                        .filter_map(|item| item.as_str().map(|s| s.to_string()))
-                        .collect()
-                })
-            });
-
-            let env = server_config.get("env").and_then(|v| {
-                v.as_object().map(|obj| {
-                    obj.iter()
-                        .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                        .collect::<HashMap<String, String>>()
-                })
-            });
-
-            let disabled = server_config
-                .get("disabled")
-                .and_then(|v| v.as_bool());
-
-            let url = server_config
-                .get("url")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            let target = server_config
-                .get("_target")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            // Capture any extra fields we don't explicitly handle
-            let extra: HashMap<String, Value> = server_config
-                .iter()
-                .filter(|(k, _)| !known_fields.contains(&k.as_str()))
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect();
-
-            let extra = if extra.is_empty() { None } else { Some(extra) };
-
-            result.push(McpServer {
-                name: name.clone(),
-                command,
-                args,
-                env,
-                disabled,
-                url,
-                target,
-                extra,
-            });
-        }
+pub fn save_sync_snapshot(
+    app: &impl AppPaths,
+    tool_id: &str,
+    servers: &[McpServer],
+) -> Result<(), McpError> {
+    let state_path = get_sync_state_path(app)?;
+    let mut state = load_sync_state(app)?;
+
+    state.insert(
+        tool_id.to_string(),
+        McpSyncSnapshot {
+            servers: servers.to_vec(),
+            synced_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        },
+    );
+
+    if let Some(parent) = state_path.parent() {
+        fs::create_dir_all(parent)?;
     }
+    fs::write(&state_path, serde_json::to_string_pretty(&state)?)?;
+    Ok(())
+}
+
+/// Auto-sync log entries kept on disk
+const MAX_SYNC_LOG_ENTRIES: usize = 500;
 
-    Ok(result)
+fn get_sync_log_path(app: &impl AppPaths) -> Result<PathBuf, McpError> {
+    let app_data = app.app_data_dir().ok_or(McpError::AppDataDir)?;
+    Ok(app_data.join("mcp-sync-log.json"))
 }
 
-pub fn servers_to_standard_format(servers: &[McpServer]) -> Value {
-    let mut map = serde_json::Map::new();
+pub fn load_sync_log(app: &impl AppPaths) -> Result<McpSyncLog, McpError> {
+    let log_path = get_sync_log_path(app)?;
 
-    for server in servers {
-        let mut server_obj = serde_json::Map::new();
-        
-        // Only add command if it's not empty (for URL-based servers)
-        if !server.command.is_empty() {
-            server_obj.insert("command".to_string(), Value::String(server.command.clone()));
-        }
+    if !log_path.exists() {
+        return Ok(McpSyncLog::default());
+    }
 
-        if let Some(args) = &server.args {
-            server_obj.insert(
-                "args".to_string(),
-                Value::Array(args.iter().map(|s| Value::String(s.clone())).collect()),
-            );
-        }
+    let content = fs::read_to_string(&log_path)?;
+    Ok(serde_json::from_str(&content)?)
+}
 
-        if let Some(env) = &server.env {
-            let env_obj: serde_json::Map<String, Value> = env
-                .iter()
-                .map(|(k, v)| (k.clone(), Value::String(v.clone())))
-                .collect();
-            server_obj.insert("env".to_string(), Value::Object(env_obj));
-        }
+pub fn save_sync_log(app: &impl AppPaths, log: &mut McpSyncLog) -> Result<(), McpError> {
+    let log_path = get_sync_log_path(app)?;
 
-        if let Some(disabled) = server.disabled {
-            if disabled {
-                server_obj.insert("disabled".to_string(), Value::Bool(disabled));
-            }
-        }
+    let excess = log.entries.len().saturating_sub(MAX_SYNC_LOG_ENTRIES);
+    log.entries.drain(..excess);
 
-        if let Some(url) = &server.url {
-            server_obj.insert("url".to_string(), Value::String(url.clone()));
-        }
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&log_path, serde_json::to_string_pretty(log)?)?;
+    Ok(())
+}
 
-        if let Some(target) = &server.target {
-            server_obj.insert("_target".to_string(), Value::String(target.clone()));
-        }
+/// Drop tools from the auto-sync review queue once they have been synced.
+pub fn dequeue_reviews(app: &impl AppPaths, tool_ids: &[String]) -> Result<(), McpError> {
+    let mut log = load_sync_log(app)?;
+    let before = log.review_queue.len();
+    log.review_queue.retain(|id| !tool_ids.contains(id));
+    if log.review_queue.len() != before {
+        save_sync_log(app, &mut log)?;
+    }
+    Ok(())
+}
 
-        // Write any extra fields we captured
-        if let Some(extra) = &server.extra {
-            for (k, v) in extra {
-                server_obj.insert(k.clone(), v.clone());
-            }
-        }
+/// File holding the source server list for the configured mode.
+pub fn mcp_source_path(app: &impl AppPaths) -> Result<PathBuf, McpError> {
+    match load_mcp_config_internal(app)?.source_mode {
+        McpSourceMode::Claude => expand_path("~/.claude.json")
+            .ok_or_else(|| McpError::NotFound("Could not expand ~/.claude.json".to_string())),
+        McpSourceMode::AppManaged => get_mcp_config_path(app),
+    }
+}
+
+pub fn read_claude_mcp_servers_internal() -> Result<Vec<McpServer>, McpError> {
+    let claude_path = expand_path("~/.claude.json")
+        .ok_or_else(|| McpError::NotFound("Could not expand ~/.claude.json".to_string()))?;
 
-        map.insert(server.name.clone(), Value::Object(server_obj));
+    if !claude_path.exists() {
+        return Ok(Vec::new());
     }
 
-    Value::Object(map)
+    let content = fs::read_to_string(&claude_path)?;
+    let root: Value = jsonc::parse(&content)?;
+
+    match root.get("mcpServers") {
+        Some(servers) => StandardAdapter.parse(servers),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Servers from the configured source: ~/.claude.json or the app-managed list.
+pub fn get_source_servers(app: &impl AppPaths) -> Result<Vec<McpServer>, McpError> {
+    let config = load_mcp_config_internal(app)?;
+
+    match config.source_mode {
+        McpSourceMode::Claude => read_claude_mcp_servers_internal(),
+        McpSourceMode::AppManaged => Ok(config.servers),
+    }
 }
 
 // Tauri commands
@@ -203,12 +192,7 @@ pub fn set_mcp_source_mode(app: AppHandle, mode: McpSourceMode) -> Result<McpCon
 
 #[tauri::command]
 pub fn get_mcp_source_servers(app: AppHandle) -> Result<Vec<McpServer>, McpError> {
-    let config = load_mcp_config_internal(&app)?;
-
-    match config.source_mode {
-        McpSourceMode::Claude => read_claude_mcp_servers_internal(),
-        McpSourceMode::AppManaged => Ok(config.servers),
-    }
+    get_source_servers(&app)
 }
 
 #[tauri::command]
@@ -284,77 +268,3 @@ pub fn remove_mcp_server(app: AppHandle, server_name: String) -> Result<Vec<McpS
 pub fn read_claude_mcp_servers() -> Result<Vec<McpServer>, McpError> {
     read_claude_mcp_servers_internal()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_standard_mcp_servers() {
-        let json = serde_json::json!({
-            "mcpServers": {
-                "filesystem": {
-                    "command": "npx",
-                    "args": ["-y", "@anthropic/mcp-filesystem-server"],
-                    "env": {
-                        "HOME": "/home/user"
-                    }
-                },
-                "git": {
-                    "command": "git-mcp",
-                    "disabled": true
-                }
-            }
-        });
-
-        let servers = parse_standard_mcp_servers(&json, "mcpServers").unwrap();
-        assert_eq!(servers.len(), 2);
-
-        let fs_server = servers.iter().find(|s| s.name == "filesystem").unwrap();
-        assert_eq!(fs_server.command, "npx");
-        assert_eq!(
-            fs_server.args,
-            Some(vec![
-                "-y".to_string(),
-                "@anthropic/mcp-filesystem-server".to_string()
-            ])
-        );
-        assert!(fs_server.env.is_some());
-
-        let git_server = servers.iter().find(|s| s.name == "git").unwrap();
-        assert_eq!(git_server.command, "git-mcp");
-        assert_eq!(git_server.disabled, Some(true));
-    }
-
-    #[test]
-    fn test_servers_to_standard_format() {
-        let servers = vec![
-            McpServer {
-                name: "test".to_string(),
-                command: "test-cmd".to_string(),
-                args: Some(vec!["arg1".to_string()]),
-                env: None,
-                disabled: None,
-                url: None,
-                target: None,
-                extra: None,
-            },
-        ];
-
-        let result = servers_to_standard_format(&servers);
-        assert!(result.is_object());
-        
-        let obj = result.as_object().unwrap();
-        assert!(obj.contains_key("test"));
-        
-        let test_server = obj.get("test").unwrap().as_object().unwrap();
-        assert_eq!(test_server.get("command").unwrap().as_str().unwrap(), "test-cmd");
-    }
-
-    #[test]
-    fn test_parse_empty_mcp_servers() {
-        let json = serde_json::json!({});
-        let servers = parse_standard_mcp_servers(&json, "mcpServers").unwrap();
-        assert!(servers.is_empty());
-    }
-}
@@ -1,101 +1,187 @@
-use crate::commands::expand_path;
-use crate::mcp::converters::{read_tool_mcp_servers, servers_to_tool_format};
-use crate::mcp::storage::{load_mcp_config_internal, read_claude_mcp_servers_internal, servers_to_standard_format};
+use crate::app_paths::AppPaths;
+use crate::commands::BackupSettings;
+use crate::config::tool_registry;
+use crate::history::{record_history, HistoryEntry};
+use crate::mcp::claude_projects::{
+    claude_project_targets, read_claude_project_servers_internal, record_claude_write,
+    stage_claude_project_write, CLAUDE_CONFIG_PATH,
+};
+use crate::mcp::converters::{read_tool_mcp_servers, render_tool_config};
+use crate::mcp::location::McpToolLocation;
+use crate::mcp::storage::{
+    dequeue_reviews, get_mcp_config_path, get_source_servers, load_mcp_config_internal,
+    load_sync_snapshot, save_mcp_config_internal, save_sync_snapshot,
+};
 use crate::mcp::types::{
-    get_mcp_tool_definitions, get_mcp_tool_info, McpConfig, McpConfigPreview, McpError, McpMergeResult, McpServer,
-    McpServerConflict, McpSourceMode, McpSyncPreview, McpSyncResult, McpSyncStatus,
-    McpToolFormat, McpToolInfo, McpToolStatus,
+    McpConfig, McpConfigPreview, McpError, McpMergeResult, McpServer, McpServerConflict,
+    McpSourceMode, McpSyncPreview, McpSyncResult, McpSyncStatus, McpToolInfo, McpToolStatus,
 };
-use serde_json::Value;
-use std::fs;
+use crate::secrets::{rereference_server_secrets, resolve_server_secrets};
+use crate::transaction::{fingerprint, FileTarget, Transaction};
+use crate::versions::record_write;
+use std::collections::HashMap;
+use std::path::Path;
 use tauri::AppHandle;
 
-fn get_source_servers(app: &AppHandle) -> Result<Vec<McpServer>, McpError> {
-    let config = load_mcp_config_internal(app)?;
-
-    match config.source_mode {
-        McpSourceMode::Claude => read_claude_mcp_servers_internal(),
-        McpSourceMode::AppManaged => Ok(config.servers),
-    }
-}
+/// Tool whose config holds the source list in `McpSourceMode::Claude`.
+pub(crate) const CLAUDE_SOURCE_TOOL_ID: &str = "claude-code";
 
+/// Merge the source list into a tool's servers.
+///
+/// With `base` (what the last sync wrote to the tool) this is a three-way
+/// merge: a side that still matches the base did not change, so the other
+/// side's edit or deletion wins, and only servers changed differently on both
+/// sides conflict. An edit always beats a deletion. Without a base every
+/// difference is a conflict and nothing is ever removed.
 pub fn compute_merge_result(
     source_servers: &[McpServer],
     target_servers: &[McpServer],
+    base_servers: Option<&[McpServer]>,
     tool_id: &str,
 ) -> McpMergeResult {
-    let mut added = Vec::new();
-    let mut kept = Vec::new();
-    let mut conflicts = Vec::new();
+    let mut result = McpMergeResult {
+        tool_id: tool_id.to_string(),
+        added: Vec::new(),
+        updated: Vec::new(),
+        removed: Vec::new(),
+        kept: Vec::new(),
+        source_updates: Vec::new(),
+        source_removals: Vec::new(),
+        conflicts: Vec::new(),
+    };
+
+    let find = |servers: &[McpServer], name: &str| servers.iter().find(|s| s.name == name).cloned();
+    let base_of = |name: &str| base_servers.and_then(|base| find(base, name));
 
     for source in source_servers {
-        match target_servers.iter().find(|t| t.name == source.name) {
-            Some(target) => {
-                if servers_equal(source, target) {
-                    kept.push(source.clone());
-                } else {
-                    conflicts.push(McpServerConflict {
-                        server_name: source.name.clone(),
-                        source_server: source.clone(),
-                        target_server: target.clone(),
-                        tool_id: tool_id.to_string(),
-                    });
+        let base = base_of(&source.name);
+        match find(target_servers, &source.name) {
+            Some(target) if servers_equal(source, &target) => result.kept.push(target),
+            Some(target) => match base {
+                Some(base) if servers_equal(&base, &target) => result.updated.push(source.clone()),
+                Some(base) if servers_equal(&base, source) => result.source_updates.push(target),
+                _ => result.conflicts.push(McpServerConflict {
+                    server_name: source.name.clone(),
+                    source_server: source.clone(),
+                    target_server: target,
+                    tool_id: tool_id.to_string(),
+                }),
+            },
+            None => match base {
+                // Deleted in the tool and untouched in the source
+                Some(base) if servers_equal(&base, source) => {
+                    result.source_removals.push(source.clone())
                 }
-            }
-            None => {
-                added.push(source.clone());
-            }
+                _ => result.added.push(source.clone()),
+            },
         }
     }
 
-    // Keep servers that exist in target but not in source
     for target in target_servers {
-        if !source_servers.iter().any(|s| s.name == target.name) {
-            kept.push(target.clone());
+        if source_servers.iter().any(|s| s.name == target.name) {
+            continue;
+        }
+        match (base_servers, base_of(&target.name)) {
+            // Never synced: we can't tell a tool-side addition from a source-side deletion
+            (None, _) => result.kept.push(target.clone()),
+            (Some(_), Some(base)) if servers_equal(&base, target) => {
+                result.removed.push(target.clone())
+            }
+            (Some(_), _) => result.source_updates.push(target.clone()),
         }
     }
 
-    McpMergeResult {
-        tool_id: tool_id.to_string(),
-        added,
-        kept,
-        conflicts,
+    result
+}
+
+/// Servers the tool should contain after applying a merge.
+pub fn merged_target_servers(
+    merge: &McpMergeResult,
+    resolved_conflicts: &[McpServer],
+) -> Vec<McpServer> {
+    let mut servers: Vec<McpServer> = merge.kept.clone();
+    servers.extend(merge.added.iter().cloned());
+    servers.extend(merge.updated.iter().cloned());
+    servers.extend(merge.source_updates.iter().cloned());
+    servers.extend(resolved_conflicts.iter().cloned());
+    servers
+}
+
+/// The source list after pulling back the tool's own edits and deletions and
+/// any conflict resolutions, so both sides agree after the sync.
+pub fn merged_source_servers(
+    source_servers: &[McpServer],
+    merge: &McpMergeResult,
+    resolved_conflicts: &[McpServer],
+) -> Vec<McpServer> {
+    let mut servers: Vec<McpServer> = source_servers
+        .iter()
+        .filter(|s| !merge.source_removals.iter().any(|r| r.name == s.name))
+        .cloned()
+        .collect();
+
+    for incoming in merge.source_updates.iter().chain(resolved_conflicts) {
+        match servers.iter_mut().find(|s| s.name == incoming.name) {
+            Some(existing) => *existing = incoming.clone(),
+            None => servers.push(incoming.clone()),
+        }
     }
+    servers
 }
 
 fn servers_equal(a: &McpServer, b: &McpServer) -> bool {
     a.command == b.command && a.args == b.args && a.env == b.env && a.url == b.url
 }
 
-fn get_sync_status(source: &[McpServer], target: &[McpServer], tool_id: &str) -> McpSyncStatus {
-    let merge = compute_merge_result(source, target, tool_id);
-    
+/// Merge the source into a tool against the snapshot from its last sync.
+fn merge_for_tool(
+    app: &impl AppPaths,
+    tool_info: &McpToolInfo,
+    source_servers: &[McpServer],
+) -> Result<McpMergeResult, McpError> {
+    let mut target_servers = read_tool_mcp_servers(tool_info)?;
+    rereference_server_secrets(app, &mut target_servers, source_servers);
+    let base = load_sync_snapshot(app, &tool_info.tool_id)?;
+    Ok(compute_merge_result(
+        source_servers,
+        &target_servers,
+        base.as_ref().map(|b| b.servers.as_slice()),
+        &tool_info.tool_id,
+    ))
+}
+
+fn get_sync_status(merge: &McpMergeResult) -> McpSyncStatus {
     if !merge.conflicts.is_empty() {
         McpSyncStatus::Conflicts
-    } else if !merge.added.is_empty() {
+    } else if merge.has_changes() {
         McpSyncStatus::OutOfSync
     } else {
         McpSyncStatus::Synced
     }
 }
 
-#[tauri::command]
-pub fn get_mcp_tool_statuses(app: AppHandle) -> Result<Vec<McpToolStatus>, McpError> {
-    let config = load_mcp_config_internal(&app)?;
-    let source_servers = get_source_servers(&app)?;
-    let tool_definitions = get_mcp_tool_definitions();
+pub fn get_mcp_tool_statuses_internal(app: &impl AppPaths) -> Result<Vec<McpToolStatus>, McpError> {
+    let config = load_mcp_config_internal(app)?;
+    let source_servers = get_source_servers(app)?;
+    let tool_definitions = tool_registry(app).mcp_tools;
 
     let mut statuses = Vec::new();
 
     for tool_info in tool_definitions {
-        let path = expand_path(&tool_info.config_path);
-        let installed = path.as_ref().map(|p| p.exists()).unwrap_or(false);
-        
+        let installed = tool_installed(&tool_info);
+
         let (sync_status, server_count) = if installed {
             match read_tool_mcp_servers(&tool_info) {
-                Ok(tool_servers) => {
-                    let status = get_sync_status(&source_servers, &tool_servers, &tool_info.tool_id);
-                    (status, tool_servers.len() as u32)
+                Ok(mut tool_servers) => {
+                    rereference_server_secrets(app, &mut tool_servers, &source_servers);
+                    let base = load_sync_snapshot(app, &tool_info.tool_id)?;
+                    let merge = compute_merge_result(
+                        &source_servers,
+                        &tool_servers,
+                        base.as_ref().map(|b| b.servers.as_slice()),
+                        &tool_info.tool_id,
+                    );
+                    (get_sync_status(&merge), tool_servers.len() as u32)
                 }
                 Err(_) => (McpSyncStatus::NoMcp, 0),
             }
@@ -119,6 +205,11 @@ pub fn get_mcp_tool_statuses(app: AppHandle) -> Result<Vec<McpToolStatus>, McpEr
     Ok(statuses)
 }
 
+#[tauri::command]
+pub fn get_mcp_tool_statuses(app: AppHandle) -> Result<Vec<McpToolStatus>, McpError> {
+    get_mcp_tool_statuses_internal(&app)
+}
+
 #[tauri::command]
 pub fn set_tool_mcp_enabled(
     app: AppHandle,
@@ -135,50 +226,56 @@ pub fn set_tool_mcp_enabled(
         config.enabled_tools.retain(|t| *t != tool_id);
     }
 
-    crate::mcp::storage::save_mcp_config_internal(&app, &config)?;
+    save_mcp_config_internal(&app, &config)?;
     Ok(config)
 }
 
-#[tauri::command]
-pub fn preview_mcp_sync(app: AppHandle, tool_id: String) -> Result<McpSyncPreview, McpError> {
-    let tool_info =
-        get_mcp_tool_info(&tool_id).ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
+pub fn preview_mcp_sync_internal(
+    app: &impl AppPaths,
+    tool_id: String,
+) -> Result<McpSyncPreview, McpError> {
+    let tool_info = tool_registry(app)
+        .mcp_tool(&tool_id)
+        .ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
 
-    let source_servers = get_source_servers(&app)?;
-    let target_servers = read_tool_mcp_servers(&tool_info)?;
+    let source_servers = get_source_servers(app)?;
+    preview_tool_sync(app, tool_info, &source_servers)
+}
 
-    let merge_result = compute_merge_result(&source_servers, &target_servers, &tool_id);
-    let has_changes = !merge_result.added.is_empty() || !merge_result.conflicts.is_empty();
+fn preview_tool_sync(
+    app: &impl AppPaths,
+    tool_info: McpToolInfo,
+    source_servers: &[McpServer],
+) -> Result<McpSyncPreview, McpError> {
+    let fingerprint = fingerprint(McpToolLocation::of(&tool_info)?.read()?.as_deref());
+    let merge_result = merge_for_tool(app, &tool_info, source_servers)?;
+    let has_changes = merge_result.has_changes();
 
     Ok(McpSyncPreview {
         tool_id: tool_info.tool_id,
         tool_name: tool_info.name,
         merge_result,
         has_changes,
+        fingerprint,
     })
 }
 
 #[tauri::command]
-pub fn preview_mcp_sync_all(app: AppHandle) -> Result<Vec<McpSyncPreview>, McpError> {
-    let config = load_mcp_config_internal(&app)?;
-    let source_servers = get_source_servers(&app)?;
+pub fn preview_mcp_sync(app: AppHandle, tool_id: String) -> Result<McpSyncPreview, McpError> {
+    preview_mcp_sync_internal(&app, tool_id)
+}
+
+pub fn preview_mcp_sync_all_internal(app: &impl AppPaths) -> Result<Vec<McpSyncPreview>, McpError> {
+    let config = load_mcp_config_internal(app)?;
+    let source_servers = get_source_servers(app)?;
+    let registry = tool_registry(app);
 
     let mut previews = Vec::new();
 
     for tool_id in &config.enabled_tools {
-        if let Some(tool_info) = get_mcp_tool_info(tool_id) {
-            let path = expand_path(&tool_info.config_path);
-            if path.map(|p| p.exists()).unwrap_or(false) {
-                let target_servers = read_tool_mcp_servers(&tool_info)?;
-                let merge_result = compute_merge_result(&source_servers, &target_servers, tool_id);
-                let has_changes = !merge_result.added.is_empty() || !merge_result.conflicts.is_empty();
-
-                previews.push(McpSyncPreview {
-                    tool_id: tool_info.tool_id,
-                    tool_name: tool_info.name,
-                    merge_result,
-                    has_changes,
-                });
+        if let Some(tool_info) = registry.mcp_tool(tool_id) {
+            if tool_installed(&tool_info) {
+                previews.push(preview_tool_sync(app, tool_info, &source_servers)?);
             }
         }
     }
@@ -187,232 +284,448 @@ pub fn preview_mcp_sync_all(app: AppHandle) -> Result<Vec<McpSyncPreview>, McpEr
 }
 
 #[tauri::command]
-pub fn sync_mcp_to_tool(
-    app: AppHandle,
+pub fn preview_mcp_sync_all(app: AppHandle) -> Result<Vec<McpSyncPreview>, McpError> {
+    preview_mcp_sync_all_internal(&app)
+}
+
+pub fn sync_mcp_to_tool_internal(
+    app: &impl AppPaths,
     tool_id: String,
     resolved_conflicts: Option<Vec<McpServer>>,
+    backup_settings: Option<BackupSettings>,
 ) -> Result<McpSyncResult, McpError> {
-    let tool_info =
-        get_mcp_tool_info(&tool_id).ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
+    let tool_info = tool_registry(app)
+        .mcp_tool(&tool_id)
+        .ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
 
-    let source_servers = get_source_servers(&app)?;
-    let target_servers = read_tool_mcp_servers(&tool_info)?;
-    let merge_result = compute_merge_result(&source_servers, &target_servers, &tool_id);
+    let source_servers = get_source_servers(app)?;
+    let merge_result = merge_for_tool(app, &tool_info, &source_servers)?;
 
     // Check for unresolved conflicts
     if !merge_result.conflicts.is_empty() && resolved_conflicts.is_none() {
-        return Ok(McpSyncResult {
-            tool_id,
-            success: false,
-            message: format!(
-                "Conflicts detected: {}. Please resolve conflicts first.",
-                merge_result
-                    .conflicts
-                    .iter()
-                    .map(|c| c.server_name.clone())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ),
-            servers_written: 0,
-        });
+        return Ok(conflicts_result(tool_id, &merge_result));
     }
 
-    // Build final server list
-    let mut final_servers: Vec<McpServer> = merge_result.kept.clone();
-    final_servers.extend(merge_result.added.clone());
+    let resolved = resolved_conflicts.unwrap_or_default();
+    let final_servers = merged_target_servers(&merge_result, &resolved);
 
-    // Add resolved conflicts
-    if let Some(resolved) = resolved_conflicts {
-        final_servers.extend(resolved);
-    } else {
-        // Add source servers for conflicts (default resolution)
-        for conflict in &merge_result.conflicts {
-            final_servers.push(conflict.source_server.clone());
-        }
+    // The tool file and the pulled-back source land together or not at all
+    let mut transaction = Transaction::new(app, backup_settings)?;
+    let written = stage_tool_write(app, &mut transaction, &tool_info, &final_servers, None)?;
+
+    let pulled_back = merge_result.source_updates.len() + merge_result.source_removals.len();
+    if pulled_back > 0 || !resolved.is_empty() {
+        let new_source = merged_source_servers(&source_servers, &merge_result, &resolved);
+        stage_source_write(app, &mut transaction, &new_source)?;
     }
 
-    // Write to tool config
-    write_mcp_to_tool(&tool_info, &final_servers)?;
+    transaction.commit()?;
+    record_write(app, &tool_info.config_path, None, &written);
+    record_history(
+        app,
+        &tool_history_entry(&tool_info, &written, "sync_mcp_to_tool"),
+    );
+    save_sync_snapshot(app, &tool_id, &final_servers)?;
+    dequeue_reviews(app, std::slice::from_ref(&tool_id))?;
 
     Ok(McpSyncResult {
         tool_id,
         success: true,
-        message: format!(
-            "Synced {} servers ({} added, {} kept)",
-            final_servers.len(),
-            merge_result.added.len(),
-            merge_result.kept.len()
-        ),
+        message: sync_message(&merge_result, final_servers.len(), pulled_back),
         servers_written: final_servers.len() as u32,
     })
 }
 
+fn tool_history_entry<'a>(
+    tool_info: &'a McpToolInfo,
+    written: &'a str,
+    origin: &'a str,
+) -> HistoryEntry<'a> {
+    HistoryEntry {
+        path: &tool_info.config_path,
+        previous: None,
+        content: written,
+        origin,
+        json_path: Some(&tool_info.json_path),
+        tool_id: Some(&tool_info.tool_id),
+    }
+}
+
+fn sync_message(merge: &McpMergeResult, written: usize, pulled_back: usize) -> String {
+    format!(
+        "Synced {} servers ({} added, {} updated, {} removed, {} kept, {} pulled back)",
+        written,
+        merge.added.len(),
+        merge.updated.len(),
+        merge.removed.len(),
+        merge.kept.len(),
+        pulled_back
+    )
+}
+
+fn conflicts_result(tool_id: String, merge: &McpMergeResult) -> McpSyncResult {
+    McpSyncResult {
+        tool_id,
+        success: false,
+        message: format!(
+            "Conflicts detected: {}. Please resolve conflicts first.",
+            merge
+                .conflicts
+                .iter()
+                .map(|c| c.server_name.clone())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        servers_written: 0,
+    }
+}
+
+fn failed_result(tool_id: String, message: String) -> McpSyncResult {
+    McpSyncResult {
+        tool_id,
+        success: false,
+        message,
+        servers_written: 0,
+    }
+}
+
+/// Stage the central server list for wherever the source lives.
+fn stage_source_write(
+    app: &impl AppPaths,
+    transaction: &mut Transaction,
+    servers: &[McpServer],
+) -> Result<(), McpError> {
+    let mut config = load_mcp_config_internal(app)?;
+    match config.source_mode {
+        McpSourceMode::AppManaged => {
+            config.servers = servers.to_vec();
+            let target = FileTarget::Local(get_mcp_config_path(app)?);
+            transaction.stage(target, serde_json::to_string_pretty(&config)?, None);
+            Ok(())
+        }
+        McpSourceMode::Claude => {
+            let claude = tool_registry(app)
+                .mcp_tool(CLAUDE_SOURCE_TOOL_ID)
+                .ok_or_else(|| McpError::ToolNotSupported(CLAUDE_SOURCE_TOOL_ID.to_string()))?;
+            stage_tool_write(app, transaction, &claude, servers, None).map(|_| ())
+        }
+    }
+}
+
 #[tauri::command]
-pub fn sync_mcp_to_all(app: AppHandle) -> Result<Vec<McpSyncResult>, McpError> {
-    let config = load_mcp_config_internal(&app)?;
+pub fn sync_mcp_to_tool(
+    app: AppHandle,
+    tool_id: String,
+    resolved_conflicts: Option<Vec<McpServer>>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<McpSyncResult, McpError> {
+    sync_mcp_to_tool_internal(&app, tool_id, resolved_conflicts, backup_settings)
+}
+
+/// Sync every enabled tool in one transaction. Tools with conflicts are
+/// skipped; if any write fails, every file is rolled back. `expected` maps
+/// tool ids to the `fingerprint` from their preview, so a file edited since
+/// then aborts the whole sync.
+pub fn sync_mcp_to_all_internal(
+    app: &impl AppPaths,
+    expected: Option<HashMap<String, String>>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<Vec<McpSyncResult>, McpError> {
+    let config = load_mcp_config_internal(app)?;
+    let registry = tool_registry(app);
+    let expected = expected.unwrap_or_default();
+    let mut source_servers = get_source_servers(app)?;
+    let mut source_changed = false;
+
+    let mut transaction = Transaction::new(app, backup_settings)?;
     let mut results = Vec::new();
+    let mut snapshots = Vec::new();
 
     for tool_id in &config.enabled_tools {
-        if let Some(tool_info) = get_mcp_tool_info(tool_id) {
-            let path = expand_path(&tool_info.config_path);
-            if path.map(|p| p.exists()).unwrap_or(false) {
-                match sync_mcp_to_tool(app.clone(), tool_id.clone(), None) {
-                    Ok(result) => results.push(result),
-                    Err(e) => results.push(McpSyncResult {
-                        tool_id: tool_id.clone(),
-                        success: false,
-                        message: e.to_string(),
-                        servers_written: 0,
-                    }),
-                }
+        let Some(tool_info) = registry.mcp_tool(tool_id).filter(tool_installed) else {
+            continue;
+        };
+
+        let merge_result = match merge_for_tool(app, &tool_info, &source_servers) {
+            Ok(merge_result) => merge_result,
+            Err(e) => {
+                results.push(failed_result(tool_id.clone(), e.to_string()));
+                continue;
             }
+        };
+        if !merge_result.conflicts.is_empty() {
+            results.push(conflicts_result(tool_id.clone(), &merge_result));
+            continue;
         }
-    }
 
-    Ok(results)
-}
+        let final_servers = merged_target_servers(&merge_result, &[]);
+        let staged = stage_tool_write(
+            app,
+            &mut transaction,
+            &tool_info,
+            &final_servers,
+            expected.get(tool_id).cloned(),
+        );
+        let written = match staged {
+            Ok(written) => written,
+            Err(e) => {
+                results.push(failed_result(tool_id.clone(), e.to_string()));
+                continue;
+            }
+        };
 
-fn write_mcp_to_tool(tool_info: &McpToolInfo, servers: &[McpServer]) -> Result<(), McpError> {
-    let path = expand_path(&tool_info.config_path)
-        .ok_or_else(|| McpError::NotFound(format!("Could not expand {}", tool_info.config_path)))?;
+        let pulled_back = merge_result.source_updates.len() + merge_result.source_removals.len();
+        if pulled_back > 0 {
+            source_servers = merged_source_servers(&source_servers, &merge_result, &[]);
+            source_changed = true;
+        }
 
-    // Create parent directories if needed
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        results.push(McpSyncResult {
+            tool_id: tool_id.clone(),
+            success: true,
+            message: sync_message(&merge_result, final_servers.len(), pulled_back),
+            servers_written: final_servers.len() as u32,
+        });
+        snapshots.push((tool_info, written, final_servers));
     }
 
-    // Read existing file or create empty object
-    let mut root: Value = if path.exists() {
-        let content = fs::read_to_string(&path)?;
-        serde_json::from_str(&content)?
-    } else {
-        serde_json::json!({})
-    };
-
-    // Create backup before writing
-    if path.exists() {
-        let backup_path = path.with_extension("bak");
-        fs::copy(&path, &backup_path)?;
+    if source_changed {
+        stage_source_write(app, &mut transaction, &source_servers)?;
     }
 
-    // Update the appropriate key based on format
-    match tool_info.format {
-        McpToolFormat::Standard => {
-            let json_path = &tool_info.json_path;
-            let servers_value = servers_to_standard_format(servers);
-            
-            // First check if the path exists as a literal key (e.g., "amp.mcpServers")
-            // This handles keys that contain dots but aren't nested paths
-            if root.get(json_path).is_some() {
-                if let Value::Object(ref mut map) = root {
-                    map.insert(json_path.clone(), servers_value);
-                }
-            } else if json_path.contains('.') {
-                // Check if it should be a literal key by looking at existing keys
-                // For Amp, "amp.mcpServers" is a literal key, not amp -> mcpServers
-                let prefix = json_path.split('.').next().unwrap_or("");
-                let has_dotted_keys = if let Value::Object(ref map) = root {
-                    map.keys().any(|k| k.contains('.') && k.starts_with(prefix))
-                } else {
-                    false
-                };
-                
-                if has_dotted_keys {
-                    // Use as literal key
-                    if let Value::Object(ref mut map) = root {
-                        map.insert(json_path.clone(), servers_value);
-                    }
-                } else {
-                    // Handle as nested path
-                    let parts: Vec<&str> = json_path.split('.').collect();
-                    set_nested_value(&mut root, &parts, servers_value);
-                }
-            } else if let Value::Object(ref mut map) = root {
-                map.insert(json_path.clone(), servers_value);
+    match transaction.commit() {
+        Ok(()) => {
+            let synced: Vec<String> = snapshots
+                .iter()
+                .map(|(tool_info, ..)| tool_info.tool_id.clone())
+                .collect();
+            for (tool_info, written, servers) in snapshots {
+                record_write(app, &tool_info.config_path, None, &written);
+                record_history(
+                    app,
+                    &tool_history_entry(&tool_info, &written, "sync_mcp_to_all"),
+                );
+                save_sync_snapshot(app, &tool_info.tool_id, &servers)?;
             }
+            dequeue_reviews(app, &synced)?;
         }
-        McpToolFormat::Copilot => {
-            let servers_value = servers_to_tool_format(servers, &McpToolFormat::Copilot);
-            if let Value::Object(ref mut map) = root {
-                map.insert("servers".to_string(), servers_value);
-            }
-        }
-        McpToolFormat::Opencode => {
-            let mcp_value = servers_to_tool_format(servers, &McpToolFormat::Opencode);
-            if let Value::Object(ref mut map) = root {
-                map.insert("mcp".to_string(), mcp_value);
+        Err(e) => {
+            for result in results.iter_mut().filter(|r| r.success) {
+                result.success = false;
+                result.message = e.to_string();
+                result.servers_written = 0;
             }
         }
     }
 
-    // Write atomically via temp file
-    let temp_path = path.with_extension("tmp");
-    let content = serde_json::to_string_pretty(&root)?;
-    fs::write(&temp_path, &content)?;
-    fs::rename(&temp_path, &path)?;
+    Ok(results)
+}
 
-    Ok(())
+#[tauri::command]
+pub fn sync_mcp_to_all(
+    app: AppHandle,
+    expected_fingerprints: Option<HashMap<String, String>>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<Vec<McpSyncResult>, McpError> {
+    sync_mcp_to_all_internal(&app, expected_fingerprints, backup_settings)
 }
 
-fn set_nested_value(root: &mut Value, parts: &[&str], value: Value) {
-    if parts.is_empty() {
-        return;
+/// Sync a Claude Code project's servers (`projects[path].mcpServers` in
+/// ~/.claude.json) into other tools' project files in that directory, such
+/// as `.cursor/mcp.json` or `opencode.json`. Works like `sync_mcp_to_all`
+/// with the project's servers as the source, so edits made in a tool's
+/// project file are pulled back into the project. `tool_ids` limits the
+/// sync to those tools; by default every tool with a project config is used.
+pub fn sync_claude_project_internal(
+    app: &impl AppPaths,
+    claude_config: &str,
+    project_path: &str,
+    tool_ids: Option<Vec<String>>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<Vec<McpSyncResult>, McpError> {
+    if !Path::new(project_path).is_dir() {
+        return Err(McpError::NotFound(format!(
+            "Project directory not found: {}",
+            project_path
+        )));
     }
-
-    let mut current = root;
-    for (i, part) in parts.iter().enumerate() {
-        if i == parts.len() - 1 {
-            if let Value::Object(ref mut map) = current {
-                map.insert((*part).to_string(), value);
+    let mut source_servers = read_claude_project_servers_internal(claude_config, project_path)?;
+    let mut source_changed = false;
+
+    let tools: Vec<McpToolInfo> = tool_registry(app)
+        .mcp_tools
+        .into_iter()
+        .filter(|tool| {
+            tool_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&tool.tool_id))
+        })
+        .collect();
+
+    let mut transaction = Transaction::new(app, backup_settings)?;
+    let mut results = Vec::new();
+    let mut snapshots = Vec::new();
+
+    for tool_info in claude_project_targets(&tools, project_path) {
+        let tool_id = tool_info.tool_id.clone();
+        let merge_result = match merge_for_tool(app, &tool_info, &source_servers) {
+            Ok(merge_result) => merge_result,
+            Err(e) => {
+                results.push(failed_result(tool_id, e.to_string()));
+                continue;
             }
-            return;
+        };
+        if !merge_result.conflicts.is_empty() {
+            results.push(conflicts_result(tool_id, &merge_result));
+            continue;
         }
 
-        if current.get(*part).is_none() {
-            if let Value::Object(ref mut map) = current {
-                map.insert((*part).to_string(), serde_json::json!({}));
+        let final_servers = merged_target_servers(&merge_result, &[]);
+        let written =
+            match stage_tool_write(app, &mut transaction, &tool_info, &final_servers, None) {
+                Ok(written) => written,
+                Err(e) => {
+                    results.push(failed_result(tool_id, e.to_string()));
+                    continue;
+                }
+            };
+
+        let pulled_back = merge_result.source_updates.len() + merge_result.source_removals.len();
+        if pulled_back > 0 {
+            source_servers = merged_source_servers(&source_servers, &merge_result, &[]);
+            source_changed = true;
+        }
+
+        results.push(McpSyncResult {
+            tool_id,
+            success: true,
+            message: sync_message(&merge_result, final_servers.len(), pulled_back),
+            servers_written: final_servers.len() as u32,
+        });
+        snapshots.push((tool_info, written, final_servers));
+    }
+
+    let source_written = if source_changed {
+        Some(stage_claude_project_write(
+            &mut transaction,
+            claude_config,
+            project_path,
+            &source_servers,
+        )?)
+    } else {
+        None
+    };
+
+    match transaction.commit() {
+        Ok(()) => {
+            for (tool_info, written, servers) in snapshots {
+                record_write(app, &tool_info.config_path, None, &written);
+                record_history(
+                    app,
+                    &tool_history_entry(&tool_info, &written, "sync_claude_project"),
+                );
+                save_sync_snapshot(app, &tool_info.tool_id, &servers)?;
+            }
+            if let Some(written) = source_written {
+                record_claude_write(app, claude_config, &written, "sync_claude_project");
+            }
+        }
+        Err(e) => {
+            for result in results.iter_mut().filter(|r| r.success) {
+                result.success = false;
+                result.message = e.to_string();
+                result.servers_written = 0;
             }
         }
-        current = current.get_mut(*part).unwrap();
     }
+
+    Ok(results)
 }
 
-/// Preview what the final config file will look like after sync
 #[tauri::command]
-pub fn preview_mcp_config_content(
+pub fn sync_claude_project(
     app: AppHandle,
-    tool_id: String,
-    resolved_conflicts: Option<Vec<McpServer>>,
-) -> Result<McpConfigPreview, McpError> {
-    let tool_info =
-        get_mcp_tool_info(&tool_id).ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
+    project_path: String,
+    tool_ids: Option<Vec<String>>,
+    backup_settings: Option<BackupSettings>,
+) -> Result<Vec<McpSyncResult>, McpError> {
+    sync_claude_project_internal(
+        &app,
+        CLAUDE_CONFIG_PATH,
+        &project_path,
+        tool_ids,
+        backup_settings,
+    )
+}
 
-    let source_servers = get_source_servers(&app)?;
-    let target_servers = read_tool_mcp_servers(&tool_info)?;
-    let merge_result = compute_merge_result(&source_servers, &target_servers, &tool_id);
+/// Whether the tool's config file exists, locally or on its remote host.
+/// An unreachable host counts as not installed. Project-level targets only
+/// need their workspace root, so sync can create the project file.
+fn tool_installed(tool_info: &McpToolInfo) -> bool {
+    if let (Some(_), Some(relative)) = (&tool_info.workspace, &tool_info.project_config_path) {
+        let depth = Path::new(relative).components().count();
+        return Path::new(&tool_info.config_path)
+            .ancestors()
+            .nth(depth)
+            .is_some_and(|root| root.is_dir());
+    }
+    McpToolLocation::of(tool_info)
+        .and_then(|location| location.exists())
+        .unwrap_or(false)
+}
 
-    // Build final server list
-    let mut final_servers: Vec<McpServer> = merge_result.kept.clone();
-    final_servers.extend(merge_result.added.clone());
+/// Stage the tool's config with `servers` written in. Builds on content
+/// already staged for the same file. Returns the staged content.
+fn stage_tool_write(
+    app: &impl AppPaths,
+    transaction: &mut Transaction,
+    tool_info: &McpToolInfo,
+    servers: &[McpServer],
+    expected: Option<String>,
+) -> Result<String, McpError> {
+    let target = FileTarget::parse(&tool_info.config_path)?;
+    let current = match transaction.staged_content(&target) {
+        Some(content) => Some(content.to_string()),
+        None => target.read()?,
+    };
 
-    // Add resolved conflicts or use source by default
-    if let Some(resolved) = resolved_conflicts {
-        final_servers.extend(resolved);
-    } else {
-        for conflict in &merge_result.conflicts {
-            final_servers.push(conflict.source_server.clone());
-        }
-    }
+    // Secret references are resolved only in the file handed to the tool
+    let servers = resolve_server_secrets(app, servers)?;
+    let content = render_tool_config(tool_info, current.as_deref(), &servers)?;
+
+    transaction.stage(target, content.clone(), expected);
+    Ok(content)
+}
+
+/// Preview what the final config file will look like after sync
+pub fn preview_mcp_config_content_internal(
+    app: &impl AppPaths,
+    tool_id: String,
+    resolved_conflicts: Option<Vec<McpServer>>,
+) -> Result<McpConfigPreview, McpError> {
+    let tool_info = tool_registry(app)
+        .mcp_tool(&tool_id)
+        .ok_or_else(|| McpError::ToolNotSupported(tool_id.clone()))?;
+
+    let source_servers = get_source_servers(app)?;
+    let merge_result = merge_for_tool(app, &tool_info, &source_servers)?;
+
+    // Use source servers for unresolved conflicts
+    let resolved = resolved_conflicts.unwrap_or_else(|| {
+        merge_result
+            .conflicts
+            .iter()
+            .map(|c| c.source_server.clone())
+            .collect()
+    });
+    let final_servers = merged_target_servers(&merge_result, &resolved);
 
     // Generate preview content without writing
-    let preview_content = generate_config_preview(&tool_info, &final_servers)?;
+    let current = McpToolLocation::of(&tool_info)?.read()?;
+    let preview_content = render_tool_config(&tool_info, current.as_deref(), &final_servers)?;
 
     // Get current content for comparison
-    let path = expand_path(&tool_info.config_path);
-    let current_content = path
-        .as_ref()
-        .and_then(|p| if p.exists() { fs::read_to_string(p).ok() } else { None })
-        .unwrap_or_else(|| "{}".to_string());
+    let current_content = current.unwrap_or_else(|| "{}".to_string());
 
     Ok(McpConfigPreview {
         tool_id,
@@ -423,66 +736,13 @@ pub fn preview_mcp_config_content(
     })
 }
 
-fn generate_config_preview(tool_info: &McpToolInfo, servers: &[McpServer]) -> Result<String, McpError> {
-    let path = expand_path(&tool_info.config_path);
-    
-    // Read existing file or create empty object
-    let mut root: Value = if let Some(ref p) = path {
-        if p.exists() {
-            let content = fs::read_to_string(p)?;
-            serde_json::from_str(&content)?
-        } else {
-            serde_json::json!({})
-        }
-    } else {
-        serde_json::json!({})
-    };
-
-    // Update the appropriate key based on format (same logic as write_mcp_to_tool)
-    match tool_info.format {
-        McpToolFormat::Standard => {
-            let json_path = &tool_info.json_path;
-            let servers_value = servers_to_standard_format(servers);
-            
-            if root.get(json_path).is_some() {
-                if let Value::Object(ref mut map) = root {
-                    map.insert(json_path.clone(), servers_value);
-                }
-            } else if json_path.contains('.') {
-                let prefix = json_path.split('.').next().unwrap_or("");
-                let has_dotted_keys = if let Value::Object(ref map) = root {
-                    map.keys().any(|k| k.contains('.') && k.starts_with(prefix))
-                } else {
-                    false
-                };
-                
-                if has_dotted_keys {
-                    if let Value::Object(ref mut map) = root {
-                        map.insert(json_path.clone(), servers_value);
-                    }
-                } else {
-                    let parts: Vec<&str> = json_path.split('.').collect();
-                    set_nested_value(&mut root, &parts, servers_value);
-                }
-            } else if let Value::Object(ref mut map) = root {
-                map.insert(json_path.clone(), servers_value);
-            }
-        }
-        McpToolFormat::Copilot => {
-            let servers_value = servers_to_tool_format(servers, &McpToolFormat::Copilot);
-            if let Value::Object(ref mut map) = root {
-                map.insert("servers".to_string(), servers_value);
-            }
-        }
-        McpToolFormat::Opencode => {
-            let mcp_value = servers_to_tool_format(servers, &McpToolFormat::Opencode);
-            if let Value::Object(ref mut map) = root {
-                map.insert("mcp".to_string(), mcp_value);
-            }
-        }
-    }
-
-    Ok(serde_json::to_string_pretty(&root)?)
+#[tauri::command]
+pub fn preview_mcp_config_content(
+    app: AppHandle,
+    tool_id: String,
+    resolved_conflicts: Option<Vec<McpServer>>,
+) -> Result<McpConfigPreview, McpError> {
+    preview_mcp_config_content_internal(&app, tool_id, resolved_conflicts)
 }
 
 #[cfg(test)]
@@ -505,7 +765,7 @@ mod tests {
         ];
         let target = vec![];
 
-        let result = compute_merge_result(&source, &target, "test");
+        let result = compute_merge_result(&source, &target, None, "test");
         assert_eq!(result.added.len(), 1);
         assert_eq!(result.kept.len(), 0);
         assert_eq!(result.conflicts.len(), 0);
@@ -534,7 +794,7 @@ mod tests {
             extra: None,
         }];
 
-        let result = compute_merge_result(&source, &target, "test");
+        let result = compute_merge_result(&source, &target, None, "test");
         assert_eq!(result.added.len(), 0);
         assert_eq!(result.kept.len(), 0);
         assert_eq!(result.conflicts.len(), 1);
@@ -554,7 +814,7 @@ mod tests {
             extra: None,
         }];
 
-        let result = compute_merge_result(&source, &target, "test");
+        let result = compute_merge_result(&source, &target, None, "test");
         assert_eq!(result.added.len(), 0);
         assert_eq!(result.kept.len(), 1);
         assert_eq!(result.conflicts.len(), 0);
@@ -587,4 +847,120 @@ mod tests {
         };
         assert!(!servers_equal(&a, &c));
     }
+
+    fn server(name: &str, command: &str) -> McpServer {
+        McpServer {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: None,
+            env: None,
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }
+    }
+
+    fn names(servers: &[McpServer]) -> Vec<&str> {
+        servers.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_three_way_merge_classifies_changes() {
+        let base = vec![
+            server("same", "a"),
+            server("source-edit", "a"),
+            server("target-edit", "a"),
+            server("both-edit", "a"),
+            server("source-delete", "a"),
+            server("target-delete", "a"),
+        ];
+        let source = vec![
+            server("same", "a"),
+            server("source-edit", "b"),
+            server("target-edit", "a"),
+            server("both-edit", "b"),
+            server("target-delete", "a"),
+            server("source-new", "a"),
+        ];
+        let target = vec![
+            server("same", "a"),
+            server("source-edit", "a"),
+            server("target-edit", "c"),
+            server("both-edit", "c"),
+            server("source-delete", "a"),
+            server("target-new", "a"),
+        ];
+
+        let result = compute_merge_result(&source, &target, Some(&base), "test");
+        assert_eq!(names(&result.kept), vec!["same"]);
+        assert_eq!(names(&result.added), vec!["source-new"]);
+        assert_eq!(names(&result.updated), vec!["source-edit"]);
+        assert_eq!(result.updated[0].command, "b");
+        assert_eq!(names(&result.removed), vec!["source-delete"]);
+        assert_eq!(
+            names(&result.source_updates),
+            vec!["target-edit", "target-new"]
+        );
+        assert_eq!(result.source_updates[0].command, "c");
+        assert_eq!(names(&result.source_removals), vec!["target-delete"]);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].server_name, "both-edit");
+    }
+
+    #[test]
+    fn test_three_way_merge_edit_beats_delete() {
+        let base = vec![server("a", "old"), server("b", "old")];
+        // Source deleted "a" but the tool edited it; the tool deleted "b" but the source edited it
+        let source = vec![server("b", "new")];
+        let target = vec![server("a", "new")];
+
+        let result = compute_merge_result(&source, &target, Some(&base), "test");
+        assert_eq!(names(&result.source_updates), vec!["a"]);
+        assert_eq!(names(&result.added), vec!["b"]);
+        assert!(result.removed.is_empty());
+        assert!(result.source_removals.is_empty());
+    }
+
+    #[test]
+    fn test_merged_lists_converge() {
+        let base = vec![
+            server("keep", "a"),
+            server("gone", "a"),
+            server("pulled", "a"),
+        ];
+        let source = vec![
+            server("keep", "a"),
+            server("pulled", "a"),
+            server("fresh", "a"),
+        ];
+        let target = vec![
+            server("keep", "a"),
+            server("gone", "a"),
+            server("pulled", "z"),
+        ];
+
+        let result = compute_merge_result(&source, &target, Some(&base), "test");
+        let new_target = merged_target_servers(&result, &[]);
+        let new_source = merged_source_servers(&source, &result, &[]);
+
+        let mut target_names = names(&new_target);
+        target_names.sort();
+        let mut source_names = names(&new_source);
+        source_names.sort();
+        assert_eq!(target_names, vec!["fresh", "keep", "pulled"]);
+        assert_eq!(target_names, source_names);
+        assert_eq!(
+            new_source
+                .iter()
+                .find(|s| s.name == "pulled")
+                .unwrap()
+                .command,
+            "z"
+        );
+
+        // Once both sides match the snapshot there is nothing left to do
+        let again = compute_merge_result(&new_source, &new_target, Some(&new_target), "test");
+        assert!(!again.has_changes());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::config::SettingsPaths;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
@@ -16,6 +17,14 @@ pub enum McpError {
     InvalidFormat(String),
     #[error("Tool not supported: {0}")]
     ToolNotSupported(String),
+    #[error(transparent)]
+    Format(#[from] crate::formats::FormatError),
+    #[error("SSH error: {0}")]
+    Ssh(#[from] crate::ssh::SshError),
+    #[error(transparent)]
+    Secret(#[from] crate::secrets::SecretError),
+    #[error(transparent)]
+    Transaction(#[from] crate::transaction::TransactionError),
 }
 
 impl Serialize for McpError {
@@ -62,6 +71,9 @@ pub struct McpConfig {
     pub source_mode: McpSourceMode,
     pub servers: Vec<McpServer>,
     pub enabled_tools: Vec<String>,
+    /// Sync enabled tools as soon as the source changes on disk
+    #[serde(default)]
+    pub auto_sync: bool,
 }
 
 impl Default for McpConfig {
@@ -70,16 +82,31 @@ impl Default for McpConfig {
             source_mode: McpSourceMode::Claude,
             servers: Vec::new(),
             enabled_tools: Vec::new(),
+            auto_sync: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "kebab-case")]
-pub enum McpToolFormat {
-    Standard,  // Claude, Gemini, Amp, Droid, Qwen
-    Copilot,   // GitHub Copilot - uses "servers" key
-    Opencode,  // OpenCode - uses "mcp" with different structure
+/// Name of the `McpFormatAdapter` that reads and writes a tool's servers,
+/// e.g. `standard`, `amp`, `copilot` or `opencode`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct McpToolFormat(String);
+
+impl McpToolFormat {
+    pub fn new(name: &str) -> Self {
+        McpToolFormat(name.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for McpToolFormat {
+    fn default() -> Self {
+        McpToolFormat::new("standard")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +117,13 @@ pub struct McpToolInfo {
     pub json_path: String,
     pub format: McpToolFormat,
     pub name: String,
+    /// Project-level config file relative to a workspace root (`.mcp.json`).
+    /// Each registered workspace gets its own sync target for it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_config_path: Option<String>,
+    /// Set on project-level targets: the workspace they belong to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -123,15 +157,81 @@ pub struct McpServerConflict {
     pub tool_id: String,
 }
 
+/// Outcome of merging the source list into one tool. Without a recorded
+/// last-synced snapshot only `added`, `kept` and `conflicts` are filled.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpMergeResult {
     pub tool_id: String,
+    /// New in the source; written to the tool
     pub added: Vec<McpServer>,
+    /// Changed in the source only; replaces the tool's copy
+    #[serde(default)]
+    pub updated: Vec<McpServer>,
+    /// Deleted from the source since the last sync; removed from the tool
+    #[serde(default)]
+    pub removed: Vec<McpServer>,
+    /// Present in the tool and staying as they are
     pub kept: Vec<McpServer>,
+    /// Added or edited in the tool's own config; flows back to the source
+    #[serde(default)]
+    pub source_updates: Vec<McpServer>,
+    /// Deleted in the tool's own config; removed from the source
+    #[serde(default)]
+    pub source_removals: Vec<McpServer>,
     pub conflicts: Vec<McpServerConflict>,
 }
 
+impl McpMergeResult {
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty()
+            || !self.updated.is_empty()
+            || !self.removed.is_empty()
+            || !self.source_updates.is_empty()
+            || !self.source_removals.is_empty()
+            || !self.conflicts.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpAutoSyncAction {
+    /// The change was written to the tool
+    Applied,
+    /// The tool has conflicts and waits for a manual sync
+    Queued,
+    Failed,
+}
+
+/// One tool's outcome of an auto-sync run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSyncLogEntry {
+    pub timestamp: u64,
+    pub tool_id: String,
+    pub config_path: String,
+    pub action: McpAutoSyncAction,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSyncLog {
+    /// Oldest first, capped at the most recent entries
+    pub entries: Vec<McpSyncLogEntry>,
+    /// Tools auto-sync skipped because of conflicts
+    pub review_queue: Vec<String>,
+}
+
+/// Servers as they were written to a tool by the last successful sync. This
+/// is the common ancestor for the next three-way merge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSyncSnapshot {
+    pub servers: Vec<McpServer>,
+    pub synced_at: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpSyncPreview {
@@ -139,6 +239,10 @@ pub struct McpSyncPreview {
     pub tool_name: String,
     pub merge_result: McpMergeResult,
     pub has_changes: bool,
+    /// Fingerprint of the tool's config file when previewed. Passing it back
+    /// to `sync_mcp_to_all` aborts the sync if the file changed since.
+    #[serde(default)]
+    pub fingerprint: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -169,70 +273,169 @@ pub struct McpSyncResult {
     pub servers_written: u32,
 }
 
-// MCP tool definitions - which tools support MCP and their config locations
+/// A project entry in ~/.claude.json, which holds Claude Code's
+/// project-scoped servers under `projects["/abs/path"].mcpServers`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeProject {
+    /// Absolute project path, as Claude Code keys it
+    pub path: String,
+    pub name: String,
+    pub server_count: u32,
+    /// Whether the project directory still exists
+    pub exists: bool,
+}
+
+// Built-in MCP tool definitions - which tools support MCP and their config locations.
+// User-defined tools are merged in by `config::ToolRegistry`.
 pub fn get_mcp_tool_definitions() -> Vec<McpToolInfo> {
     vec![
         McpToolInfo {
             tool_id: "claude-code".to_string(),
             config_path: "~/.claude.json".to_string(),
             json_path: "mcpServers".to_string(),
-            format: McpToolFormat::Standard,
+            format: McpToolFormat::new("standard"),
             name: "Claude Code".to_string(),
+            project_config_path: Some(".mcp.json".to_string()),
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "gemini-cli".to_string(),
             config_path: "~/.gemini/settings.json".to_string(),
             json_path: "mcpServers".to_string(),
-            format: McpToolFormat::Standard,
+            format: McpToolFormat::new("standard"),
             name: "Gemini CLI".to_string(),
+            project_config_path: None,
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "amp".to_string(),
             config_path: "~/.config/amp/settings.json".to_string(),
             json_path: "amp.mcpServers".to_string(),
-            format: McpToolFormat::Standard,
+            format: McpToolFormat::new("amp"),
             name: "Amp".to_string(),
+            project_config_path: Some(".amp/settings.json".to_string()),
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "copilot-cli".to_string(),
             config_path: "~/.copilot/mcp-config.json".to_string(),
             json_path: "servers".to_string(),
-            format: McpToolFormat::Copilot,
+            format: McpToolFormat::new("copilot"),
             name: "GitHub Copilot CLI".to_string(),
+            project_config_path: None,
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "opencode".to_string(),
             config_path: "~/.config/opencode/opencode.json".to_string(),
             json_path: "mcp".to_string(),
-            format: McpToolFormat::Opencode,
+            format: McpToolFormat::new("opencode"),
             name: "OpenCode".to_string(),
+            project_config_path: Some("opencode.json".to_string()),
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "factory-droid".to_string(),
             config_path: "~/.factory/mcp.json".to_string(),
             json_path: "mcpServers".to_string(),
-            format: McpToolFormat::Standard,
+            format: McpToolFormat::new("standard"),
             name: "Factory Droid CLI".to_string(),
+            project_config_path: None,
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "qwen-code".to_string(),
             config_path: "~/.qwen/settings.json".to_string(),
             json_path: "mcpServers".to_string(),
-            format: McpToolFormat::Standard,
+            format: McpToolFormat::new("standard"),
             name: "Qwen Code".to_string(),
+            project_config_path: Some(".qwen/settings.json".to_string()),
+            workspace: None,
         },
         McpToolInfo {
             tool_id: "qoder-cli".to_string(),
             config_path: "~/.qoder.json".to_string(),
             json_path: "mcpServers".to_string(),
-            format: McpToolFormat::Standard,
+            format: McpToolFormat::new("standard"),
             name: "Qoder CLI".to_string(),
+            project_config_path: None,
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "cursor".to_string(),
+            config_path: "~/.cursor/mcp.json".to_string(),
+            json_path: "mcpServers".to_string(),
+            format: McpToolFormat::new("standard"),
+            name: "Cursor".to_string(),
+            project_config_path: Some(".cursor/mcp.json".to_string()),
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "kiro-cli".to_string(),
+            config_path: "~/.kiro/settings/mcp.json".to_string(),
+            json_path: "mcpServers".to_string(),
+            format: McpToolFormat::new("standard"),
+            name: "Kiro".to_string(),
+            project_config_path: Some(".kiro/settings/mcp.json".to_string()),
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "kilo-code-cli".to_string(),
+            config_path: "~/.kilocode/mcp_settings.json".to_string(),
+            json_path: "mcpServers".to_string(),
+            format: McpToolFormat::new("standard"),
+            name: "Kilo Code".to_string(),
+            project_config_path: Some(".kilocode/mcp.json".to_string()),
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "continue".to_string(),
+            config_path: "~/.continue/config.yaml".to_string(),
+            json_path: "mcpServers".to_string(),
+            format: McpToolFormat::new("continue"),
+            name: "Continue".to_string(),
+            project_config_path: None,
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "codex".to_string(),
+            config_path: "~/.codex/config.toml".to_string(),
+            json_path: "mcp_servers".to_string(),
+            format: McpToolFormat::new("codex"),
+            name: "Codex CLI".to_string(),
+            project_config_path: None,
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "zed".to_string(),
+            config_path: SettingsPaths {
+                linux: "~/.config/zed/settings.json".to_string(),
+                macos: "~/.config/zed/settings.json".to_string(),
+                windows: "%APPDATA%\\Zed\\settings.json".to_string(),
+            }
+            .current()
+            .to_string(),
+            json_path: "context_servers".to_string(),
+            format: McpToolFormat::new("zed"),
+            name: "Zed".to_string(),
+            project_config_path: Some(".zed/settings.json".to_string()),
+            workspace: None,
+        },
+        McpToolInfo {
+            tool_id: "vscode".to_string(),
+            config_path: SettingsPaths {
+                linux: "~/.config/Code/User/settings.json".to_string(),
+                macos: "~/Library/Application Support/Code/User/settings.json".to_string(),
+                windows: "%APPDATA%\\Code\\User\\settings.json".to_string(),
+            }
+            .current()
+            .to_string(),
+            json_path: "mcp.servers".to_string(),
+            format: McpToolFormat::new("vscode"),
+            name: "VS Code".to_string(),
+            project_config_path: None,
+            workspace: None,
         },
     ]
 }
-
-pub fn get_mcp_tool_info(tool_id: &str) -> Option<McpToolInfo> {
-    get_mcp_tool_definitions()
-        .into_iter()
-        .find(|t| t.tool_id == tool_id)
-}
@@ -0,0 +1,647 @@
+use crate::app_paths::AppPaths;
+use crate::mcp::{
+    get_source_servers, load_mcp_config_internal, save_mcp_config_internal, McpError, McpServer,
+    McpSourceMode,
+};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use thiserror::Error;
+
+mod scan;
+
+pub use scan::*;
+
+/// Passphrase for headless use (CLI, CI) when the vault was not unlocked in
+/// this process.
+pub const PASSPHRASE_ENV: &str = "CLI_CONFIG_EDITOR_VAULT_PASSPHRASE";
+
+const VAULT_VERSION: u32 = 1;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const REFERENCE_PREFIX: &str = "${secret:";
+
+#[derive(Error, Debug)]
+pub enum SecretError {
+    #[error("Secret vault is locked; unlock it or set {}", PASSPHRASE_ENV)]
+    Locked,
+    #[error("Wrong passphrase or corrupted secret vault")]
+    WrongPassphrase,
+    #[error("Secret not found: {0}")]
+    NotFound(String),
+    #[error("Invalid secret name: {0}")]
+    InvalidName(String),
+    #[error("Unsupported secret vault: {0}")]
+    Unsupported(String),
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to get app data directory")]
+    AppDataDir,
+}
+
+impl Serialize for SecretError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// On-disk form of the vault: the secret map as JSON, sealed with
+/// ChaCha20-Poly1305 under a key derived from the passphrase.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// An unlocked vault. Only the derived key is kept, never the passphrase.
+pub struct SecretVault {
+    path: PathBuf,
+    key: [u8; 32],
+    salt: Vec<u8>,
+    iterations: u32,
+    secrets: BTreeMap<String, String>,
+}
+
+impl SecretVault {
+    /// Open the vault at `path`, or start an empty one if it does not exist.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, SecretError> {
+        Self::open_with_iterations(path, passphrase, PBKDF2_ITERATIONS)
+    }
+
+    fn open_with_iterations(
+        path: &Path,
+        passphrase: &str,
+        new_iterations: u32,
+    ) -> Result<Self, SecretError> {
+        if !path.exists() {
+            let mut salt = vec![0u8; SALT_LEN];
+            fill_random(&mut salt)?;
+            return Ok(SecretVault {
+                path: path.to_path_buf(),
+                key: derive_key(passphrase, &salt, new_iterations)?,
+                salt,
+                iterations: new_iterations,
+                secrets: BTreeMap::new(),
+            });
+        }
+
+        let file: VaultFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if file.version != VAULT_VERSION {
+            return Err(SecretError::Unsupported(format!(
+                "version {}",
+                file.version
+            )));
+        }
+
+        let salt = decode(&file.salt)?;
+        let nonce: [u8; NONCE_LEN] = decode(&file.nonce)?
+            .try_into()
+            .map_err(|_| SecretError::WrongPassphrase)?;
+        let mut buffer = decode(&file.ciphertext)?;
+
+        let key = derive_key(passphrase, &salt, file.iterations)?;
+        let plaintext = sealing_key(&key)?
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut buffer,
+            )
+            .map_err(|_| SecretError::WrongPassphrase)?;
+
+        Ok(SecretVault {
+            path: path.to_path_buf(),
+            key,
+            salt,
+            iterations: file.iterations,
+            secrets: serde_json::from_slice(plaintext)?,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), SecretError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        fill_random(&mut nonce)?;
+
+        let mut buffer = serde_json::to_vec(&self.secrets)?;
+        sealing_key(&self.key)?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut buffer,
+            )
+            .map_err(|_| SecretError::Crypto("encryption failed".to_string()))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            iterations: self.iterations,
+            salt: BASE64.encode(&self.salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(&buffer),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_string_pretty(&file)?)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).map(String::as_str)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), SecretError> {
+        if !is_valid_secret_name(name) {
+            return Err(SecretError::InvalidName(name.to_string()));
+        }
+        self.secrets.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<(), SecretError> {
+        self.secrets
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| SecretError::NotFound(name.to_string()))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.secrets.keys().cloned().collect()
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<[u8; 32], SecretError> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| SecretError::Unsupported("zero KDF iterations".to_string()))?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    Ok(key)
+}
+
+fn sealing_key(key: &[u8; 32]) -> Result<LessSafeKey, SecretError> {
+    UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| SecretError::Crypto("invalid key".to_string()))
+}
+
+fn fill_random(buffer: &mut [u8]) -> Result<(), SecretError> {
+    SystemRandom::new()
+        .fill(buffer)
+        .map_err(|_| SecretError::Crypto("no system randomness".to_string()))
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, SecretError> {
+    BASE64
+        .decode(value)
+        .map_err(|_| SecretError::WrongPassphrase)
+}
+
+/// Secret names are embedded in `${secret:name}` references, so keep them to
+/// ASCII letters, digits, `_`, `-` and `.`.
+pub fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// `${secret:name}` for `name`.
+pub fn secret_reference(name: &str) -> String {
+    format!("{}{}}}", REFERENCE_PREFIX, name)
+}
+
+/// The secret name if `value` is exactly one `${secret:name}` reference.
+pub fn parse_secret_reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(REFERENCE_PREFIX)
+        .and_then(|rest| rest.strip_suffix('}'))
+        .filter(|name| is_valid_secret_name(name))
+}
+
+// Vault unlocked for the lifetime of the process (the GUI session)
+static SESSION: Mutex<Option<SecretVault>> = Mutex::new(None);
+
+fn get_vault_path(app: &impl AppPaths) -> Result<PathBuf, SecretError> {
+    let app_data = app.app_data_dir().ok_or(SecretError::AppDataDir)?;
+    Ok(app_data.join("secrets.vault"))
+}
+
+/// Run `f` on the unlocked vault: the session vault if it was unlocked for
+/// these app paths, otherwise one opened with `PASSPHRASE_ENV`.
+fn with_vault<T>(
+    app: &impl AppPaths,
+    f: impl FnOnce(&mut SecretVault) -> Result<T, SecretError>,
+) -> Result<T, SecretError> {
+    let path = get_vault_path(app)?;
+
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(vault) = session.as_mut().filter(|vault| vault.path == path) {
+        return f(vault);
+    }
+    drop(session);
+
+    let passphrase = std::env::var(PASSPHRASE_ENV).map_err(|_| SecretError::Locked)?;
+    f(&mut SecretVault::open(&path, &passphrase)?)
+}
+
+pub fn unlock_secret_vault_internal(
+    app: &impl AppPaths,
+    passphrase: &str,
+) -> Result<Vec<String>, SecretError> {
+    let vault = SecretVault::open(&get_vault_path(app)?, passphrase)?;
+    let names = vault.names();
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = Some(vault);
+    Ok(names)
+}
+
+pub fn lock_secret_vault_internal() {
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn list_secrets_internal(app: &impl AppPaths) -> Result<Vec<String>, SecretError> {
+    with_vault(app, |vault| Ok(vault.names()))
+}
+
+pub fn set_secret_internal(
+    app: &impl AppPaths,
+    name: &str,
+    value: &str,
+) -> Result<(), SecretError> {
+    with_vault(app, |vault| {
+        vault.set(name, value)?;
+        vault.save()
+    })
+}
+
+pub fn delete_secret_internal(app: &impl AppPaths, name: &str) -> Result<(), SecretError> {
+    with_vault(app, |vault| {
+        vault.remove(name)?;
+        vault.save()
+    })
+}
+
+/// Copies of `servers` with every `${secret:..}` env reference replaced by
+/// its value. The vault is only needed when a reference is present.
+pub fn resolve_server_secrets(
+    app: &impl AppPaths,
+    servers: &[McpServer],
+) -> Result<Vec<McpServer>, SecretError> {
+    let has_references = servers
+        .iter()
+        .flat_map(|s| s.env.iter().flatten())
+        .any(|(_, value)| parse_secret_reference(value).is_some());
+    if !has_references {
+        return Ok(servers.to_vec());
+    }
+
+    with_vault(app, |vault| {
+        let mut resolved = servers.to_vec();
+        for (_, value) in resolved.iter_mut().flat_map(|s| s.env.iter_mut().flatten()) {
+            if let Some(name) = parse_secret_reference(value) {
+                *value = vault
+                    .get(name)
+                    .ok_or_else(|| SecretError::NotFound(name.to_string()))?
+                    .to_string();
+            }
+        }
+        Ok(resolved)
+    })
+}
+
+/// Put references back into servers read from a tool's config wherever the
+/// tool holds the value the source's reference resolves to, so a written
+/// secret does not look like a tool-side edit. With the vault locked the
+/// value cannot be checked and is assumed to match.
+pub fn rereference_server_secrets(
+    app: &impl AppPaths,
+    servers: &mut [McpServer],
+    source: &[McpServer],
+) {
+    if secret_usages(source).is_empty() {
+        return;
+    }
+    let secrets = with_vault(app, |vault| Ok(vault.secrets.clone())).ok();
+
+    for server in servers.iter_mut() {
+        let Some(source_env) = source
+            .iter()
+            .find(|s| s.name == server.name)
+            .and_then(|s| s.env.as_ref())
+        else {
+            continue;
+        };
+        for (key, value) in server.env.iter_mut().flatten() {
+            let Some(name) = source_env.get(key).and_then(|v| parse_secret_reference(v)) else {
+                continue;
+            };
+            let matches = secrets
+                .as_ref()
+                .is_none_or(|secrets| secrets.get(name) == Some(value));
+            if matches {
+                *value = secret_reference(name);
+            }
+        }
+    }
+}
+
+/// Where a secret is referenced from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretUsage {
+    pub secret: String,
+    pub server_name: String,
+    pub env_key: String,
+}
+
+pub fn secret_usages(servers: &[McpServer]) -> Vec<SecretUsage> {
+    let mut usages: Vec<SecretUsage> = servers
+        .iter()
+        .flat_map(|server| {
+            server.env.iter().flatten().filter_map(|(key, value)| {
+                parse_secret_reference(value).map(|name| SecretUsage {
+                    secret: name.to_string(),
+                    server_name: server.name.clone(),
+                    env_key: key.clone(),
+                })
+            })
+        })
+        .collect();
+    usages.sort_by(|a, b| {
+        (&a.secret, &a.server_name, &a.env_key).cmp(&(&b.secret, &b.server_name, &b.env_key))
+    });
+    usages
+}
+
+pub fn list_secret_usages_internal(app: &impl AppPaths) -> Result<Vec<SecretUsage>, McpError> {
+    Ok(secret_usages(&get_source_servers(app)?))
+}
+
+/// Move every plaintext env value of the app-managed servers into the vault
+/// as `<server>_<key>` and leave a reference in its place. A name already
+/// holding a different value gets a numeric suffix instead of being
+/// overwritten.
+pub fn extract_mcp_secrets_internal(app: &impl AppPaths) -> Result<Vec<SecretUsage>, McpError> {
+    let mut config = load_mcp_config_internal(app)?;
+    if config.source_mode != McpSourceMode::AppManaged {
+        return Err(McpError::InvalidFormat(
+            "Secret references need the app-managed MCP source".to_string(),
+        ));
+    }
+
+    let mut extracted = Vec::new();
+    with_vault(app, |vault| {
+        for server in config.servers.iter_mut() {
+            for (key, value) in server.env.iter_mut().flatten() {
+                if parse_secret_reference(value).is_some() {
+                    continue;
+                }
+                let name = free_secret_name(vault, &secret_name_for(&server.name, key), value);
+                vault.set(&name, value)?;
+                *value = secret_reference(&name);
+                extracted.push(SecretUsage {
+                    secret: name,
+                    server_name: server.name.clone(),
+                    env_key: key.clone(),
+                });
+            }
+        }
+        vault.save()
+    })?;
+
+    save_mcp_config_internal(app, &config)?;
+    extracted.sort_by(|a, b| a.secret.cmp(&b.secret));
+    Ok(extracted)
+}
+
+fn secret_name_for(server: &str, key: &str) -> String {
+    format!("{}_{}", server, key)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// `base`, or `base_2`, `base_3`, ... for the first name that is unused or
+/// already holds `value`.
+fn free_secret_name(vault: &SecretVault, base: &str, value: &str) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => base.to_string(),
+            n => format!("{}_{}", base, n),
+        })
+        .find(|name| vault.get(name).is_none_or(|existing| existing == value))
+        .expect("unbounded suffixes")
+}
+
+#[tauri::command]
+pub fn unlock_secret_vault(app: AppHandle, passphrase: String) -> Result<Vec<String>, SecretError> {
+    unlock_secret_vault_internal(&app, &passphrase)
+}
+
+#[tauri::command]
+pub fn lock_secret_vault() {
+    lock_secret_vault_internal()
+}
+
+#[tauri::command]
+pub fn list_secrets(app: AppHandle) -> Result<Vec<String>, SecretError> {
+    list_secrets_internal(&app)
+}
+
+#[tauri::command]
+pub fn set_secret(app: AppHandle, name: String, value: String) -> Result<(), SecretError> {
+    set_secret_internal(&app, &name, &value)
+}
+
+#[tauri::command]
+pub fn delete_secret(app: AppHandle, name: String) -> Result<(), SecretError> {
+    delete_secret_internal(&app, &name)
+}
+
+#[tauri::command]
+pub fn list_secret_usages(app: AppHandle) -> Result<Vec<SecretUsage>, McpError> {
+    list_secret_usages_internal(&app)
+}
+
+#[tauri::command]
+pub fn extract_mcp_secrets(app: AppHandle) -> Result<Vec<SecretUsage>, McpError> {
+    extract_mcp_secrets_internal(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn temp_vault_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("secrets-test-{}-{}", name, uuid::Uuid::new_v4()))
+            .join("secrets.vault")
+    }
+
+    fn server_with_env(name: &str, env: &[(&str, &str)]) -> McpServer {
+        McpServer {
+            name: name.to_string(),
+            command: "cmd".to_string(),
+            args: None,
+            env: Some(
+                env.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            disabled: None,
+            url: None,
+            target: None,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_vault_roundtrip_and_wrong_passphrase() {
+        let path = temp_vault_path("roundtrip");
+
+        let mut vault = SecretVault::open_with_iterations(&path, "correct horse", 1_000).unwrap();
+        vault.set("github_token", "ghp_abc").unwrap();
+        vault.save().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("ghp_abc"));
+
+        let reopened = SecretVault::open(&path, "correct horse").unwrap();
+        assert_eq!(reopened.get("github_token"), Some("ghp_abc"));
+
+        assert!(matches!(
+            SecretVault::open(&path, "battery staple"),
+            Err(SecretError::WrongPassphrase)
+        ));
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_invalid_secret_name() {
+        let mut vault =
+            SecretVault::open_with_iterations(&temp_vault_path("names"), "pw", 1_000).unwrap();
+        assert!(matches!(
+            vault.set("bad name}", "x"),
+            Err(SecretError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_secret_reference() {
+        assert_eq!(
+            parse_secret_reference("${secret:github_token}"),
+            Some("github_token")
+        );
+        assert_eq!(
+            parse_secret_reference(&secret_reference("a.b-c")),
+            Some("a.b-c")
+        );
+        assert_eq!(parse_secret_reference("${secret:}"), None);
+        assert_eq!(parse_secret_reference("prefix ${secret:x}"), None);
+        assert_eq!(parse_secret_reference("${GITHUB_TOKEN}"), None);
+    }
+
+    #[test]
+    fn test_secret_usages() {
+        let servers = vec![
+            server_with_env(
+                "github",
+                &[("GITHUB_TOKEN", "${secret:gh}"), ("DEBUG", "1")],
+            ),
+            server_with_env("gitlab", &[("TOKEN", "${secret:gh}")]),
+        ];
+
+        let usages = secret_usages(&servers);
+        assert_eq!(usages.len(), 2);
+        assert!(usages.iter().all(|u| u.secret == "gh"));
+        assert_eq!(usages[0].server_name, "github");
+        assert_eq!(usages[1].env_key, "TOKEN");
+    }
+
+    #[test]
+    fn test_rereference_with_locked_vault() {
+        let paths = crate::app_paths::StandalonePaths {
+            data_dir: Some(temp_vault_path("locked").parent().unwrap().to_path_buf()),
+            config_dir: None,
+        };
+        let source = vec![server_with_env(
+            "github",
+            &[("GITHUB_TOKEN", "${secret:gh}"), ("DEBUG", "1")],
+        )];
+        let mut target = vec![server_with_env(
+            "github",
+            &[("GITHUB_TOKEN", "ghp_abc"), ("DEBUG", "2")],
+        )];
+
+        rereference_server_secrets(&paths, &mut target, &source);
+
+        let env = target[0].env.as_ref().unwrap();
+        assert_eq!(env["GITHUB_TOKEN"], "${secret:gh}");
+        assert_eq!(env["DEBUG"], "2");
+        assert!(matches!(
+            resolve_server_secrets(&paths, &source),
+            Err(SecretError::Locked)
+        ));
+    }
+
+    #[test]
+    fn test_secret_name_for() {
+        assert_eq!(secret_name_for("My Server", "API_KEY"), "my_server_api_key");
+    }
+    #[test]
+    fn test_free_secret_name_avoids_overwrites() {
+        let path = temp_vault_path("collision");
+        let mut vault = SecretVault::open_with_iterations(&path, "pass", 1_000).unwrap();
+        vault.set("my_server_api_key", "first").unwrap();
+        vault.set("my_server_api_key_2", "second").unwrap();
+
+        assert_eq!(free_secret_name(&vault, "other_key", "x"), "other_key");
+        assert_eq!(
+            free_secret_name(&vault, "my_server_api_key", "first"),
+            "my_server_api_key"
+        );
+        assert_eq!(
+            free_secret_name(&vault, "my_server_api_key", "second"),
+            "my_server_api_key_2"
+        );
+        assert_eq!(
+            free_secret_name(&vault, "my_server_api_key", "third"),
+            "my_server_api_key_3"
+        );
+    }
+}
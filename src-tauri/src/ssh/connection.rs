@@ -65,6 +65,22 @@ impl SshConnection {
         Ok(Self { user, host, port, path })
     }
 
+    /// Parse `path` only if it names a remote file. Local paths (`~/..`, `/..`,
+    /// `./..`, `%APPDATA%\..`, `C:\..`) are never taken for `host:path`.
+    pub fn parse_remote(path: &str) -> Option<Self> {
+        let path = path.trim();
+        let bytes = path.as_bytes();
+        let is_drive = bytes.len() >= 2
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes.get(2), None | Some(b'\\') | Some(b'/'));
+
+        if is_drive || path.starts_with(['~', '/', '.', '\\', '%']) {
+            return None;
+        }
+        Self::parse(path).ok()
+    }
+
     fn parse_host_port_path(s: &str) -> Result<(String, Option<u16>, String), super::SshError> {
         if s.starts_with('[') {
             if let Some(bracket_end) = s.find(']') {
@@ -243,6 +259,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_remote_skips_local_paths() {
+        for local in [
+            "~/.claude.json",
+            "/etc/config.json",
+            "./config.json",
+            "C:\\Users\\me\\config.json",
+            "%APPDATA%\\tool\\config.json",
+        ] {
+            assert!(SshConnection::parse_remote(local).is_none(), "{}", local);
+        }
+
+        let conn = SshConnection::parse_remote("dev@box:~/.claude.json").unwrap();
+        assert_eq!(conn.ssh_target(), "dev@box");
+        assert_eq!(conn.path, "~/.claude.json");
+    }
+
     #[test]
     fn test_ssh_target() {
         let conn = SshConnection::parse("user@host:/path").unwrap();
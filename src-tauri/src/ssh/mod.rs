@@ -13,6 +13,9 @@ pub use commands::{
 };
 pub use connection::{SshConnection, SshStatus};
 pub use error::SshError;
+pub use operations::{
+    ssh_backup_file, ssh_file_exists, ssh_read_file, ssh_remove_file, ssh_write_file,
+};
 
 #[cfg(test)]
 mod tests;
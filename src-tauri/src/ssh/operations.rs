@@ -16,14 +16,14 @@ pub fn ssh_test_connection(conn: &SshConnection) -> SshStatus {
 pub fn ssh_file_exists(conn: &SshConnection) -> Result<bool, SshError> {
     let cmd = format!(
         "test -f {} && echo 'exists' || echo 'not_exists'",
-        shell_escape(&conn.path)
+        remote_path(&conn.path)
     );
     let output = run_ssh_command(conn, &cmd, DEFAULT_TIMEOUT_SECS)?;
     Ok(output.trim() == "exists")
 }
 
 pub fn ssh_read_file(conn: &SshConnection) -> Result<String, SshError> {
-    let cmd = format!("cat {}", shell_escape(&conn.path));
+    let cmd = format!("cat {}", remote_path(&conn.path));
     run_ssh_command(conn, &cmd, DEFAULT_TIMEOUT_SECS)
 }
 
@@ -32,10 +32,7 @@ pub fn ssh_write_file(conn: &SshConnection, content: &str) -> Result<(), SshErro
     let mut args = conn.ssh_args();
     args.push(target);
 
-    let cmd = format!(
-        "cat > {}",
-        shell_escape(&conn.path)
-    );
+    let cmd = format!("cat > {}", remote_path(&conn.path));
     args.push(cmd);
 
     let mut child = Command::new("ssh")
@@ -64,13 +61,19 @@ pub fn ssh_backup_file(conn: &SshConnection) -> Result<String, SshError> {
     let backup_path = format!("{}.bak", conn.path);
     let cmd = format!(
         "cp {} {}",
-        shell_escape(&conn.path),
-        shell_escape(&backup_path)
+        remote_path(&conn.path),
+        remote_path(&backup_path)
     );
     run_ssh_command(conn, &cmd, DEFAULT_TIMEOUT_SECS)?;
     Ok(backup_path)
 }
 
+pub fn ssh_remove_file(conn: &SshConnection) -> Result<(), SshError> {
+    let cmd = format!("rm -f {}", remote_path(&conn.path));
+    run_ssh_command(conn, &cmd, DEFAULT_TIMEOUT_SECS)?;
+    Ok(())
+}
+
 fn run_ssh_command(conn: &SshConnection, remote_cmd: &str, timeout_secs: u64) -> Result<String, SshError> {
     let target = conn.ssh_target();
     let mut args = conn.ssh_args();
@@ -115,6 +118,15 @@ fn shell_escape(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\"'\"'"))
 }
 
+/// Quote a remote path for the shell, leaving a leading `~/` unquoted so it
+/// still resolves to the remote user's home directory.
+pub(super) fn remote_path(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/{}", shell_escape(rest)),
+        None => shell_escape(path),
+    }
+}
+
 pub fn ssh_read_file_with_retry(
     conn: &SshConnection,
     max_attempts: u32,
@@ -1,3 +1,4 @@
+use super::operations::remote_path;
 use super::*;
 
 #[test]
@@ -7,3 +8,9 @@ fn test_ssh_error_retryable() {
     assert!(!SshError::AuthenticationFailed("test".into()).is_retryable());
     assert!(!SshError::FileNotFound("test".into()).is_retryable());
 }
+
+#[test]
+fn test_remote_path_expands_home() {
+    assert_eq!(remote_path("~/.claude.json"), "\"$HOME\"/'.claude.json'");
+    assert_eq!(remote_path("/etc/it's.json"), "'/etc/it'\"'\"'s.json'");
+}
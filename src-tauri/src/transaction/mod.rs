@@ -0,0 +1,611 @@
+use crate::app_paths::AppPaths;
+use crate::commands::{create_backup, expand_path, BackupSettings};
+use crate::ssh::{
+    ssh_backup_file, ssh_read_file, ssh_remove_file, ssh_write_file, SshConnection, SshError,
+};
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Fingerprint of a file that does not exist.
+const MISSING: &str = "missing";
+
+#[derive(Error, Debug)]
+pub enum TransactionError {
+    #[error("{0} changed since it was previewed")]
+    Changed(String),
+    #[error("Writing {file} failed, every file was rolled back: {cause}")]
+    RolledBack { file: String, cause: String },
+    #[error("Writing {file} failed ({cause}) and these files could not be restored: {}", .unrestored.join(", "))]
+    RollbackFailed {
+        file: String,
+        cause: String,
+        unrestored: Vec<String>,
+    },
+    #[error("Cannot resolve path: {0}")]
+    PathResolution(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("SSH error: {0}")]
+    Ssh(#[from] SshError),
+    #[error("Failed to get app data directory")]
+    AppDataDir,
+}
+
+impl Serialize for TransactionError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Short content fingerprint used to check that a file is still what a
+/// preview was computed from. A missing file has its own fingerprint.
+pub fn fingerprint(content: Option<&str>) -> String {
+    match content {
+        Some(content) => {
+            let hash = digest(&SHA256, content.as_bytes());
+            let hex: String = hash.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+            format!("sha256:{}", hex)
+        }
+        None => MISSING.to_string(),
+    }
+}
+
+/// A file written by a transaction, on this machine or over SSH.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileTarget {
+    Local(PathBuf),
+    Remote(SshConnection),
+}
+
+impl FileTarget {
+    /// `user@host:path` is remote, anything else is expanded locally.
+    pub fn parse(path: &str) -> Result<Self, TransactionError> {
+        if let Some(conn) = SshConnection::parse_remote(path) {
+            return Ok(FileTarget::Remote(conn));
+        }
+        expand_path(path)
+            .map(FileTarget::Local)
+            .ok_or_else(|| TransactionError::PathResolution(path.to_string()))
+    }
+
+    pub fn read(&self) -> Result<Option<String>, TransactionError> {
+        match self {
+            FileTarget::Local(path) if path.exists() => Ok(Some(fs::read_to_string(path)?)),
+            FileTarget::Local(_) => Ok(None),
+            FileTarget::Remote(conn) => match ssh_read_file(conn) {
+                Ok(content) => Ok(Some(content)),
+                Err(SshError::FileNotFound(_)) => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+
+    fn write(&self, content: &str) -> Result<(), TransactionError> {
+        match self {
+            FileTarget::Local(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let temp_path = path.with_extension("tmp");
+                fs::write(&temp_path, content)?;
+                fs::rename(&temp_path, path)?;
+            }
+            FileTarget::Remote(conn) => ssh_write_file(conn, content)?,
+        }
+        Ok(())
+    }
+
+    fn remove(&self) -> Result<(), TransactionError> {
+        match self {
+            FileTarget::Local(path) if path.exists() => fs::remove_file(path)?,
+            FileTarget::Local(_) => {}
+            FileTarget::Remote(conn) => ssh_remove_file(conn)?,
+        }
+        Ok(())
+    }
+
+    /// Put `original` back, or delete the file if it did not exist.
+    fn restore(&self, original: Option<&str>) -> Result<(), TransactionError> {
+        match original {
+            Some(content) => self.write(content),
+            None => self.remove(),
+        }
+    }
+
+    /// Back up the file before it is replaced, as single-file writes do.
+    /// Remote files keep one `.bak`, like remote saves.
+    fn back_up(&self, settings: &BackupSettings) -> Result<(), TransactionError> {
+        match self {
+            FileTarget::Local(path) => create_backup(path, settings)?,
+            FileTarget::Remote(conn) if settings.enabled && settings.max_backups > 0 => {
+                ssh_backup_file(conn)?;
+            }
+            FileTarget::Remote(_) => {}
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FileTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileTarget::Local(path) => write!(f, "{}", path.display()),
+            FileTarget::Remote(conn) => write!(f, "{}", conn),
+        }
+    }
+}
+
+/// On-disk record of a commit in progress: what every file held before it
+/// was touched. A journal left behind by a crash is rolled back on the next
+/// start; one marked `committed` only needs deleting.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Journal {
+    id: String,
+    committed: bool,
+    entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalEntry {
+    target: FileTarget,
+    original: Option<String>,
+}
+
+impl Journal {
+    fn save(&self, path: &Path) -> Result<(), TransactionError> {
+        let temp_path = path.with_extension("tmp");
+        write_private(&temp_path, &serde_json::to_string(self)?)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Restore every entry, returning the ones that could not be restored.
+    fn roll_back(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.target.restore(entry.original.as_deref()).is_err())
+            .map(|entry| entry.target.to_string())
+            .collect()
+    }
+}
+
+/// Write a file only its owner can read. Journals hold the full original
+/// content of every file they cover, secrets included.
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    // The mode only applies to new files, so never reuse a leftover one
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content.as_bytes())
+}
+
+/// Exclusive lock on a journal, held for the whole commit. Recovery runs in
+/// every process that starts (the GUI and each CLI call), so it must not roll
+/// back a journal another process is still committing. The OS releases the
+/// lock when its holder dies, which is what marks a journal as abandoned.
+struct JournalLock {
+    file: fs::File,
+    path: PathBuf,
+}
+
+impl JournalLock {
+    fn open(journal_path: &Path) -> std::io::Result<(fs::File, PathBuf)> {
+        let path = journal_path.with_extension("lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        Ok((file, path))
+    }
+
+    fn acquire(journal_path: &Path) -> Result<Self, TransactionError> {
+        let (file, path) = Self::open(journal_path)?;
+        file.lock()?;
+        Ok(JournalLock { file, path })
+    }
+
+    /// `None` while another commit holds the lock
+    fn try_acquire(journal_path: &Path) -> Result<Option<Self>, TransactionError> {
+        let (file, path) = Self::open(journal_path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(JournalLock { file, path })),
+            Err(fs::TryLockError::WouldBlock) => Ok(None),
+            Err(fs::TryLockError::Error(e)) => Err(e.into()),
+        }
+    }
+}
+
+impl Drop for JournalLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn journal_dir(app: &impl AppPaths) -> Result<PathBuf, TransactionError> {
+    let app_data = app.app_data_dir().ok_or(TransactionError::AppDataDir)?;
+    Ok(app_data.join("transactions"))
+}
+
+struct StagedWrite {
+    target: FileTarget,
+    content: String,
+    expected: Option<String>,
+}
+
+/// A set of file writes that land together or not at all.
+pub struct Transaction {
+    journal_dir: PathBuf,
+    backup_settings: BackupSettings,
+    writes: Vec<StagedWrite>,
+}
+
+impl Transaction {
+    /// Replaced files are backed up as `backup_settings` ask, or with a
+    /// single `.bak` without them.
+    pub fn new(
+        app: &impl AppPaths,
+        backup_settings: Option<BackupSettings>,
+    ) -> Result<Self, TransactionError> {
+        Ok(Transaction {
+            journal_dir: journal_dir(app)?,
+            backup_settings: backup_settings.unwrap_or_default(),
+            writes: Vec::new(),
+        })
+    }
+
+    /// Queue `content` for `target`. With `expected`, the commit only goes
+    /// ahead if the file's `fingerprint` still matches. Staging the same
+    /// target again replaces the content but keeps the first expectation.
+    pub fn stage(&mut self, target: FileTarget, content: String, expected: Option<String>) {
+        match self.writes.iter_mut().find(|write| write.target == target) {
+            Some(write) => {
+                write.content = content;
+                write.expected = write.expected.take().or(expected);
+            }
+            None => self.writes.push(StagedWrite {
+                target,
+                content,
+                expected,
+            }),
+        }
+    }
+
+    /// Content already staged for `target`, so later writes can build on it.
+    pub fn staged_content(&self, target: &FileTarget) -> Option<&str> {
+        self.writes
+            .iter()
+            .find(|write| write.target == *target)
+            .map(|write| write.content.as_str())
+    }
+
+    /// Check every precondition, then write every file. If any write fails,
+    /// the files already written are restored before returning.
+    pub fn commit(self) -> Result<(), TransactionError> {
+        // Nothing is written until every file is known to be unchanged
+        let mut entries = Vec::new();
+        for write in &self.writes {
+            let original = write.target.read()?;
+            if let Some(expected) = &write.expected {
+                if fingerprint(original.as_deref()) != *expected {
+                    return Err(TransactionError::Changed(write.target.to_string()));
+                }
+            }
+            entries.push(JournalEntry {
+                target: write.target.clone(),
+                original,
+            });
+        }
+
+        // Backups are taken before any write, so a failed one leaves every
+        // file untouched
+        for entry in entries.iter().filter(|entry| entry.original.is_some()) {
+            entry.target.back_up(&self.backup_settings)?;
+        }
+
+        fs::create_dir_all(&self.journal_dir)?;
+        let mut journal = Journal {
+            id: uuid::Uuid::new_v4().to_string(),
+            committed: false,
+            entries,
+        };
+        let journal_path = self.journal_dir.join(format!("{}.json", journal.id));
+        // Taken before the journal exists, so recovery never sees it unlocked
+        let _lock = JournalLock::acquire(&journal_path)?;
+        journal.save(&journal_path)?;
+
+        for (index, write) in self.writes.iter().enumerate() {
+            if let Err(e) = write.target.write(&write.content) {
+                let touched = Journal {
+                    id: journal.id.clone(),
+                    committed: false,
+                    entries: journal.entries.drain(..=index).collect(),
+                };
+                let unrestored = touched.roll_back();
+                let file = write.target.to_string();
+                let cause = e.to_string();
+                if unrestored.is_empty() {
+                    fs::remove_file(&journal_path)?;
+                    return Err(TransactionError::RolledBack { file, cause });
+                }
+                // Keep the journal so the next start retries the restore
+                touched.save(&journal_path)?;
+                return Err(TransactionError::RollbackFailed {
+                    file,
+                    cause,
+                    unrestored,
+                });
+            }
+        }
+
+        // Every file has landed, so the commit stands whatever happens to
+        // the journal now. One left behind must not be rolled back later.
+        journal.committed = true;
+        if journal.save(&journal_path).is_err() || fs::remove_file(&journal_path).is_err() {
+            let _ = fs::remove_file(&journal_path);
+        }
+        Ok(())
+    }
+}
+
+/// Roll back commits interrupted by a crash. Journals of commits still
+/// running in another process are left alone. Returns the files restored.
+pub fn recover_transactions(app: &impl AppPaths) -> Result<Vec<String>, TransactionError> {
+    let dir = journal_dir(app)?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut restored = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(_lock) = JournalLock::try_acquire(&path)? else {
+            continue;
+        };
+        // The commit may have finished between listing and locking
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let journal: Journal = serde_json::from_str(&content)?;
+        if !journal.committed {
+            let unrestored = journal.roll_back();
+            if !unrestored.is_empty() {
+                continue;
+            }
+            restored.extend(journal.entries.iter().map(|entry| entry.target.to_string()));
+        }
+        fs::remove_file(&path)?;
+    }
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+
+    fn setup() -> (PathBuf, StandalonePaths) {
+        let dir = std::env::temp_dir().join(format!("transaction-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let paths = StandalonePaths {
+            data_dir: Some(dir.join("data")),
+            config_dir: None,
+        };
+        (dir, paths)
+    }
+
+    #[test]
+    fn test_commit_writes_all_and_keeps_backups() {
+        let (dir, paths) = setup();
+        let a = dir.join("a.json");
+        let b = dir.join("nested/b.json");
+        fs::write(&a, "old a").unwrap();
+
+        let mut tx = Transaction::new(&paths, None).unwrap();
+        tx.stage(
+            FileTarget::Local(a.clone()),
+            "new a".to_string(),
+            Some(fingerprint(Some("old a"))),
+        );
+        tx.stage(
+            FileTarget::Local(b.clone()),
+            "new b".to_string(),
+            Some(fingerprint(None)),
+        );
+        tx.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&a).unwrap(), "new a");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "new b");
+        assert_eq!(
+            fs::read_to_string(a.with_extension("bak")).unwrap(),
+            "old a"
+        );
+        assert_eq!(
+            fs::read_dir(dir.join("data/transactions")).unwrap().count(),
+            0
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_changed_file_aborts_before_writing() {
+        let (dir, paths) = setup();
+        let a = dir.join("a.json");
+        let b = dir.join("b.json");
+        fs::write(&a, "old a").unwrap();
+        fs::write(&b, "edited elsewhere").unwrap();
+
+        let mut tx = Transaction::new(&paths, None).unwrap();
+        tx.stage(FileTarget::Local(a.clone()), "new a".to_string(), None);
+        tx.stage(
+            FileTarget::Local(b.clone()),
+            "new b".to_string(),
+            Some(fingerprint(Some("old b"))),
+        );
+
+        assert!(matches!(tx.commit(), Err(TransactionError::Changed(_))));
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old a");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "edited elsewhere");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_failed_write_rolls_back_earlier_files() {
+        let (dir, paths) = setup();
+        let a = dir.join("a.json");
+        let created = dir.join("created.json");
+        fs::write(&a, "old a").unwrap();
+        // The parent is a file, so this write fails
+        fs::write(dir.join("plain"), "").unwrap();
+        let blocked = dir.join("plain/blocked.json");
+
+        let mut tx = Transaction::new(&paths, None).unwrap();
+        tx.stage(FileTarget::Local(a.clone()), "new a".to_string(), None);
+        tx.stage(FileTarget::Local(created.clone()), "new".to_string(), None);
+        tx.stage(FileTarget::Local(blocked.clone()), "x".to_string(), None);
+
+        assert!(matches!(
+            tx.commit(),
+            Err(TransactionError::RolledBack { .. })
+        ));
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old a");
+        assert!(!created.exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_backups_rotate_and_failures_abort_before_writing() {
+        let (dir, paths) = setup();
+        let a = dir.join("a.json");
+        fs::write(&a, "v1").unwrap();
+        let settings = BackupSettings {
+            enabled: true,
+            max_backups: 3,
+        };
+
+        for content in ["v2", "v3"] {
+            let mut tx = Transaction::new(&paths, Some(settings.clone())).unwrap();
+            tx.stage(FileTarget::Local(a.clone()), content.to_string(), None);
+            tx.commit().unwrap();
+        }
+        assert_eq!(fs::read_to_string(a.with_extension("bak")).unwrap(), "v2");
+        assert_eq!(fs::read_to_string(dir.join("a.json.bak.1")).unwrap(), "v1");
+
+        // A backup that cannot be written stops the commit before any file
+        let b = dir.join("b.json");
+        fs::write(&b, "old b").unwrap();
+        fs::create_dir(b.with_extension("bak")).unwrap();
+        let mut tx = Transaction::new(&paths, None).unwrap();
+        tx.stage(FileTarget::Local(a.clone()), "v4".to_string(), None);
+        tx.stage(FileTarget::Local(b.clone()), "new b".to_string(), None);
+
+        assert!(matches!(tx.commit(), Err(TransactionError::Io(_))));
+        assert_eq!(fs::read_to_string(&a).unwrap(), "v3");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "old b");
+        assert_eq!(
+            fs::read_dir(dir.join("data/transactions")).unwrap().count(),
+            0
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_recover_rolls_back_interrupted_commit() {
+        let (dir, paths) = setup();
+        let a = dir.join("a.json");
+        fs::write(&a, "half written").unwrap();
+
+        let journal_dir = dir.join("data/transactions");
+        fs::create_dir_all(&journal_dir).unwrap();
+        let journal = Journal {
+            id: "crashed".to_string(),
+            committed: false,
+            entries: vec![JournalEntry {
+                target: FileTarget::Local(a.clone()),
+                original: Some("before".to_string()),
+            }],
+        };
+        journal.save(&journal_dir.join("crashed.json")).unwrap();
+
+        let restored = recover_transactions(&paths).unwrap();
+
+        assert_eq!(restored, vec![a.to_string_lossy().to_string()]);
+        assert_eq!(fs::read_to_string(&a).unwrap(), "before");
+        assert!(!journal_dir.join("crashed.json").exists());
+        assert!(!journal_dir.join("crashed.lock").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_recovery_skips_journals_of_running_commits() {
+        let (dir, paths) = setup();
+        let a = dir.join("a.json");
+        fs::write(&a, "being written").unwrap();
+
+        let journal_dir = dir.join("data/transactions");
+        fs::create_dir_all(&journal_dir).unwrap();
+        let journal_path = journal_dir.join("running.json");
+        let journal = Journal {
+            id: "running".to_string(),
+            committed: false,
+            entries: vec![JournalEntry {
+                target: FileTarget::Local(a.clone()),
+                original: Some("token=secret".to_string()),
+            }],
+        };
+        let lock = JournalLock::acquire(&journal_path).unwrap();
+        journal.save(&journal_path).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&journal_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Another process is still committing: nothing is touched
+        assert!(recover_transactions(&paths).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "being written");
+        assert!(journal_path.exists());
+
+        // Once its holder is gone the journal counts as abandoned
+        drop(lock);
+        assert_eq!(
+            recover_transactions(&paths).unwrap(),
+            vec![a.to_string_lossy().to_string()]
+        );
+        assert_eq!(fs::read_to_string(&a).unwrap(), "token=secret");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
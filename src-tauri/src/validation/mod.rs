@@ -0,0 +1,324 @@
+use crate::config::{tool_registry, CliTool, ConfigFormat, SuggestedConfig};
+use crate::formats::{jsonc, ConfigDocument};
+use jsonschema::error::ValidationErrorKind;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+const CLAUDE_CODE_SETTINGS: &str = include_str!("schemas/claude-code-settings.json");
+const GEMINI_SETTINGS: &str = include_str!("schemas/gemini-settings.json");
+const MCP_SERVERS: &str = include_str!("schemas/mcp-servers.json");
+const MCP_FILE: &str = include_str!("schemas/mcp-file.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiagnostic {
+    /// JSON Pointer to the offending value ("" for the document root)
+    pub pointer: String,
+    pub message: String,
+    pub severity: DiagnosticSeverity,
+}
+
+/// Identifies the suggested config a piece of content belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaTarget {
+    pub tool_id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    /// False when no bundled schema exists for the target; only parse errors are reported then
+    pub has_schema: bool,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == DiagnosticSeverity::Error)
+    }
+}
+
+/// Bundled schema source for a suggested config, keyed by tool id and label.
+fn bundled_schema(tool_id: &str, label: &str) -> Option<&'static str> {
+    match (tool_id, label) {
+        ("claude-code", "Settings") => Some(CLAUDE_CODE_SETTINGS),
+        ("claude-code", "MCP Servers (User)") => Some(MCP_SERVERS),
+        ("claude-code", "MCP Servers (Project)") => Some(MCP_FILE),
+        ("gemini-cli", "Settings") => Some(GEMINI_SETTINGS),
+        ("qwen-code", "Settings (User)") | ("qwen-code", "Settings (Project)") => {
+            Some(GEMINI_SETTINGS)
+        }
+        ("qwen-code", "MCP Servers") => Some(MCP_SERVERS),
+        ("cursor", "MCP Servers") => Some(MCP_SERVERS),
+        ("qoder-cli", "MCP Servers") => Some(MCP_SERVERS),
+        ("kilo-code-cli", "MCP Settings") => Some(MCP_SERVERS),
+        ("kiro-cli", "MCP Servers") => Some(MCP_FILE),
+        _ => None,
+    }
+}
+
+fn find_suggested_config<'a>(
+    tools: &'a [CliTool],
+    tool_id: &str,
+    label: &str,
+) -> Option<&'a SuggestedConfig> {
+    tools
+        .iter()
+        .find(|tool| tool.id == tool_id)?
+        .suggested_configs
+        .as_ref()?
+        .iter()
+        .find(|config| config.label == label)
+}
+
+fn error_at_root(message: String) -> ConfigDiagnostic {
+    ConfigDiagnostic {
+        pointer: String::new(),
+        message,
+        severity: DiagnosticSeverity::Error,
+    }
+}
+
+/// Parse editor content the way the editor holds it: configs scoped to a
+/// `json_path` are always edited as JSON, everything else in its own format.
+fn parse_content(content: &str, config: Option<&SuggestedConfig>) -> Result<Value, String> {
+    match config {
+        Some(config) if config.json_path.is_none() && config.format != ConfigFormat::Json => {
+            ConfigDocument::parse(content, &config.format)
+                .map(|doc| doc.to_value())
+                .map_err(|e| e.to_string())
+        }
+        _ => jsonc::parse(content).map_err(|e| e.to_string()),
+    }
+}
+
+pub fn validate_value(schema_source: &str, value: &Value) -> Vec<ConfigDiagnostic> {
+    let schema: Value = match serde_json::from_str(schema_source) {
+        Ok(schema) => schema,
+        Err(e) => return vec![error_at_root(format!("Bundled schema is invalid: {}", e))],
+    };
+    let validator = match jsonschema::validator_for(&schema) {
+        Ok(validator) => validator,
+        Err(e) => return vec![error_at_root(format!("Bundled schema is invalid: {}", e))],
+    };
+
+    validator
+        .iter_errors(value)
+        .map(|error| {
+            // Unknown keys are usually settings newer than the bundled schema, so
+            // they are surfaced without blocking writes
+            let severity = match error.kind {
+                ValidationErrorKind::AdditionalProperties { .. } => DiagnosticSeverity::Warning,
+                _ => DiagnosticSeverity::Error,
+            };
+            ConfigDiagnostic {
+                pointer: error.instance_path.to_string(),
+                message: error.to_string(),
+                severity,
+            }
+        })
+        .collect()
+}
+
+/// Validate editor content for a suggested config. User-defined tools from
+/// the registry are parsed in their declared format but have no bundled schema.
+pub fn validate_content(
+    tools: &[CliTool],
+    target: &SchemaTarget,
+    content: &str,
+) -> ValidationReport {
+    let config = find_suggested_config(tools, &target.tool_id, &target.label);
+    let schema = bundled_schema(&target.tool_id, &target.label);
+
+    let diagnostics = match parse_content(content, config) {
+        Ok(value) => schema
+            .map(|schema| validate_value(schema, &value))
+            .unwrap_or_default(),
+        Err(message) => vec![error_at_root(message)],
+    };
+
+    ValidationReport {
+        has_schema: schema.is_some(),
+        diagnostics,
+    }
+}
+
+/// Validate a whole parsed file for a suggested config. When the config is
+/// scoped to a `json_path`, only that subtree is checked against the schema.
+pub fn validate_document(
+    tools: &[CliTool],
+    target: &SchemaTarget,
+    document: &ConfigDocument,
+) -> ValidationReport {
+    let config = find_suggested_config(tools, &target.tool_id, &target.label);
+    let schema = bundled_schema(&target.tool_id, &target.label);
+
+    let value = match config.and_then(|c| c.json_path.as_deref()) {
+        Some(json_path) => document.get(json_path).unwrap_or(Value::Null),
+        None => document.to_value(),
+    };
+
+    ValidationReport {
+        has_schema: schema.is_some(),
+        diagnostics: schema
+            .map(|schema| validate_value(schema, &value))
+            .unwrap_or_default(),
+    }
+}
+
+#[tauri::command]
+pub fn validate_config(
+    app: AppHandle,
+    tool_id: String,
+    label: String,
+    content: String,
+) -> ValidationReport {
+    validate_content(
+        &tool_registry(&app).tools,
+        &SchemaTarget { tool_id, label },
+        &content,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::get_cli_tools;
+
+    fn validate(tool_id: &str, label: &str, content: &str) -> ValidationReport {
+        validate_content(&get_cli_tools(), &target(tool_id, label), content)
+    }
+
+    fn target(tool_id: &str, label: &str) -> SchemaTarget {
+        SchemaTarget {
+            tool_id: tool_id.to_string(),
+            label: label.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bundled_schemas_compile() {
+        for schema in [CLAUDE_CODE_SETTINGS, GEMINI_SETTINGS, MCP_SERVERS, MCP_FILE] {
+            let schema: Value = serde_json::from_str(schema).unwrap();
+            assert!(jsonschema::validator_for(&schema).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_every_schema_matches_a_suggested_config() {
+        let pairs = [
+            ("claude-code", "Settings"),
+            ("claude-code", "MCP Servers (User)"),
+            ("claude-code", "MCP Servers (Project)"),
+            ("gemini-cli", "Settings"),
+            ("qwen-code", "Settings (User)"),
+            ("qwen-code", "MCP Servers"),
+            ("cursor", "MCP Servers"),
+            ("qoder-cli", "MCP Servers"),
+            ("kilo-code-cli", "MCP Settings"),
+            ("kiro-cli", "MCP Servers"),
+        ];
+        let tools = get_cli_tools();
+        for (tool_id, label) in pairs {
+            assert!(bundled_schema(tool_id, label).is_some());
+            assert!(
+                find_suggested_config(&tools, tool_id, label).is_some(),
+                "{} / {}",
+                tool_id,
+                label
+            );
+        }
+    }
+
+    #[test]
+    fn test_valid_claude_settings() {
+        let content = r#"{
+            // comments are fine
+            "model": "opus",
+            "permissions": {
+                "allow": ["Bash(npm run test:*)", "Read(~/.zshrc)", "mcp__github__get_issue"],
+                "deny": ["WebFetch"],
+                "defaultMode": "acceptEdits"
+            },
+            "env": { "FOO": "bar" }
+        }"#;
+        let report = validate("claude-code", "Settings", content);
+        assert!(report.has_schema);
+        assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    }
+
+    #[test]
+    fn test_bad_claude_permissions() {
+        let content = r#"{
+            "permissions": {
+                "allow": "Bash(ls)",
+                "deny": ["Bash(rm", 3],
+                "defaultMode": "yolo",
+                "alow": []
+            }
+        }"#;
+        let report = validate("claude-code", "Settings", content);
+        assert!(report.has_errors());
+
+        let errors: Vec<&str> = report
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+            .map(|d| d.pointer.as_str())
+            .collect();
+        assert!(errors.contains(&"/permissions/allow"));
+        assert!(errors.contains(&"/permissions/deny/0"));
+        assert!(errors.contains(&"/permissions/deny/1"));
+        assert!(errors.contains(&"/permissions/defaultMode"));
+
+        let warning = report
+            .diagnostics
+            .iter()
+            .find(|d| d.severity == DiagnosticSeverity::Warning)
+            .unwrap();
+        assert_eq!(warning.pointer, "/permissions");
+        assert!(warning.message.contains("alow"));
+    }
+
+    #[test]
+    fn test_mcp_servers_subtree() {
+        let content = r#"{
+            "ok": { "command": "npx", "args": ["-y", "server"] },
+            "remote": { "type": "http", "url": "https://example.com/mcp" },
+            "broken": { "args": ["x"] }
+        }"#;
+        let report = validate("claude-code", "MCP Servers (User)", content);
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(report.diagnostics[0].pointer, "/broken");
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_parse_error_is_reported() {
+        let report = validate("claude-code", "Settings", "{ \"model\": ");
+        assert!(report.has_errors());
+        assert_eq!(report.diagnostics[0].pointer, "");
+    }
+
+    #[test]
+    fn test_no_schema_only_checks_syntax() {
+        let report = validate("aider", "Settings", "model: gpt-4o\n");
+        assert!(!report.has_schema);
+        assert!(report.diagnostics.is_empty());
+
+        let report = validate("aider", "Settings", "model: [unclosed\n");
+        assert!(report.has_errors());
+    }
+}
@@ -1,10 +1,21 @@
+use crate::app_paths::AppPaths;
+use crate::transaction::FileTarget;
+use ring::digest::{digest, SHA256};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
 use thiserror::Error;
 use uuid::Uuid;
 
+mod policy;
+
+pub use policy::{
+    get_version_policy, preview_version_pruning, prune_versions, record_write, set_version_policy,
+};
+
 #[derive(Error, Debug)]
 pub enum VersionError {
     #[error("Version not found: {0}")]
@@ -15,6 +26,8 @@ pub enum VersionError {
     Json(#[from] serde_json::Error),
     #[error("Failed to get app data directory")]
     AppDataDir,
+    #[error("Corrupt version content: {0}")]
+    Corrupt(String),
 }
 
 impl Serialize for VersionError {
@@ -67,83 +80,335 @@ impl From<&ConfigVersion> for VersionMetadata {
     }
 }
 
-fn get_versions_dir(app: &AppHandle) -> Result<PathBuf, VersionError> {
-    let app_data = app
-        .path()
-        .app_data_dir()
-        .map_err(|_| VersionError::AppDataDir)?;
+/// A version as recorded in the index: its metadata plus the blob that
+/// holds its content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredVersion {
+    #[serde(flatten)]
+    metadata: VersionMetadata,
+    /// SHA-256 of the content, naming its blob
+    blob: String,
+    /// Uncompressed content length in bytes
+    size: u64,
+}
+
+/// `versions/index.json`. Listing versions only reads this file; content
+/// lives in zstd-compressed blobs under `versions/blobs/`, one per distinct
+/// content, shared by every version that has it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionIndex {
+    versions: Vec<StoredVersion>,
+    /// Config id each file was last listed or saved as, keyed by `path_key`.
+    /// Writes that only know the file, like MCP sync, are filed under it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    config_paths: BTreeMap<String, String>,
+}
+
+impl VersionIndex {
+    fn find(&self, config_id: &str, version_id: &str) -> Result<&StoredVersion, VersionError> {
+        self.versions
+            .iter()
+            .find(|v| v.metadata.config_id == config_id && v.metadata.id == version_id)
+            .ok_or_else(|| VersionError::NotFound(version_id.to_string()))
+    }
+
+    fn find_mut(
+        &mut self,
+        config_id: &str,
+        version_id: &str,
+    ) -> Result<&mut StoredVersion, VersionError> {
+        self.versions
+            .iter_mut()
+            .find(|v| v.metadata.config_id == config_id && v.metadata.id == version_id)
+            .ok_or_else(|| VersionError::NotFound(version_id.to_string()))
+    }
+}
+
+/// Serializes read-modify-write cycles on the index across commands.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+const ZSTD_LEVEL: i32 = 9;
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn get_versions_dir(app: &impl AppPaths) -> Result<PathBuf, VersionError> {
+    let app_data = app.app_data_dir().ok_or(VersionError::AppDataDir)?;
     Ok(app_data.join("versions"))
 }
 
-fn get_config_versions_dir(app: &AppHandle, config_id: &str) -> Result<PathBuf, VersionError> {
+fn content_hash(content: &str) -> String {
+    digest(&SHA256, content.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn blob_path(versions_dir: &Path, hash: &str) -> PathBuf {
+    versions_dir
+        .join("blobs")
+        .join(&hash[..2])
+        .join(format!("{}.zst", &hash[2..]))
+}
+
+/// Store `content` unless an identical blob already exists. Returns its hash.
+fn write_blob(versions_dir: &Path, content: &str) -> Result<String, VersionError> {
+    let hash = content_hash(content);
+    let path = blob_path(versions_dir, &hash);
+    if path.exists() {
+        return Ok(hash);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let compressed = zstd::encode_all(content.as_bytes(), ZSTD_LEVEL)?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, compressed)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(hash)
+}
+
+fn read_blob(versions_dir: &Path, hash: &str) -> Result<String, VersionError> {
+    let path = blob_path(versions_dir, hash);
+    if !path.exists() {
+        return Err(VersionError::Corrupt(format!("missing blob {}", hash)));
+    }
+
+    let bytes = zstd::decode_all(fs::read(&path)?.as_slice())?;
+    let content = String::from_utf8(bytes)
+        .map_err(|_| VersionError::Corrupt(format!("blob {} is not UTF-8", hash)))?;
+    if content_hash(&content) != hash {
+        return Err(VersionError::Corrupt(format!(
+            "blob {} does not match its hash",
+            hash
+        )));
+    }
+    Ok(content)
+}
+
+/// Delete blobs no version refers to any more.
+fn remove_unreferenced_blobs(
+    versions_dir: &Path,
+    index: &VersionIndex,
+) -> Result<(), VersionError> {
+    let blobs_dir = versions_dir.join("blobs");
+    if !blobs_dir.exists() {
+        return Ok(());
+    }
+
+    let referenced: HashSet<PathBuf> = index
+        .versions
+        .iter()
+        .map(|v| blob_path(versions_dir, &v.blob))
+        .collect();
+    for shard in fs::read_dir(&blobs_dir)? {
+        let shard = shard?.path();
+        if !shard.is_dir() {
+            continue;
+        }
+        for blob in fs::read_dir(&shard)? {
+            let blob = blob?.path();
+            if !referenced.contains(&blob) {
+                fs::remove_file(&blob)?;
+            }
+        }
+        // Only succeeds once the shard is empty
+        let _ = fs::remove_dir(&shard);
+    }
+    Ok(())
+}
+
+fn save_index(versions_dir: &Path, index: &VersionIndex) -> Result<(), VersionError> {
+    fs::create_dir_all(versions_dir)?;
+    let path = versions_dir.join("index.json");
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_string(index)?)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(())
+}
+
+/// Move versions from the old layout, one pretty-printed `ConfigVersion`
+/// per file under `versions/<config_id>/`, into blobs and the index. Legacy
+/// files are deleted only after the index holding them has been saved, so
+/// an interrupted migration simply resumes on the next load.
+fn migrate_legacy_versions(
+    versions_dir: &Path,
+    index: &mut VersionIndex,
+) -> Result<(), VersionError> {
+    let mut legacy_files = Vec::new();
+    for entry in fs::read_dir(versions_dir)? {
+        let config_dir = entry?.path();
+        if !config_dir.is_dir() {
+            continue;
+        }
+        for file in fs::read_dir(&config_dir)? {
+            let path = file?.path();
+            if path.extension().map(|e| e == "json").unwrap_or(false) {
+                legacy_files.push(path);
+            }
+        }
+    }
+
+    if legacy_files.is_empty() {
+        return Ok(());
+    }
+
+    for path in &legacy_files {
+        let version: ConfigVersion = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if index.find(&version.config_id, &version.id).is_ok() {
+            continue;
+        }
+        let blob = write_blob(versions_dir, &version.content)?;
+        index.versions.push(StoredVersion {
+            metadata: VersionMetadata::from(&version),
+            blob,
+            size: version.content.len() as u64,
+        });
+    }
+    save_index(versions_dir, index)?;
+
+    for path in &legacy_files {
+        fs::remove_file(path)?;
+        if let Some(config_dir) = path.parent() {
+            let _ = fs::remove_dir(config_dir);
+        }
+    }
+    Ok(())
+}
+
+fn load_index(versions_dir: &Path) -> Result<VersionIndex, VersionError> {
+    let path = versions_dir.join("index.json");
+    let mut index = if path.exists() {
+        serde_json::from_str(&fs::read_to_string(&path)?)?
+    } else {
+        VersionIndex::default()
+    };
+
+    if versions_dir.exists() {
+        migrate_legacy_versions(versions_dir, &mut index)?;
+    }
+    Ok(index)
+}
+
+/// Run `f` on the index under the lock and save it afterwards if `f`
+/// succeeded. Blobs orphaned by the change are removed.
+fn update_index<T>(
+    app: &impl AppPaths,
+    f: impl FnOnce(&Path, &mut VersionIndex) -> Result<T, VersionError>,
+) -> Result<T, VersionError> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     let versions_dir = get_versions_dir(app)?;
-    Ok(versions_dir.join(sanitize_filename(config_id)))
+    let mut index = load_index(&versions_dir)?;
+    let result = f(&versions_dir, &mut index)?;
+    save_index(&versions_dir, &index)?;
+    remove_unreferenced_blobs(&versions_dir, &index)?;
+    Ok(result)
 }
 
-fn sanitize_filename(name: &str) -> String {
-    name.chars()
-        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-        .collect()
+fn read_index(app: &impl AppPaths) -> Result<(PathBuf, VersionIndex), VersionError> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let versions_dir = get_versions_dir(app)?;
+    let index = load_index(&versions_dir)?;
+    Ok((versions_dir, index))
 }
 
-fn get_version_path(app: &AppHandle, config_id: &str, version_id: &str) -> Result<PathBuf, VersionError> {
-    let config_dir = get_config_versions_dir(app, config_id)?;
-    Ok(config_dir.join(format!("{}.json", version_id)))
+fn load_stored(versions_dir: &Path, stored: &StoredVersion) -> Result<ConfigVersion, VersionError> {
+    let metadata = &stored.metadata;
+    Ok(ConfigVersion {
+        id: metadata.id.clone(),
+        config_id: metadata.config_id.clone(),
+        name: metadata.name.clone(),
+        content: read_blob(versions_dir, &stored.blob)?,
+        description: metadata.description.clone(),
+        timestamp: metadata.timestamp,
+        source: metadata.source.clone(),
+        is_default: metadata.is_default,
+    })
 }
 
-#[tauri::command]
-pub fn save_version(
-    app: AppHandle,
+fn add_version(
+    versions_dir: &Path,
+    index: &mut VersionIndex,
+    version: &ConfigVersion,
+) -> Result<(), VersionError> {
+    let blob = write_blob(versions_dir, &version.content)?;
+    index.versions.push(StoredVersion {
+        metadata: VersionMetadata::from(version),
+        blob,
+        size: version.content.len() as u64,
+    });
+    Ok(())
+}
+
+pub fn save_version_internal(
+    app: &impl AppPaths,
     config_id: String,
     name: String,
     content: String,
     description: Option<String>,
     source: String,
 ) -> Result<ConfigVersion, VersionError> {
-    let config_dir = get_config_versions_dir(&app, &config_id)?;
-    fs::create_dir_all(&config_dir)?;
-
     let version = ConfigVersion {
         id: Uuid::new_v4().to_string(),
         config_id,
         name,
         content,
         description,
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+        timestamp: now(),
         source,
         is_default: false,
     };
 
-    let version_path = config_dir.join(format!("{}.json", version.id));
-    let json = serde_json::to_string_pretty(&version)?;
-    fs::write(&version_path, json)?;
-
+    update_index(app, |versions_dir, index| {
+        add_version(versions_dir, index, &version)
+    })?;
     Ok(version)
 }
 
 #[tauri::command]
-pub fn list_versions(app: AppHandle, config_id: String) -> Result<Vec<VersionMetadata>, VersionError> {
-    let config_dir = get_config_versions_dir(&app, &config_id)?;
-
-    if !config_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut versions = Vec::new();
+pub fn save_version(
+    app: AppHandle,
+    config_id: String,
+    name: String,
+    content: String,
+    description: Option<String>,
+    source: String,
+) -> Result<ConfigVersion, VersionError> {
+    save_version_internal(&app, config_id, name, content, description, source)
+}
 
-    for entry in fs::read_dir(&config_dir)? {
-        let entry = entry?;
-        let path = entry.path();
+/// Config ids that have at least one saved version.
+pub fn list_config_ids_internal(app: &impl AppPaths) -> Result<Vec<String>, VersionError> {
+    let (_, index) = read_index(app)?;
+    let mut config_ids: Vec<String> = index
+        .versions
+        .into_iter()
+        .map(|v| v.metadata.config_id)
+        .collect();
+    config_ids.sort();
+    config_ids.dedup();
+    Ok(config_ids)
+}
 
-        if path.extension().map(|e| e == "json").unwrap_or(false) {
-            let content = fs::read_to_string(&path)?;
-            let version: ConfigVersion = serde_json::from_str(&content)?;
-            versions.push(VersionMetadata::from(&version));
-        }
-    }
+pub fn list_versions_internal(
+    app: &impl AppPaths,
+    config_id: String,
+) -> Result<Vec<VersionMetadata>, VersionError> {
+    let (_, index) = read_index(app)?;
+    let mut versions: Vec<VersionMetadata> = index
+        .versions
+        .into_iter()
+        .map(|v| v.metadata)
+        .filter(|m| m.config_id == config_id)
+        .collect();
 
     // Sort by timestamp descending (newest first)
     versions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -151,31 +416,100 @@ pub fn list_versions(app: AppHandle, config_id: String) -> Result<Vec<VersionMet
     Ok(versions)
 }
 
+/// `path` is the file the config edits. Passing it links the two, so
+/// snapshots of writes made without the config id end up in its list.
 #[tauri::command]
-pub fn load_version(app: AppHandle, config_id: String, version_id: String) -> Result<ConfigVersion, VersionError> {
-    let version_path = get_version_path(&app, &config_id, &version_id)?;
+pub fn list_versions(
+    app: AppHandle,
+    config_id: String,
+    path: Option<String>,
+) -> Result<Vec<VersionMetadata>, VersionError> {
+    if let Some(path) = path {
+        link_config_path_internal(&app, &path, &config_id)?;
+    }
+    list_versions_internal(&app, config_id)
+}
 
-    if !version_path.exists() {
-        return Err(VersionError::NotFound(version_id));
+/// The same file always gets the same key, however its path was written.
+fn path_key(path: &str) -> String {
+    FileTarget::parse(path)
+        .map(|target| target.to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// File later path-only writes to `path` under `config_id`, and move the
+/// versions already filed under the path itself over to it.
+pub fn link_config_path_internal(
+    app: &impl AppPaths,
+    path: &str,
+    config_id: &str,
+) -> Result<(), VersionError> {
+    let key = path_key(path);
+    {
+        let (_, index) = read_index(app)?;
+        let adopted = index
+            .versions
+            .iter()
+            .any(|v| path_key(&v.metadata.config_id) == key);
+        if !adopted && index.config_paths.get(&key).map(String::as_str) == Some(config_id) {
+            return Ok(());
+        }
     }
 
-    let content = fs::read_to_string(&version_path)?;
-    let version: ConfigVersion = serde_json::from_str(&content)?;
+    update_index(app, |_, index| {
+        for version in index.versions.iter_mut() {
+            if path_key(&version.metadata.config_id) == key {
+                version.metadata.config_id = config_id.to_string();
+            }
+        }
+        index.config_paths.insert(key, config_id.to_string());
+        Ok(())
+    })
+}
 
-    Ok(version)
+/// Config id that writes to `path` are filed under: the linked config, or
+/// the path itself while none is.
+pub fn config_id_for_path(app: &impl AppPaths, path: &str) -> Result<String, VersionError> {
+    let key = path_key(path);
+    let (_, mut index) = read_index(app)?;
+    Ok(index.config_paths.remove(&key).unwrap_or(key))
 }
 
-#[tauri::command]
-pub fn delete_version(app: AppHandle, config_id: String, version_id: String) -> Result<(), VersionError> {
-    let version_path = get_version_path(&app, &config_id, &version_id)?;
+pub fn load_version_internal(
+    app: &impl AppPaths,
+    config_id: String,
+    version_id: String,
+) -> Result<ConfigVersion, VersionError> {
+    let (versions_dir, index) = read_index(app)?;
+    load_stored(&versions_dir, index.find(&config_id, &version_id)?)
+}
 
-    if !version_path.exists() {
-        return Err(VersionError::NotFound(version_id));
-    }
+#[tauri::command]
+pub fn load_version(
+    app: AppHandle,
+    config_id: String,
+    version_id: String,
+) -> Result<ConfigVersion, VersionError> {
+    load_version_internal(&app, config_id, version_id)
+}
 
-    fs::remove_file(&version_path)?;
+pub fn delete_version_internal(
+    app: &impl AppPaths,
+    config_id: &str,
+    version_id: &str,
+) -> Result<(), VersionError> {
+    update_index(app, |_, index| {
+        index.find(config_id, version_id)?;
+        index
+            .versions
+            .retain(|v| !(v.metadata.config_id == config_id && v.metadata.id == version_id));
+        Ok(())
+    })
+}
 
-    Ok(())
+#[tauri::command]
+pub fn delete_version(app: AppHandle, config_id: String, version_id: String) -> Result<(), VersionError> {
+    delete_version_internal(&app, &config_id, &version_id)
 }
 
 #[tauri::command]
@@ -187,67 +521,38 @@ pub fn update_version_metadata(
     description: Option<String>,
     is_default: Option<bool>,
 ) -> Result<VersionMetadata, VersionError> {
-    let version_path = get_version_path(&app, &config_id, &version_id)?;
+    update_index(&app, |_, index| {
+        index.find(&config_id, &version_id)?;
 
-    if !version_path.exists() {
-        return Err(VersionError::NotFound(version_id));
-    }
-
-    let content = fs::read_to_string(&version_path)?;
-    let mut version: ConfigVersion = serde_json::from_str(&content)?;
-
-    // Update fields if provided
-    if let Some(new_name) = name {
-        version.name = new_name;
-    }
-    if let Some(new_desc) = description {
-        version.description = Some(new_desc);
-    }
-    if let Some(new_default) = is_default {
-        if new_default {
+        if is_default == Some(true) {
             // Clear default from other versions first
-            clear_default_versions(&app, &config_id, &version_id)?;
+            for other in index
+                .versions
+                .iter_mut()
+                .filter(|v| v.metadata.config_id == config_id)
+            {
+                other.metadata.is_default = false;
+            }
         }
-        version.is_default = new_default;
-    }
-
-    // Update timestamp
-    version.timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    // Save updated version
-    let json = serde_json::to_string_pretty(&version)?;
-    fs::write(&version_path, json)?;
 
-    Ok(VersionMetadata::from(&version))
-}
-
-fn clear_default_versions(app: &AppHandle, config_id: &str, except_version_id: &str) -> Result<(), VersionError> {
-    let config_dir = get_config_versions_dir(app, config_id)?;
-
-    if !config_dir.exists() {
-        return Ok(());
-    }
-
-    for entry in fs::read_dir(&config_dir)? {
-        let entry = entry?;
-        let path = entry.path();
+        let version = &mut index.find_mut(&config_id, &version_id)?.metadata;
 
-        if path.extension().map(|e| e == "json").unwrap_or(false) {
-            let content = fs::read_to_string(&path)?;
-            let mut version: ConfigVersion = serde_json::from_str(&content)?;
-
-            if version.id != except_version_id && version.is_default {
-                version.is_default = false;
-                let json = serde_json::to_string_pretty(&version)?;
-                fs::write(&path, json)?;
-            }
+        // Update fields if provided
+        if let Some(new_name) = name {
+            version.name = new_name;
+        }
+        if let Some(new_desc) = description {
+            version.description = Some(new_desc);
+        }
+        if let Some(new_default) = is_default {
+            version.is_default = new_default;
         }
-    }
 
-    Ok(())
+        // Update timestamp
+        version.timestamp = now();
+
+        Ok(version.clone())
+    })
 }
 
 #[tauri::command]
@@ -257,36 +562,40 @@ pub fn duplicate_version(
     version_id: String,
     new_name: String,
 ) -> Result<ConfigVersion, VersionError> {
-    let version_path = get_version_path(&app, &config_id, &version_id)?;
-
-    if !version_path.exists() {
-        return Err(VersionError::NotFound(version_id));
-    }
-
-    let content = fs::read_to_string(&version_path)?;
-    let original: ConfigVersion = serde_json::from_str(&content)?;
-
-    // Create new version with duplicated content
-    let new_version = ConfigVersion {
-        id: Uuid::new_v4().to_string(),
-        config_id: config_id.clone(),
-        name: new_name,
-        content: original.content,
-        description: original.description.map(|d| format!("{} (copy)", d)),
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
-        source: "manual".to_string(),
-        is_default: false,
-    };
-
-    let config_dir = get_config_versions_dir(&app, &config_id)?;
-    let new_version_path = config_dir.join(format!("{}.json", new_version.id));
-    let json = serde_json::to_string_pretty(&new_version)?;
-    fs::write(&new_version_path, json)?;
+    update_index(&app, |versions_dir, index| {
+        let original = load_stored(versions_dir, index.find(&config_id, &version_id)?)?;
+
+        // Create new version with duplicated content
+        let new_version = ConfigVersion {
+            id: Uuid::new_v4().to_string(),
+            config_id: config_id.clone(),
+            name: new_name,
+            content: original.content,
+            description: original.description.map(|d| format!("{} (copy)", d)),
+            timestamp: now(),
+            source: "manual".to_string(),
+            is_default: false,
+        };
+
+        add_version(versions_dir, index, &new_version)?;
+        Ok(new_version)
+    })
+}
 
-    Ok(new_version)
+pub fn update_version_content_internal(
+    app: &impl AppPaths,
+    config_id: &str,
+    version_id: &str,
+    content: String,
+) -> Result<VersionMetadata, VersionError> {
+    update_index(app, |versions_dir, index| {
+        let blob = write_blob(versions_dir, &content)?;
+        let version = index.find_mut(config_id, version_id)?;
+        version.blob = blob;
+        version.size = content.len() as u64;
+        version.metadata.timestamp = now();
+        Ok(version.metadata.clone())
+    })
 }
 
 #[tauri::command]
@@ -296,48 +605,175 @@ pub fn update_version_content(
     version_id: String,
     content: String,
 ) -> Result<VersionMetadata, VersionError> {
-    let version_path = get_version_path(&app, &config_id, &version_id)?;
-
-    if !version_path.exists() {
-        return Err(VersionError::NotFound(version_id));
-    }
-
-    let file_content = fs::read_to_string(&version_path)?;
-    let mut version: ConfigVersion = serde_json::from_str(&file_content)?;
+    update_version_content_internal(&app, &config_id, &version_id, content)
+}
 
-    version.content = content;
-    version.timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+/// Every version with its content, for profile export.
+pub fn export_versions_internal(app: &impl AppPaths) -> Result<Vec<ConfigVersion>, VersionError> {
+    let (versions_dir, index) = read_index(app)?;
+    index
+        .versions
+        .iter()
+        .map(|stored| load_stored(&versions_dir, stored))
+        .collect()
+}
 
-    let json = serde_json::to_string_pretty(&version)?;
-    fs::write(&version_path, json)?;
+/// Add versions from a profile, keeping their ids and timestamps. Versions
+/// already present are skipped. Returns how many were added.
+pub fn import_versions_internal(
+    app: &impl AppPaths,
+    versions: &[ConfigVersion],
+) -> Result<usize, VersionError> {
+    update_index(app, |versions_dir, index| {
+        let mut added = 0;
+        for version in versions {
+            if index.find(&version.config_id, &version.id).is_err() {
+                add_version(versions_dir, index, version)?;
+                added += 1;
+            }
+        }
+        Ok(added)
+    })
+}
 
-    Ok(VersionMetadata::from(&version))
+/// Versions in `versions` not yet present, without changing anything.
+pub fn count_new_versions_internal(
+    app: &impl AppPaths,
+    versions: &[ConfigVersion],
+) -> Result<usize, VersionError> {
+    let (_, index) = read_index(app)?;
+    Ok(versions
+        .iter()
+        .filter(|v| index.find(&v.config_id, &v.id).is_err())
+        .count())
 }
 
 #[tauri::command]
 pub fn get_default_version(app: AppHandle, config_id: String) -> Result<Option<VersionMetadata>, VersionError> {
-    let config_dir = get_config_versions_dir(&app, &config_id)?;
+    let (_, index) = read_index(&app)?;
+    Ok(index
+        .versions
+        .into_iter()
+        .map(|v| v.metadata)
+        .find(|m| m.config_id == config_id && m.is_default))
+}
 
-    if !config_dir.exists() {
-        return Ok(None);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+
+    fn setup() -> (PathBuf, StandalonePaths) {
+        let dir = std::env::temp_dir().join(format!("versions-test-{}", Uuid::new_v4()));
+        let paths = StandalonePaths {
+            data_dir: Some(dir.clone()),
+            config_dir: None,
+        };
+        (dir, paths)
     }
 
-    for entry in fs::read_dir(&config_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().map(|e| e == "json").unwrap_or(false) {
-            let content = fs::read_to_string(&path)?;
-            let version: ConfigVersion = serde_json::from_str(&content)?;
-
-            if version.is_default {
-                return Ok(Some(VersionMetadata::from(&version)));
-            }
+    fn blob_count(dir: &Path) -> usize {
+        let blobs = dir.join("versions/blobs");
+        if !blobs.exists() {
+            return 0;
         }
+        fs::read_dir(blobs)
+            .unwrap()
+            .map(|shard| fs::read_dir(shard.unwrap().path()).unwrap().count())
+            .sum()
     }
 
-    Ok(None)
+    #[test]
+    fn test_identical_content_shares_a_blob() {
+        let (dir, paths) = setup();
+        let save = |config: &str, content: &str| {
+            save_version_internal(
+                &paths,
+                config.to_string(),
+                "v".to_string(),
+                content.to_string(),
+                None,
+                "auto".to_string(),
+            )
+            .unwrap()
+        };
+
+        let first = save("claude", r#"{"model": "opus"}"#);
+        let second = save("claude", r#"{"model": "opus"}"#);
+        save("gemini", r#"{"model": "opus"}"#);
+        assert_eq!(blob_count(&dir), 1);
+
+        update_version_content_internal(&paths, "claude", &second.id, "{}".to_string()).unwrap();
+        assert_eq!(blob_count(&dir), 2);
+        assert_eq!(
+            load_version_internal(&paths, "claude".to_string(), second.id.clone())
+                .unwrap()
+                .content,
+            "{}"
+        );
+
+        delete_version_internal(&paths, "claude", &second.id).unwrap();
+        assert_eq!(blob_count(&dir), 1);
+        assert!(matches!(
+            delete_version_internal(&paths, "claude", &second.id),
+            Err(VersionError::NotFound(_))
+        ));
+
+        assert_eq!(
+            list_versions_internal(&paths, "claude".to_string())
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            list_config_ids_internal(&paths).unwrap(),
+            vec!["claude", "gemini"]
+        );
+        assert_eq!(
+            load_version_internal(&paths, "claude".to_string(), first.id)
+                .unwrap()
+                .content,
+            r#"{"model": "opus"}"#
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_migrates_legacy_version_files() {
+        let (dir, paths) = setup();
+        let legacy_dir = dir.join("versions/claude_settings");
+        fs::create_dir_all(&legacy_dir).unwrap();
+        let legacy = ConfigVersion {
+            id: "legacy-1".to_string(),
+            config_id: "claude/settings".to_string(),
+            name: "Before upgrade".to_string(),
+            content: "{\n  \"model\": \"sonnet\"\n}".to_string(),
+            description: Some("old layout".to_string()),
+            timestamp: 1_700_000_000,
+            source: "manual".to_string(),
+            is_default: true,
+        };
+        fs::write(
+            legacy_dir.join("legacy-1.json"),
+            serde_json::to_string_pretty(&legacy).unwrap(),
+        )
+        .unwrap();
+
+        let listed = list_versions_internal(&paths, "claude/settings".to_string()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].is_default);
+        assert!(!legacy_dir.exists());
+
+        let loaded = load_version_internal(
+            &paths,
+            "claude/settings".to_string(),
+            "legacy-1".to_string(),
+        )
+        .unwrap();
+        assert_eq!(loaded.content, legacy.content);
+        assert_eq!(loaded.description, legacy.description);
+
+        fs::remove_dir_all(&dir).ok();
+    }
 }
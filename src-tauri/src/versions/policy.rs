@@ -0,0 +1,463 @@
+use super::{
+    add_version, config_id_for_path, content_hash, get_versions_dir, link_config_path_internal,
+    now, read_index, update_index, write_blob,
+};
+use super::{ConfigVersion, VersionError, VersionIndex, VersionMetadata};
+use crate::app_paths::AppPaths;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+const AUTO_SOURCE: &str = "auto";
+
+/// Name given to automatic snapshots. Renaming one marks it as named, which
+/// protects it from pruning.
+const AUTO_VERSION_NAME: &str = "Auto snapshot";
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionRules {
+    /// Automatic snapshots always kept, newest first
+    pub keep_last: usize,
+    /// Beyond those, the newest snapshot of each day is kept for this many days
+    pub keep_daily_days: u64,
+}
+
+impl Default for RetentionRules {
+    fn default() -> Self {
+        RetentionRules {
+            keep_last: 20,
+            keep_daily_days: 30,
+        }
+    }
+}
+
+/// `versions/policy.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VersionPolicy {
+    /// Snapshot every write made through the app
+    pub auto_snapshots: bool,
+    /// A write within this many seconds of the previous automatic snapshot
+    /// replaces it instead of adding another
+    pub coalesce_window_secs: u64,
+    pub retention: RetentionRules,
+    /// Rules for specific config ids, replacing `retention`
+    pub overrides: HashMap<String, RetentionRules>,
+}
+
+impl Default for VersionPolicy {
+    fn default() -> Self {
+        VersionPolicy {
+            auto_snapshots: true,
+            coalesce_window_secs: 5 * 60,
+            retention: RetentionRules::default(),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl VersionPolicy {
+    fn rules_for(&self, config_id: &str) -> &RetentionRules {
+        self.overrides.get(config_id).unwrap_or(&self.retention)
+    }
+}
+
+/// Manual, default and renamed versions are never pruned or coalesced into.
+fn is_protected(version: &VersionMetadata) -> bool {
+    version.source != AUTO_SOURCE || version.is_default || version.name != AUTO_VERSION_NAME
+}
+
+pub fn load_policy_internal(app: &impl AppPaths) -> Result<VersionPolicy, VersionError> {
+    let path = get_versions_dir(app)?.join("policy.json");
+    if !path.exists() {
+        return Ok(VersionPolicy::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub fn save_policy_internal(
+    app: &impl AppPaths,
+    policy: &VersionPolicy,
+) -> Result<(), VersionError> {
+    let versions_dir = get_versions_dir(app)?;
+    fs::create_dir_all(&versions_dir)?;
+    fs::write(
+        versions_dir.join("policy.json"),
+        serde_json::to_string_pretty(policy)?,
+    )?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_version_policy(app: AppHandle) -> Result<VersionPolicy, VersionError> {
+    load_policy_internal(&app)
+}
+
+#[tauri::command]
+pub fn set_version_policy(
+    app: AppHandle,
+    policy: VersionPolicy,
+) -> Result<VersionPolicy, VersionError> {
+    save_policy_internal(&app, &policy)?;
+    Ok(policy)
+}
+
+/// Versions of `config_id` the rules would drop at `at`.
+fn prunable(
+    index: &VersionIndex,
+    config_id: &str,
+    rules: &RetentionRules,
+    at: u64,
+) -> Vec<VersionMetadata> {
+    let mut snapshots: Vec<&VersionMetadata> = index
+        .versions
+        .iter()
+        .map(|v| &v.metadata)
+        .filter(|m| m.config_id == config_id && !is_protected(m))
+        .collect();
+    snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    let mut days_kept = HashSet::new();
+    snapshots
+        .into_iter()
+        .enumerate()
+        .filter(|(i, version)| {
+            let day = version.timestamp / DAY_SECS;
+            if *i < rules.keep_last {
+                days_kept.insert(day);
+                return false;
+            }
+            // Newest first, so the first snapshot seen for a day is kept
+            let recent = at.saturating_sub(version.timestamp) < rules.keep_daily_days * DAY_SECS;
+            !(recent && days_kept.insert(day))
+        })
+        .map(|(_, version)| version.clone())
+        .collect()
+}
+
+fn remove_versions(index: &mut VersionIndex, pruned: &[VersionMetadata]) {
+    let ids: HashSet<(&str, &str)> = pruned
+        .iter()
+        .map(|v| (v.config_id.as_str(), v.id.as_str()))
+        .collect();
+    index
+        .versions
+        .retain(|v| !ids.contains(&(v.metadata.config_id.as_str(), v.metadata.id.as_str())));
+}
+
+fn config_ids(index: &VersionIndex, config_id: Option<&str>) -> Vec<String> {
+    let mut ids: Vec<String> = match config_id {
+        Some(id) => vec![id.to_string()],
+        None => index
+            .versions
+            .iter()
+            .map(|v| v.metadata.config_id.clone())
+            .collect(),
+    };
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// What `prune_versions` would delete, for one config or all of them.
+pub fn preview_version_pruning_internal(
+    app: &impl AppPaths,
+    config_id: Option<String>,
+) -> Result<Vec<VersionMetadata>, VersionError> {
+    let policy = load_policy_internal(app)?;
+    let (_, index) = read_index(app)?;
+    let at = now();
+    Ok(config_ids(&index, config_id.as_deref())
+        .iter()
+        .flat_map(|id| prunable(&index, id, policy.rules_for(id), at))
+        .collect())
+}
+
+#[tauri::command]
+pub fn preview_version_pruning(
+    app: AppHandle,
+    config_id: Option<String>,
+) -> Result<Vec<VersionMetadata>, VersionError> {
+    preview_version_pruning_internal(&app, config_id)
+}
+
+/// Apply the retention rules now. Returns the deleted versions.
+pub fn prune_versions_internal(
+    app: &impl AppPaths,
+    config_id: Option<String>,
+) -> Result<Vec<VersionMetadata>, VersionError> {
+    let policy = load_policy_internal(app)?;
+    update_index(app, |_, index| {
+        let at = now();
+        let pruned: Vec<VersionMetadata> = config_ids(index, config_id.as_deref())
+            .iter()
+            .flat_map(|id| prunable(index, id, policy.rules_for(id), at))
+            .collect();
+        remove_versions(index, &pruned);
+        Ok(pruned)
+    })
+}
+
+#[tauri::command]
+pub fn prune_versions(
+    app: AppHandle,
+    config_id: Option<String>,
+) -> Result<Vec<VersionMetadata>, VersionError> {
+    prune_versions_internal(&app, config_id)
+}
+
+/// Record `content` as an automatic snapshot of `config_id` after a write,
+/// then prune that config. Returns `None` when snapshots are off or the
+/// content matches the newest version.
+pub fn auto_snapshot_internal(
+    app: &impl AppPaths,
+    config_id: &str,
+    content: &str,
+) -> Result<Option<VersionMetadata>, VersionError> {
+    let policy = load_policy_internal(app)?;
+    if !policy.auto_snapshots {
+        return Ok(None);
+    }
+
+    update_index(app, |versions_dir, index| {
+        let at = now();
+        let latest = index
+            .versions
+            .iter_mut()
+            .filter(|v| v.metadata.config_id == config_id)
+            .max_by_key(|v| v.metadata.timestamp);
+
+        let snapshot = match latest {
+            Some(latest) if latest.blob == content_hash(content) => return Ok(None),
+            Some(latest)
+                if !is_protected(&latest.metadata)
+                    && at.saturating_sub(latest.metadata.timestamp)
+                        < policy.coalesce_window_secs =>
+            {
+                latest.blob = write_blob(versions_dir, content)?;
+                latest.size = content.len() as u64;
+                latest.metadata.timestamp = at;
+                latest.metadata.clone()
+            }
+            _ => {
+                let version = ConfigVersion {
+                    id: Uuid::new_v4().to_string(),
+                    config_id: config_id.to_string(),
+                    name: AUTO_VERSION_NAME.to_string(),
+                    content: content.to_string(),
+                    description: None,
+                    timestamp: at,
+                    source: AUTO_SOURCE.to_string(),
+                    is_default: false,
+                };
+                add_version(versions_dir, index, &version)?;
+                VersionMetadata::from(&version)
+            }
+        };
+
+        let pruned = prunable(index, config_id, policy.rules_for(config_id), at);
+        remove_versions(index, &pruned);
+        Ok(Some(snapshot))
+    })
+}
+
+/// Snapshot a write to `path` that has already landed. `config_id` is the
+/// config the write was made through; without one (MCP sync, the CLI) the
+/// snapshot goes to the config linked to the file. Failures are reported
+/// with `AppPaths::warn`.
+pub fn record_write(app: &impl AppPaths, path: &str, config_id: Option<&str>, content: &str) {
+    let config_id = match config_id {
+        Some(config_id) => {
+            link_config_path_internal(app, path, config_id).map(|_| config_id.to_string())
+        }
+        None => config_id_for_path(app, path),
+    };
+    let result = config_id.and_then(|config_id| auto_snapshot_internal(app, &config_id, content));
+    if let Err(e) = result {
+        app.warn(&format!("Failed to snapshot {}: {}", path, e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use crate::versions::{
+        list_versions_internal, load_version_internal, save_version_internal, StoredVersion,
+    };
+    use std::path::PathBuf;
+
+    fn setup() -> (PathBuf, StandalonePaths) {
+        let dir = std::env::temp_dir().join(format!("version-policy-test-{}", Uuid::new_v4()));
+        let paths = StandalonePaths {
+            data_dir: Some(dir.clone()),
+            config_dir: None,
+        };
+        (dir, paths)
+    }
+
+    fn stored(id: &str, timestamp: u64, name: &str, source: &str) -> StoredVersion {
+        StoredVersion {
+            metadata: VersionMetadata {
+                id: id.to_string(),
+                config_id: "claude".to_string(),
+                name: name.to_string(),
+                description: None,
+                timestamp,
+                source: source.to_string(),
+                is_default: false,
+            },
+            blob: String::new(),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_prunable_keeps_last_daily_and_protected() {
+        let at = 100 * DAY_SECS;
+        let mut index = VersionIndex::default();
+        // Two snapshots a day for the last five days, newest first
+        for day in 0..5 {
+            for half in 0..2 {
+                let ts = at - day * DAY_SECS - half * (DAY_SECS / 2) - 1;
+                index.versions.push(stored(
+                    &format!("d{}-{}", day, half),
+                    ts,
+                    AUTO_VERSION_NAME,
+                    AUTO_SOURCE,
+                ));
+            }
+        }
+        index.versions.push(stored(
+            "old",
+            at - 60 * DAY_SECS,
+            AUTO_VERSION_NAME,
+            AUTO_SOURCE,
+        ));
+        index.versions.push(stored(
+            "named",
+            at - 60 * DAY_SECS,
+            "Before MCP cleanup",
+            AUTO_SOURCE,
+        ));
+        index
+            .versions
+            .push(stored("manual", at - 90 * DAY_SECS, "v1", "manual"));
+        let mut default = stored(
+            "default",
+            at - 90 * DAY_SECS,
+            AUTO_VERSION_NAME,
+            AUTO_SOURCE,
+        );
+        default.metadata.is_default = true;
+        index.versions.push(default);
+
+        let rules = RetentionRules {
+            keep_last: 3,
+            keep_daily_days: 30,
+        };
+        let mut pruned: Vec<String> = prunable(&index, "claude", &rules, at)
+            .into_iter()
+            .map(|v| v.id)
+            .collect();
+        pruned.sort();
+        // d0-0, d0-1, d1-0 are the last three; d1-1 shares d1-0's day; each
+        // later day keeps its newest; "old" is past the daily window
+        assert_eq!(pruned, vec!["d1-1", "d2-1", "d3-1", "d4-1", "old"]);
+    }
+
+    #[test]
+    fn test_auto_snapshots_coalesce_and_skip_unchanged() {
+        let (dir, paths) = setup();
+
+        let first = auto_snapshot_internal(&paths, "claude", "{}")
+            .unwrap()
+            .unwrap();
+        assert!(auto_snapshot_internal(&paths, "claude", "{}")
+            .unwrap()
+            .is_none());
+
+        // Within the window the snapshot is replaced
+        let second = auto_snapshot_internal(&paths, "claude", r#"{"a": 1}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(
+            list_versions_internal(&paths, "claude".to_string())
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // A manual version in between starts a new snapshot
+        save_version_internal(
+            &paths,
+            "claude".to_string(),
+            "mine".to_string(),
+            "{}".to_string(),
+            None,
+            "manual".to_string(),
+        )
+        .unwrap();
+        let third = auto_snapshot_internal(&paths, "claude", r#"{"a": 2}"#)
+            .unwrap()
+            .unwrap();
+        assert_ne!(third.id, first.id);
+        assert_eq!(
+            list_versions_internal(&paths, "claude".to_string())
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let policy = VersionPolicy {
+            auto_snapshots: false,
+            ..VersionPolicy::default()
+        };
+        save_policy_internal(&paths, &policy).unwrap();
+        assert!(auto_snapshot_internal(&paths, "claude", "[]")
+            .unwrap()
+            .is_none());
+        assert!(preview_version_pruning_internal(&paths, None)
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+    #[test]
+    fn test_path_only_writes_are_filed_under_the_linked_config() {
+        let (dir, paths) = setup();
+        let file = dir.join("mcp.json").to_string_lossy().to_string();
+        let listed = |config_id: &str| {
+            list_versions_internal(&paths, config_id.to_string())
+                .unwrap()
+                .len()
+        };
+
+        // Before any config is linked, snapshots are kept under the file
+        record_write(&paths, &file, None, "{}");
+        assert_eq!(listed(&file), 1);
+
+        // Linking moves them over, and later path-only writes follow
+        link_config_path_internal(&paths, &file, "config-mcp").unwrap();
+        assert_eq!(listed(&file), 0);
+        record_write(&paths, &file, None, r#"{"a": 1}"#);
+        assert_eq!(listed("config-mcp"), 1);
+
+        // A write through another config of the same file relinks it
+        record_write(&paths, &file, Some("config-other"), r#"{"b": 1}"#);
+        record_write(&paths, &file, None, r#"{"c": 1}"#);
+        let other = list_versions_internal(&paths, "config-other".to_string()).unwrap();
+        let latest =
+            load_version_internal(&paths, "config-other".to_string(), other[0].id.clone()).unwrap();
+        assert_eq!(latest.content, r#"{"c": 1}"#);
+        assert_eq!(listed("config-mcp"), 1);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,360 @@
+use crate::app_paths::AppPaths;
+use crate::commands::expand_path;
+use crate::config::tool_registry;
+use crate::mcp::{
+    auto_sync_internal, get_mcp_config_path, get_mcp_tool_statuses_internal, mcp_source_path,
+};
+use crate::ssh::SshConnection;
+use crate::transaction::fingerprint;
+use crate::workspace::is_project_relative;
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use thiserror::Error;
+
+/// Emitted with a `ConfigChangeEvent` when a watched file changes on disk
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+/// Emitted with fresh `McpToolStatus`es after a watched MCP config changes
+pub const MCP_STATUS_CHANGED_EVENT: &str = "mcp-status-changed";
+/// Emitted with the `McpSyncLogEntry`s of an auto-sync run
+pub const MCP_AUTO_SYNC_EVENT: &str = "mcp-auto-sync";
+
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Error, Debug)]
+pub enum WatcherError {
+    #[error("Watch error: {0}")]
+    Notify(#[from] notify::Error),
+    #[error("Failed to resolve path: {0}")]
+    PathResolution(String),
+}
+
+impl Serialize for WatcherError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangeEvent {
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Same value as the `token` returned by the read commands, so a client
+    /// can tell its own writes apart from external ones
+    pub hash: String,
+    /// The file feeds MCP sync, either as source or as a tool target
+    pub mcp: bool,
+}
+
+fn current_hash(path: &Path) -> String {
+    fingerprint(fs::read_to_string(path).ok().as_deref())
+}
+
+/// The files being watched and the hash each had when last seen.
+#[derive(Default)]
+struct WatchSet {
+    registry: HashSet<PathBuf>,
+    mcp: HashSet<PathBuf>,
+    opened: HashSet<PathBuf>,
+    hashes: HashMap<PathBuf, String>,
+}
+
+impl WatchSet {
+    fn contains(&self, path: &Path) -> bool {
+        self.registry.contains(path) || self.opened.contains(path)
+    }
+
+    fn track(&mut self, path: &Path) {
+        if !self.hashes.contains_key(path) {
+            self.hashes.insert(path.to_path_buf(), current_hash(path));
+        }
+    }
+
+    fn set_registry(&mut self, paths: Vec<(PathBuf, bool)>) {
+        self.registry.clear();
+        self.mcp.clear();
+        for (path, mcp) in paths {
+            self.track(&path);
+            if mcp {
+                self.mcp.insert(path.clone());
+            }
+            self.registry.insert(path);
+        }
+        let (registry, opened) = (&self.registry, &self.opened);
+        self.hashes
+            .retain(|path, _| registry.contains(path) || opened.contains(path));
+    }
+
+    /// Directories to hand to the OS watcher. Files are often replaced by a
+    /// rename, which a watch on the file itself would not survive.
+    fn dirs(&self) -> HashSet<PathBuf> {
+        self.registry
+            .iter()
+            .chain(&self.opened)
+            .filter_map(|path| path.parent())
+            .filter(|dir| dir.is_dir())
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    /// Compare a watched file against its last known hash. Returns `None`
+    /// for unwatched files and for events that left the content unchanged.
+    fn detect(&mut self, path: &Path) -> Option<ConfigChangeEvent> {
+        if !self.contains(path) {
+            return None;
+        }
+
+        let hash = current_hash(path);
+        let previous = self.hashes.insert(path.to_path_buf(), hash.clone());
+        let missing = fingerprint(None);
+        let kind = match previous {
+            Some(previous) if previous == hash => return None,
+            None if hash == missing => return None,
+            Some(previous) if previous == missing => ChangeKind::Created,
+            None => ChangeKind::Created,
+            Some(_) if hash == missing => ChangeKind::Removed,
+            Some(_) => ChangeKind::Modified,
+        };
+
+        Some(ConfigChangeEvent {
+            path: path.to_string_lossy().to_string(),
+            kind,
+            hash,
+            mcp: self.mcp.contains(path),
+        })
+    }
+}
+
+/// Local config files from the tool registry, flagged when they take part
+/// in MCP sync. Project-relative and remote paths are left out.
+fn registry_paths(app: &impl AppPaths) -> Vec<(PathBuf, bool)> {
+    let registry = tool_registry(app);
+    let local = |path: &str| {
+        if is_project_relative(path) || SshConnection::parse_remote(path).is_some() {
+            None
+        } else {
+            expand_path(path)
+        }
+    };
+
+    let mut paths: Vec<(PathBuf, bool)> = Vec::new();
+    for tool in &registry.tools {
+        for config in tool.suggested_configs.iter().flatten() {
+            paths.extend(local(&config.path).map(|p| (p, false)));
+        }
+        for layer in tool.layering.iter().flat_map(|l| &l.layers) {
+            paths.extend(local(layer.path_for_os()).map(|p| (p, false)));
+        }
+    }
+
+    let mut mcp: Vec<PathBuf> = registry
+        .mcp_tools
+        .iter()
+        .filter_map(|t| local(&t.config_path))
+        .collect();
+    mcp.extend(local("~/.claude.json"));
+    mcp.extend(get_mcp_config_path(app).ok());
+
+    paths.retain(|(path, _)| !mcp.contains(path));
+    paths.extend(mcp.into_iter().map(|p| (p, true)));
+    paths
+}
+
+/// Watches config files and pushes `config-changed` events to the frontend.
+pub struct ConfigWatcher {
+    set: Arc<Mutex<WatchSet>>,
+    debouncer: Mutex<Debouncer<RecommendedWatcher>>,
+    watched_dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl ConfigWatcher {
+    pub fn start(app: AppHandle) -> Result<Self, WatcherError> {
+        let set = Arc::new(Mutex::new(WatchSet::default()));
+
+        let handler_set = Arc::clone(&set);
+        let handler_app = app.clone();
+        let debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+            let Ok(events) = result else {
+                return;
+            };
+
+            let changes: Vec<ConfigChangeEvent> = {
+                let mut set = handler_set.lock().unwrap_or_else(|e| e.into_inner());
+                let paths: HashSet<PathBuf> = events.into_iter().map(|e| e.path).collect();
+                paths.iter().filter_map(|path| set.detect(path)).collect()
+            };
+
+            for change in &changes {
+                let _ = handler_app.emit(CONFIG_CHANGED_EVENT, change.clone());
+            }
+            let source = mcp_source_path(&handler_app).ok();
+            if changes
+                .iter()
+                .any(|c| source.as_deref() == Some(Path::new(&c.path)))
+            {
+                match auto_sync_internal(&handler_app) {
+                    Ok(entries) if !entries.is_empty() => {
+                        let _ = handler_app.emit(MCP_AUTO_SYNC_EVENT, entries);
+                    }
+                    Ok(_) => {}
+                    Err(e) => handler_app.warn(&format!("MCP auto-sync failed: {}", e)),
+                }
+            }
+            if changes.iter().any(|c| c.mcp) {
+                if let Ok(statuses) = get_mcp_tool_statuses_internal(&handler_app) {
+                    let _ = handler_app.emit(MCP_STATUS_CHANGED_EVENT, statuses);
+                }
+            }
+        })?;
+
+        let watcher = ConfigWatcher {
+            set,
+            debouncer: Mutex::new(debouncer),
+            watched_dirs: Mutex::new(HashSet::new()),
+        };
+        watcher.refresh(&app)?;
+        Ok(watcher)
+    }
+
+    /// Re-read the tool registry, e.g. after tools or workspaces changed.
+    pub fn refresh(&self, app: &impl AppPaths) -> Result<(), WatcherError> {
+        let paths = registry_paths(app);
+        self.lock_set().set_registry(paths);
+        self.sync_dirs()
+    }
+
+    pub fn watch_file(&self, path: PathBuf) -> Result<(), WatcherError> {
+        {
+            let mut set = self.lock_set();
+            set.track(&path);
+            set.opened.insert(path);
+        }
+        self.sync_dirs()
+    }
+
+    pub fn unwatch_file(&self, path: &Path) -> Result<(), WatcherError> {
+        {
+            let mut set = self.lock_set();
+            set.opened.remove(path);
+            if !set.registry.contains(path) {
+                set.hashes.remove(path);
+            }
+        }
+        self.sync_dirs()
+    }
+
+    fn lock_set(&self) -> std::sync::MutexGuard<'_, WatchSet> {
+        self.set.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn sync_dirs(&self) -> Result<(), WatcherError> {
+        let wanted = self.lock_set().dirs();
+        let mut watched = self.watched_dirs.lock().unwrap_or_else(|e| e.into_inner());
+        let mut debouncer = self.debouncer.lock().unwrap_or_else(|e| e.into_inner());
+        let watcher = debouncer.watcher();
+
+        for dir in watched.difference(&wanted) {
+            // The directory may be gone already
+            let _ = watcher.unwatch(dir);
+        }
+        for dir in wanted.difference(&watched) {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+        *watched = wanted;
+        Ok(())
+    }
+}
+
+/// Pick up registry changes, if the watcher is running.
+pub fn refresh_watcher(app: &AppHandle) {
+    if let Some(watcher) = app.try_state::<ConfigWatcher>() {
+        if let Err(e) = watcher.refresh(app) {
+            app.warn(&format!("Failed to refresh config watcher: {}", e));
+        }
+    }
+}
+
+fn resolve(path: &str) -> Result<PathBuf, WatcherError> {
+    expand_path(path).ok_or_else(|| WatcherError::PathResolution(path.to_string()))
+}
+
+/// Watch a file opened in the editor, in addition to the registry's files.
+#[tauri::command]
+pub fn watch_file(watcher: State<'_, ConfigWatcher>, path: String) -> Result<(), WatcherError> {
+    watcher.watch_file(resolve(&path)?)
+}
+
+#[tauri::command]
+pub fn unwatch_file(watcher: State<'_, ConfigWatcher>, path: String) -> Result<(), WatcherError> {
+    watcher.unwatch_file(&resolve(&path)?)
+}
+
+#[tauri::command]
+pub fn refresh_config_watcher(
+    app: AppHandle,
+    watcher: State<'_, ConfigWatcher>,
+) -> Result<(), WatcherError> {
+    watcher.refresh(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_reports_changes_to_watched_files() {
+        let dir = std::env::temp_dir().join(format!("watcher-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let settings = dir.join("settings.json");
+        let mcp = dir.join("mcp.json");
+        let other = dir.join("other.json");
+        fs::write(&settings, "{}").unwrap();
+
+        let mut set = WatchSet::default();
+        set.set_registry(vec![(settings.clone(), false), (mcp.clone(), true)]);
+        assert_eq!(set.dirs(), HashSet::from([dir.clone()]));
+
+        // Touching a file without changing it is not a change
+        fs::write(&settings, "{}").unwrap();
+        assert!(set.detect(&settings).is_none());
+
+        fs::write(&settings, r#"{"model": "opus"}"#).unwrap();
+        let change = set.detect(&settings).unwrap();
+        assert_eq!(change.kind, ChangeKind::Modified);
+        assert_eq!(change.hash, fingerprint(Some(r#"{"model": "opus"}"#)));
+        assert!(!change.mcp);
+
+        fs::write(&mcp, "{}").unwrap();
+        let change = set.detect(&mcp).unwrap();
+        assert_eq!(change.kind, ChangeKind::Created);
+        assert!(change.mcp);
+
+        fs::remove_file(&settings).unwrap();
+        assert_eq!(set.detect(&settings).unwrap().kind, ChangeKind::Removed);
+
+        fs::write(&other, "{}").unwrap();
+        assert!(set.detect(&other).is_none());
+        set.opened.insert(other.clone());
+        assert_eq!(set.detect(&other).unwrap().kind, ChangeKind::Created);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
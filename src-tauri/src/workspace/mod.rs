@@ -0,0 +1,312 @@
+use crate::app_paths::AppPaths;
+use crate::commands::expand_path;
+use crate::config::{tool_registry, CliTool};
+use crate::ssh::SshConnection;
+use crate::watcher::refresh_watcher;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("Workspace not found: {0}")]
+    NotFound(String),
+    #[error("Not a directory: {0}")]
+    NotADirectory(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to get app data directory")]
+    AppDataDir,
+}
+
+impl Serialize for WorkspaceError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A registered project root. Project-relative suggested configs
+/// (`.mcp.json`, `.qwen/settings.json`, ...) resolve against `path`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    /// Lowercase slug of the directory name, unique among workspaces
+    pub id: String,
+    pub name: String,
+    pub path: String,
+}
+
+impl Workspace {
+    pub fn root(&self) -> PathBuf {
+        PathBuf::from(&self.path)
+    }
+}
+
+/// True for paths that only make sense inside a project (`.mcp.json`,
+/// `opencode.json`), as opposed to `~/...`, absolute or remote paths.
+pub fn is_project_relative(path: &str) -> bool {
+    !(path.starts_with(['~', '/', '%', '\\'])
+        || Path::new(path).is_absolute()
+        || SshConnection::parse_remote(path).is_some())
+}
+
+fn get_workspaces_path(app: &impl AppPaths) -> Result<PathBuf, WorkspaceError> {
+    let app_data = app.app_data_dir().ok_or(WorkspaceError::AppDataDir)?;
+    Ok(app_data.join("workspaces.json"))
+}
+
+pub fn load_workspaces(app: &impl AppPaths) -> Result<Vec<Workspace>, WorkspaceError> {
+    let path = get_workspaces_path(app)?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_workspaces(app: &impl AppPaths, workspaces: &[Workspace]) -> Result<(), WorkspaceError> {
+    let path = get_workspaces_path(app)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, serde_json::to_string_pretty(workspaces)?)?;
+    Ok(())
+}
+
+fn slugify(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "project".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Register `path` as a project root. Adding the same root twice returns the
+/// existing workspace.
+pub fn add_workspace_internal(
+    app: &impl AppPaths,
+    path: String,
+    name: Option<String>,
+) -> Result<Workspace, WorkspaceError> {
+    let root = expand_path(&path).ok_or_else(|| WorkspaceError::NotADirectory(path.clone()))?;
+    if !root.is_dir() {
+        return Err(WorkspaceError::NotADirectory(path));
+    }
+    let root = root.canonicalize()?;
+    let root_str = root.to_string_lossy().to_string();
+
+    let mut workspaces = load_workspaces(app)?;
+    if let Some(existing) = workspaces.iter().find(|w| w.path == root_str) {
+        return Ok(existing.clone());
+    }
+
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .or_else(|| root.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| root_str.clone());
+
+    let base = slugify(&name);
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while workspaces.iter().any(|w| w.id == id) {
+        id = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+
+    let workspace = Workspace {
+        id,
+        name,
+        path: root_str,
+    };
+    workspaces.push(workspace.clone());
+    save_workspaces(app, &workspaces)?;
+    Ok(workspace)
+}
+
+pub fn remove_workspace_internal(
+    app: &impl AppPaths,
+    workspace_id: &str,
+) -> Result<Vec<Workspace>, WorkspaceError> {
+    let mut workspaces = load_workspaces(app)?;
+    let before = workspaces.len();
+    workspaces.retain(|w| w.id != workspace_id);
+    if workspaces.len() == before {
+        return Err(WorkspaceError::NotFound(workspace_id.to_string()));
+    }
+    save_workspaces(app, &workspaces)?;
+    Ok(workspaces)
+}
+
+pub fn find_workspace(
+    app: &impl AppPaths,
+    workspace_id: &str,
+) -> Result<Workspace, WorkspaceError> {
+    load_workspaces(app)?
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| WorkspaceError::NotFound(workspace_id.to_string()))
+}
+
+/// The registry's tools with project-relative suggested configs resolved
+/// against `root`, so a project's files list next to the user-level ones.
+pub fn tools_for_workspace(tools: Vec<CliTool>, root: &Path) -> Vec<CliTool> {
+    tools
+        .into_iter()
+        .map(|mut tool| {
+            for config in tool.suggested_configs.iter_mut().flatten() {
+                if is_project_relative(&config.path) {
+                    config.path = root.join(&config.path).to_string_lossy().to_string();
+                }
+            }
+            tool
+        })
+        .collect()
+}
+
+pub fn get_workspace_tools_internal(
+    app: &impl AppPaths,
+    workspace_id: &str,
+) -> Result<Vec<CliTool>, WorkspaceError> {
+    let workspace = find_workspace(app, workspace_id)?;
+    Ok(tools_for_workspace(
+        tool_registry(app).tools,
+        &workspace.root(),
+    ))
+}
+
+#[tauri::command]
+pub fn list_workspaces(app: AppHandle) -> Result<Vec<Workspace>, WorkspaceError> {
+    load_workspaces(&app)
+}
+
+#[tauri::command]
+pub fn add_workspace(
+    app: AppHandle,
+    path: String,
+    name: Option<String>,
+) -> Result<Workspace, WorkspaceError> {
+    let workspace = add_workspace_internal(&app, path, name)?;
+    refresh_watcher(&app);
+    Ok(workspace)
+}
+
+#[tauri::command]
+pub fn remove_workspace(
+    app: AppHandle,
+    workspace_id: String,
+) -> Result<Vec<Workspace>, WorkspaceError> {
+    let workspaces = remove_workspace_internal(&app, &workspace_id)?;
+    refresh_watcher(&app);
+    Ok(workspaces)
+}
+
+#[tauri::command]
+pub fn get_workspace_tools(
+    app: AppHandle,
+    workspace_id: String,
+) -> Result<Vec<CliTool>, WorkspaceError> {
+    get_workspace_tools_internal(&app, &workspace_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_paths::StandalonePaths;
+    use crate::config::get_cli_tools;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("workspace-test-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_is_project_relative() {
+        assert!(is_project_relative(".mcp.json"));
+        assert!(is_project_relative(".qwen/settings.json"));
+        assert!(is_project_relative("opencode.json"));
+        assert!(!is_project_relative("~/.claude.json"));
+        assert!(!is_project_relative("/etc/config.json"));
+        assert!(!is_project_relative("%APPDATA%\\Code\\settings.json"));
+        assert!(!is_project_relative("dev@box:~/.claude.json"));
+    }
+
+    #[test]
+    fn test_add_and_remove_workspaces() {
+        let data = temp_dir("data");
+        let paths = StandalonePaths {
+            data_dir: Some(data.clone()),
+            config_dir: None,
+        };
+        let project = temp_dir("project");
+        let project_str = project.to_string_lossy().to_string();
+
+        let first = add_workspace_internal(&paths, project_str.clone(), Some("My App".to_string()))
+            .unwrap();
+        assert_eq!(first.id, "my-app");
+        assert_eq!(
+            add_workspace_internal(&paths, project_str, None).unwrap(),
+            first
+        );
+
+        let other = temp_dir("other");
+        let second = add_workspace_internal(
+            &paths,
+            other.to_string_lossy().to_string(),
+            Some("my app".to_string()),
+        )
+        .unwrap();
+        assert_eq!(second.id, "my-app-2");
+
+        assert!(add_workspace_internal(&paths, "/nonexistent/project".to_string(), None).is_err());
+
+        let remaining = remove_workspace_internal(&paths, "my-app").unwrap();
+        assert_eq!(remaining, vec![second]);
+        assert!(matches!(
+            remove_workspace_internal(&paths, "my-app"),
+            Err(WorkspaceError::NotFound(_))
+        ));
+
+        for dir in [data, project, other] {
+            fs::remove_dir_all(dir).ok();
+        }
+    }
+
+    #[test]
+    fn test_tools_for_workspace_resolves_relative_configs() {
+        let root = Path::new("/work/app");
+        let tools = tools_for_workspace(get_cli_tools(), root);
+        let claude = tools.iter().find(|t| t.id == "claude-code").unwrap();
+        let paths: Vec<&str> = claude
+            .suggested_configs
+            .iter()
+            .flatten()
+            .map(|c| c.path.as_str())
+            .collect();
+
+        assert!(paths.contains(&"/work/app/.mcp.json"));
+        assert!(paths.contains(&"~/.claude.json"));
+    }
+}
@@ -19,10 +19,12 @@ import { CommandPalette } from '@/components/command-palette';
 import { UnsavedChangesDialog, type UnsavedChangesAction } from '@/components/ui';
 import type { AppView } from '@/components';
 import { useAppStore } from '@/stores/app-store';
+import { useVersionsStore } from '@/stores/versions-store';
 import { useShallow } from 'zustand/react/shallow';
 import { useFileWatcher, useSystemTheme, useReducedMotion, useRecentFiles, useUpdateChecker, useSidebarStateSync } from '@/hooks';
 import { invoke } from '@tauri-apps/api/core';
-import { ConfigFormat, CustomTool, CliTool, ConfigFile, parseBackendError, isFileNotFoundError, isFileReadError } from '@/types';
+import { listen } from '@tauri-apps/api/event';
+import { ConfigFormat, CustomTool, CliTool, ConfigFile, parseBackendError, isFileNotFoundError, isFileReadError, isConflictError, FileContent } from '@/types';
 import { IDE_PLATFORMS } from '@/utils/cli-tools';
 import { getFileName } from '@/utils/path';
 import { formatErrorShort } from '@/utils/error-messages';
@@ -74,6 +76,7 @@ function App() {
   const setCurrentJsonPrefix = useAppStore((state) => state.setCurrentJsonPrefix);
   const setCurrentPathType = useAppStore((state) => state.setCurrentPathType);
   const setCurrentSshPath = useAppStore((state) => state.setCurrentSshPath);
+  const setCurrentFileToken = useAppStore((state) => state.setCurrentFileToken);
   const setLoading = useAppStore((state) => state.setLoading);
   const setError = useAppStore((state) => state.setError);
   const setFileNotFound = useAppStore((state) => state.setFileNotFound);
@@ -201,26 +204,37 @@ function App() {
     onExternalChange: handleExternalChange,
   });
 
+  // Failures in background work, e.g. a snapshot after a save that succeeded
+  useEffect(() => {
+    const unlisten = listen<string>('app-warning', (event) => {
+      toast.warning(event.payload);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   const handleReloadFile = useCallback(async () => {
     if (!currentFilePath) return;
     try {
-      let content: string;
+      let file: FileContent;
       if (currentJsonPath) {
-        content = await invoke<string>('read_json_path', {
+        file = await invoke<FileContent>('read_json_path', {
           path: currentFilePath,
           jsonPath: currentJsonPath
         });
       } else {
-        content = await invoke<string>('read_file', { path: currentFilePath });
+        file = await invoke<FileContent>('read_file', { path: currentFilePath });
       }
-      setEditorContent(content);
-      setOriginalContent(content);
+      setEditorContent(file.content);
+      setOriginalContent(file.content);
+      setCurrentFileToken(file.token);
       toast.success('File reloaded');
     } catch (err) {
       toast.error(formatErrorShort(err, 'Failed to reload'));
     }
     setExternalChangeDetected(false);
-  }, [currentFilePath, currentJsonPath, setEditorContent, setOriginalContent]);
+  }, [currentFilePath, currentJsonPath, setEditorContent, setOriginalContent, setCurrentFileToken]);
 
   const handleDismissExternalChange = useCallback(() => {
     setExternalChangeDetected(false);
@@ -234,6 +248,7 @@ function App() {
       setError(null);
       setFileNotFound(false);
       clearFileReadError();
+      setCurrentFileToken(null);
 
       // Get tool name for recent files tracking
       const allTools = getAllTools();
@@ -246,13 +261,15 @@ function App() {
         // Handle SSH remote paths
         if (configFile.pathType === 'ssh' && configFile.sshPath) {
           content = await readRemoteConfig(configFile.sshPath);
-        } else if (configFile.jsonPath) {
-          content = await invoke<string>('read_json_path', {
-            path: configFile.path,
-            jsonPath: configFile.jsonPath
-          });
         } else {
-          content = await invoke<string>('read_file', { path: configFile.path });
+          const file = configFile.jsonPath
+            ? await invoke<FileContent>('read_json_path', {
+                path: configFile.path,
+                jsonPath: configFile.jsonPath
+              })
+            : await invoke<FileContent>('read_file', { path: configFile.path });
+          content = file.content;
+          setCurrentFileToken(file.token);
         }
         setEditorContent(content);
         setOriginalContent(content);
@@ -329,6 +346,7 @@ function App() {
       setCurrentJsonPath,
       setCurrentPathType,
       setCurrentSshPath,
+      setCurrentFileToken,
       getAllTools,
       addRecentFile,
     ]
@@ -370,26 +388,28 @@ function App() {
       setError(null);
       setFileNotFound(false);
       clearFileReadError();
+      setCurrentFileToken(null);
 
       try {
-        let content: string;
+        let file: FileContent;
         if (settingPath) {
-          content = await invoke<string>('read_json_path', {
+          file = await invoke<FileContent>('read_json_path', {
             path: settingsPath,
             jsonPath: settingPath,
           });
           setCurrentJsonPath(settingPath);
           setCurrentJsonPrefix(null);
         } else {
-          content = await invoke<string>('read_json_prefix', {
+          file = await invoke<FileContent>('read_json_prefix', {
             path: settingsPath,
             prefix: prefix,
           });
           setCurrentJsonPath(null);
           setCurrentJsonPrefix(prefix);
         }
-        setEditorContent(content);
-        setOriginalContent(content);
+        setEditorContent(file.content);
+        setOriginalContent(file.content);
+        setCurrentFileToken(file.token);
         setCurrentFilePath(settingsPath);
         setCurrentFormat('json');
         setFileNotFound(false);
@@ -456,6 +476,7 @@ function App() {
       setCurrentFormat,
       setCurrentJsonPath,
       setCurrentJsonPrefix,
+      setCurrentFileToken,
     ]
   );
 
@@ -474,7 +495,7 @@ function App() {
   const handleSave = useCallback(async (isAutoSave?: boolean): Promise<boolean> => {
     if (!currentFilePath) return false;
 
-    const { backupSettings } = useAppStore.getState();
+    const { backupSettings, currentFileToken, activeConfigFileId } = useAppStore.getState();
 
     try {
       markAsInternalWrite();
@@ -491,7 +512,7 @@ function App() {
         }
         await writeRemoteConfig(currentSshPath, editorContent);
       } else if (currentJsonPath) {
-        await invoke('write_json_path', {
+        const token = await invoke<string>('write_json_path', {
           path: currentFilePath,
           jsonPath: currentJsonPath,
           content: editorContent,
@@ -499,9 +520,12 @@ function App() {
             enabled: backupSettings.enabled,
             maxBackups: backupSettings.maxBackups,
           },
+          expectedToken: currentFileToken,
+          configId: activeConfigFileId,
         });
+        setCurrentFileToken(token);
       } else if (currentJsonPrefix) {
-        await invoke('write_json_prefix', {
+        const token = await invoke<string>('write_json_prefix', {
           path: currentFilePath,
           prefix: currentJsonPrefix,
           content: editorContent,
@@ -509,29 +533,46 @@ function App() {
             enabled: backupSettings.enabled,
             maxBackups: backupSettings.maxBackups,
           },
+          expectedToken: currentFileToken,
+          configId: activeConfigFileId,
         });
+        setCurrentFileToken(token);
       } else {
-        await invoke('write_file', {
+        const token = await invoke<string>('write_file', {
           path: currentFilePath,
           content: editorContent,
           backupSettings: {
             enabled: backupSettings.enabled,
             maxBackups: backupSettings.maxBackups,
           },
+          expectedToken: currentFileToken,
+          configId: activeConfigFileId,
         });
+        setCurrentFileToken(token);
       }
       setOriginalContent(editorContent);
       setError(null);
       setFileNotFound(false);
+      // The save may have added or replaced an automatic snapshot
+      const { currentConfigId, fetchVersions } = useVersionsStore.getState();
+      if (currentConfigId && currentConfigId === activeConfigFileId) {
+        fetchVersions(currentConfigId);
+      }
       if (!isAutoSave) {
         toast.success('Configuration saved successfully');
       }
       return true;
     } catch (err) {
+      if (isConflictError(parseBackendError(err))) {
+        // Keep the user's edits; the banner offers to reload the on-disk version
+        toast.error('The file was changed outside the editor. Reload it before saving.');
+        setExternalChangeDetected(true);
+        return false;
+      }
       toast.error(formatErrorShort(err, 'Failed to save'));
       return false;
     }
-  }, [currentFilePath, currentJsonPath, currentJsonPrefix, currentPathType, currentSshPath, editorContent, setOriginalContent, markAsInternalWrite, setError, setFileNotFound]);
+  }, [currentFilePath, currentJsonPath, currentJsonPrefix, currentPathType, currentSshPath, editorContent, setOriginalContent, setCurrentFileToken, markAsInternalWrite, setError, setFileNotFound]);
 
   const handleUnsavedChangesAction = useCallback(async (action: UnsavedChangesAction) => {
     if (action === 'cancel') {
@@ -10,6 +10,8 @@ function getErrorTitle(errorType: string): string {
   switch (errorType) {
     case 'JsonParse':
       return 'JSON Parse Error';
+    case 'ConfigParse':
+      return 'Config Parse Error';
     case 'PermissionDenied':
       return 'Permission Denied';
     case 'Io':
@@ -25,6 +27,8 @@ function getErrorDescription(errorType: string): string {
   switch (errorType) {
     case 'JsonParse':
       return 'The file contains invalid JSON. Please fix the syntax error before editing.';
+    case 'ConfigParse':
+      return 'The file could not be parsed in its configured format. Please fix the syntax error before editing.';
     case 'PermissionDenied':
       return 'Cannot read this file due to permission restrictions.';
     case 'Io':
@@ -27,6 +27,10 @@ const formatLabels: Record<McpDetectedFormat, string> = {
   amp: 'Amp (amp.mcpServers)',
   copilot: 'Copilot (servers)',
   opencode: 'OpenCode (mcp.servers)',
+  vscode: 'VS Code (mcp.servers)',
+  zed: 'Zed (context_servers)',
+  continue: 'Continue (mcpServers list)',
+  codex: 'Codex (mcp_servers tables)',
 };
 
 export function McpImportPreviewModal({
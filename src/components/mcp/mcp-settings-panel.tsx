@@ -1,7 +1,8 @@
 import { useEffect, useState } from 'react';
 import { open } from '@tauri-apps/plugin-dialog';
+import { listen } from '@tauri-apps/api/event';
 import { useMcpStore } from '@/stores/mcp-store';
-import { McpServer, McpSyncPreview, McpServerConflict, McpConfigPreview, McpImportResult, McpImportMode, McpDetectedFormat } from '@/types';
+import { McpServer, McpToolStatus, McpSyncLogEntry, McpSyncPreview, McpServerConflict, McpConfigPreview, McpImportResult, McpImportMode, McpDetectedFormat } from '@/types';
 import { McpSourceSelector } from './mcp-source-selector';
 import { McpServerList } from './mcp-server-list';
 import { McpServerEditorModal } from './mcp-server-editor';
@@ -40,6 +41,12 @@ export function McpSettingsPanel() {
     clearActiveConflicts,
     importFromFile,
     executeImport,
+    setToolStatuses,
+    loadSourceServers,
+    autoSync,
+    setAutoSync,
+    reviewQueue,
+    loadSyncLog,
   } = useMcpStore();
 
   const [isServerEditorOpen, setIsServerEditorOpen] = useState(false);
@@ -68,6 +75,36 @@ export function McpSettingsPanel() {
     loadConfig();
   }, [loadConfig]);
 
+  useEffect(() => {
+    const unlisten = listen<McpToolStatus[]>('mcp-status-changed', (event) => {
+      setToolStatuses(event.payload);
+      loadSourceServers();
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [setToolStatuses, loadSourceServers]);
+
+  useEffect(() => {
+    loadSyncLog();
+    const unlisten = listen<McpSyncLogEntry[]>('mcp-auto-sync', (event) => {
+      const applied = event.payload.filter((e) => e.action === 'applied').length;
+      const queued = event.payload.filter((e) => e.action === 'queued').length;
+      const failed = event.payload.filter((e) => e.action === 'failed').length;
+      if (failed > 0) {
+        toast.error(`Auto-sync failed for ${failed} tool${failed !== 1 ? 's' : ''}`);
+      } else if (queued > 0) {
+        toast.warning(`Auto-synced ${applied} tool${applied !== 1 ? 's' : ''}, ${queued} need review`);
+      } else {
+        toast.success(`Auto-synced ${applied} tool${applied !== 1 ? 's' : ''}`);
+      }
+      loadSyncLog();
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [loadSyncLog]);
+
   const handleAddServer = async (server: McpServer) => {
     try {
       await addServer(server);
@@ -365,6 +402,27 @@ export function McpSettingsPanel() {
               serverCount={servers.length}
               onModeChange={setSourceMode}
             />
+            <label className="mt-4 flex items-start gap-3 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={autoSync}
+                onChange={(e) => setAutoSync(e.target.checked)}
+                className="mt-0.5 rounded border-slate-300 dark:border-slate-600 text-indigo-600 focus:ring-indigo-500"
+              />
+              <span>
+                <span className="block text-sm font-medium text-slate-700 dark:text-slate-200">
+                  Auto-sync on source changes
+                </span>
+                <span className="block text-xs text-slate-500 dark:text-slate-400 mt-0.5">
+                  Push changes to enabled tools as soon as the source file changes. Tools with conflicts are queued for review.
+                </span>
+                {reviewQueue.length > 0 && (
+                  <span className="block text-xs text-amber-600 dark:text-amber-400 mt-1">
+                    Waiting for review: {reviewQueue.join(', ')}
+                  </span>
+                )}
+              </span>
+            </label>
           </section>
 
           {/* MCP Servers */}
@@ -165,6 +165,21 @@ export function McpSyncPreviewModal({
                 </div>
               )}
 
+              {(preview.mergeResult.updated.length > 0 || preview.mergeResult.removed.length > 0) && (
+                <div className="text-xs text-slate-600 dark:text-slate-400">
+                  From source: {preview.mergeResult.updated.length} updated,{' '}
+                  {preview.mergeResult.removed.length} removed
+                </div>
+              )}
+
+              {(preview.mergeResult.sourceUpdates.length > 0 ||
+                preview.mergeResult.sourceRemovals.length > 0) && (
+                <div className="text-xs text-slate-600 dark:text-slate-400">
+                  Back to source: {preview.mergeResult.sourceUpdates.length} updated,{' '}
+                  {preview.mergeResult.sourceRemovals.length} removed
+                </div>
+              )}
+
               {preview.mergeResult.kept.length > 0 && (
                 <div className="text-xs text-slate-500 dark:text-slate-400">
                   {preview.mergeResult.kept.length} server
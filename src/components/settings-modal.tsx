@@ -1,8 +1,11 @@
-import { useState } from 'react';
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { Moon, Sun, Monitor, Type, Code, Settings2, Shield, RotateCcw, Keyboard } from 'lucide-react';
 import { useAppStore } from '@/stores/app-store';
 import { APP_VERSION } from '@/constants/design-tokens';
 import { Modal, Toggle, Button } from '@/components/ui';
+import { toast } from './toast';
+import type { HistoryStatus } from '@/types';
 
 interface SettingsModalProps {
   isOpen: boolean;
@@ -26,6 +29,21 @@ export function SettingsModal({ isOpen, onClose, onOpenKeyboardShortcuts }: Sett
   } = useAppStore();
 
   const [activeTab, setActiveTab] = useState<SettingsTab>('appearance');
+  const [historyStatus, setHistoryStatus] = useState<HistoryStatus | null>(null);
+
+  useEffect(() => {
+    if (isOpen && activeTab === 'backup') {
+      invoke<HistoryStatus>('get_history_status').then(setHistoryStatus).catch(() => setHistoryStatus(null));
+    }
+  }, [isOpen, activeTab]);
+
+  const handleHistoryToggle = async (enabled: boolean) => {
+    try {
+      setHistoryStatus(await invoke<HistoryStatus>('set_history_enabled', { enabled }));
+    } catch (err) {
+      toast.error(`Failed to change git history: ${String(err)}`);
+    }
+  };
 
   const handleTabKeyDown = (e: React.KeyboardEvent, currentIndex: number) => {
     const tabCount = tabs.length;
@@ -325,6 +343,21 @@ export function SettingsModal({ isOpen, onClose, onOpenKeyboardShortcuts }: Sett
                 Older backups will be automatically deleted
               </p>
             </div>
+
+            {historyStatus && (
+              <div>
+                <Toggle
+                  label="Git History"
+                  description="Commit every save to a local git repository instead of rotating .bak files"
+                  checked={historyStatus.enabled}
+                  onChange={handleHistoryToggle}
+                  disabled={!historyStatus.gitAvailable}
+                />
+                <p className="text-xs dark:text-slate-500 text-slate-400 mt-1 font-mono break-all">
+                  {historyStatus.gitAvailable ? historyStatus.repoPath : 'git was not found on PATH'}
+                </p>
+              </div>
+            )}
           </div>
         )}
 
@@ -5,6 +5,7 @@ import { IDE_PLATFORMS, IDE_EXTENSIONS } from '@/utils/cli-tools';
 import { PLATFORM_ICONS, DEFAULT_PLATFORM_ICON, EXTENSION_ICON, ACCENT_COLORS } from '@/constants/tool-icons';
 import { SidebarSection } from './sidebar-section';
 import { IconContainer } from '@/components/ui';
+import type { FileContent } from '@/types';
 
 interface IdeExtensionsSectionProps {
   onExtensionConfigSelect?: (platformId: string, extensionId: string, settingPath: string | null) => void;
@@ -39,7 +40,7 @@ export const IdeExtensionsSection = memo(function IdeExtensionsSection({ onExten
         setSettingStatus(prev => ({ ...prev, [key]: 'loading' }));
 
         try {
-          await invoke<string>('read_json_path', {
+          await invoke<FileContent>('read_json_path', {
             path: settingsPath,
             jsonPath: fullPath,
           });
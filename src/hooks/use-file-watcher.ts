@@ -1,59 +1,53 @@
 import { useEffect, useRef, useCallback } from 'react';
-import { watch, WatchEvent } from '@tauri-apps/plugin-fs';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useAppStore } from '@/stores/app-store';
+import type { ConfigChangeEvent } from '@/types';
 
 interface UseFileWatcherOptions {
   onExternalChange: () => void;
-  debounceMs?: number;
 }
 
+// Changes are detected and debounced by the backend watcher, which emits
+// 'config-changed' with the new content hash of the file.
 export function useFileWatcher(
   filePath: string | null,
-  { onExternalChange, debounceMs = 500 }: UseFileWatcherOptions
+  { onExternalChange }: UseFileWatcherOptions
 ) {
-  const unwatchRef = useRef<(() => void) | null>(null);
   const lastWriteTimeRef = useRef<number>(0);
-  const timeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 
   const markAsInternalWrite = useCallback(() => {
     lastWriteTimeRef.current = Date.now();
   }, []);
 
   useEffect(() => {
-    if (!filePath) {
-      if (unwatchRef.current) {
-        unwatchRef.current();
-        unwatchRef.current = null;
-      }
-      return;
-    }
+    if (!filePath) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
 
     const setupWatcher = async () => {
       try {
-        if (unwatchRef.current) {
-          unwatchRef.current();
-        }
+        const resolvedPath = await invoke<string>('resolve_path', { path: filePath });
+        await invoke('watch_file', { path: filePath });
 
-        const unwatch = await watch(filePath, (event: WatchEvent) => {
-          const isModify =
-            typeof event.type === 'object' && 'modify' in event.type;
+        const stop = await listen<ConfigChangeEvent>('config-changed', (event) => {
+          if (event.payload.path !== resolvedPath) return;
 
-          if (!isModify) return;
+          // Our own save: the hash matches the token the write returned
+          if (event.payload.hash === useAppStore.getState().currentFileToken) return;
 
           const timeSinceWrite = Date.now() - lastWriteTimeRef.current;
-          if (timeSinceWrite < 1000) {
-            return;
-          }
+          if (timeSinceWrite < 1000) return;
 
-          if (timeoutRef.current) {
-            clearTimeout(timeoutRef.current);
-          }
-
-          timeoutRef.current = setTimeout(() => {
-            onExternalChange();
-          }, debounceMs);
+          onExternalChange();
         });
 
-        unwatchRef.current = unwatch;
+        if (disposed) {
+          stop();
+        } else {
+          unlisten = stop;
+        }
       } catch (error) {
         console.error('Failed to setup file watcher:', error);
       }
@@ -62,15 +56,13 @@ export function useFileWatcher(
     setupWatcher();
 
     return () => {
-      if (unwatchRef.current) {
-        unwatchRef.current();
-        unwatchRef.current = null;
-      }
-      if (timeoutRef.current) {
-        clearTimeout(timeoutRef.current);
+      disposed = true;
+      if (unlisten) {
+        unlisten();
       }
+      invoke('unwatch_file', { path: filePath }).catch(() => {});
     };
-  }, [filePath, onExternalChange, debounceMs]);
+  }, [filePath, onExternalChange]);
 
   return { markAsInternalWrite };
 }
@@ -45,6 +45,7 @@ interface AppState {
   currentJsonPrefix: string | null;  // For prefix-based JSON editing (e.g., "amp")
   currentPathType: PathType;  // 'local' or 'ssh' for current file
   currentSshPath: string | null;  // SSH path when pathType is 'ssh'
+  currentFileToken: string | null;  // Token of the on-disk version the editor was loaded from
 
   // UI state
   searchQuery: string;
@@ -73,6 +74,7 @@ interface AppState {
   setCurrentJsonPrefix: (prefix: string | null) => void;
   setCurrentPathType: (pathType: PathType) => void;
   setCurrentSshPath: (sshPath: string | null) => void;
+  setCurrentFileToken: (token: string | null) => void;
   setSearchQuery: (query: string) => void;
   setSidebarCollapsed: (collapsed: boolean) => void;
   setSidebarWidth: (width: number) => void;
@@ -129,6 +131,7 @@ export const useAppStore = create<AppState>()(
       currentJsonPrefix: null,
       currentPathType: 'local',
       currentSshPath: null,
+      currentFileToken: null,
       searchQuery: '',
       expandedTools: new Set<string>(),
       sidebarCollapsed: false,
@@ -174,6 +177,7 @@ export const useAppStore = create<AppState>()(
       setCurrentJsonPrefix: (prefix) => set({ currentJsonPrefix: prefix }),
       setCurrentPathType: (pathType) => set({ currentPathType: pathType }),
       setCurrentSshPath: (sshPath) => set({ currentSshPath: sshPath }),
+      setCurrentFileToken: (token) => set({ currentFileToken: token }),
       setSearchQuery: (query) => set({ searchQuery: query }),
       setSidebarCollapsed: (collapsed) => set({ sidebarCollapsed: collapsed }),
       setSidebarWidth: (width) => set({ sidebarWidth: width }),
@@ -34,6 +34,7 @@ const createMockConfig = (overrides?: Partial<McpConfig>): McpConfig => ({
   sourceMode: 'claude',
   servers: [],
   enabledTools: [],
+  autoSync: false,
   ...overrides,
 });
 
@@ -44,11 +45,13 @@ describe('mcp-store', () => {
       sourceMode: 'claude',
       servers: [],
       enabledTools: [],
+      autoSync: false,
       toolStatuses: [],
       isLoading: false,
       error: null,
       syncPreviews: [],
       activeConflicts: [],
+      reviewQueue: [],
     });
   });
 
@@ -527,6 +530,28 @@ describe('mcp-store', () => {
       });
     });
 
+    describe('setAutoSync', () => {
+      it('should enable auto-sync', async () => {
+        mockInvokeSuccess({
+          set_mcp_auto_sync: createMockConfig({ autoSync: true }),
+        });
+
+        await useMcpStore.getState().setAutoSync(true);
+
+        expect(useMcpStore.getState().autoSync).toBe(true);
+      });
+
+      it('should load the review queue from the sync log', async () => {
+        mockInvokeSuccess({
+          get_mcp_sync_log: { entries: [], reviewQueue: ['cursor'] },
+        });
+
+        await useMcpStore.getState().loadSyncLog();
+
+        expect(useMcpStore.getState().reviewQueue).toEqual(['cursor']);
+      });
+    });
+
     describe('previewSync', () => {
       it('should preview sync for single tool successfully', async () => {
         const preview: McpSyncPreview = {
@@ -535,10 +560,15 @@ describe('mcp-store', () => {
           mergeResult: {
             toolId: 'claude',
             added: [createMockServer('new-server')],
+            updated: [],
+            removed: [],
             kept: [],
+            sourceUpdates: [],
+            sourceRemovals: [],
             conflicts: [],
           },
           hasChanges: true,
+          fingerprint: 'sha256:abc',
         };
 
         mockInvokeSuccess({
@@ -569,14 +599,16 @@ describe('mcp-store', () => {
           {
             toolId: 'claude',
             toolName: 'Claude',
-            mergeResult: { toolId: 'claude', added: [], kept: [], conflicts: [] },
+            mergeResult: { toolId: 'claude', added: [], updated: [], removed: [], kept: [], sourceUpdates: [], sourceRemovals: [], conflicts: [] },
             hasChanges: false,
+            fingerprint: 'sha256:abc',
           },
           {
             toolId: 'cursor',
             toolName: 'Cursor',
-            mergeResult: { toolId: 'cursor', added: [createMockServer('new')], kept: [], conflicts: [] },
+            mergeResult: { toolId: 'cursor', added: [createMockServer('new')], updated: [], removed: [], kept: [], sourceUpdates: [], sourceRemovals: [], conflicts: [] },
             hasChanges: true,
+            fingerprint: 'missing',
           },
         ];
 
@@ -11,13 +11,16 @@ import {
   McpConfigPreview,
   McpImportResult,
   McpImportMode,
+  McpSyncLog,
 } from '@/types';
+import { useAppStore } from './app-store';
 
 interface McpState {
   // Config
   sourceMode: McpSourceMode;
   servers: McpServer[];
   enabledTools: string[];
+  autoSync: boolean;
   
   // Tool statuses
   toolStatuses: McpToolStatus[];
@@ -29,12 +32,18 @@ interface McpState {
   // Sync preview
   syncPreviews: McpSyncPreview[];
   activeConflicts: McpServerConflict[];
+
+  // Auto-sync
+  reviewQueue: string[];
   
   // Actions
   loadConfig: () => Promise<void>;
   setSourceMode: (mode: McpSourceMode) => Promise<void>;
   loadSourceServers: () => Promise<void>;
   loadToolStatuses: () => Promise<void>;
+  setToolStatuses: (statuses: McpToolStatus[]) => void;
+  setAutoSync: (enabled: boolean) => Promise<void>;
+  loadSyncLog: () => Promise<McpSyncLog | null>;
   
   // Server management (app-managed mode)
   addServer: (server: McpServer) => Promise<void>;
@@ -74,11 +83,13 @@ export const useMcpStore = create<McpState>()((set, get) => ({
   sourceMode: 'claude',
   servers: [],
   enabledTools: [],
+  autoSync: false,
   toolStatuses: [],
   isLoading: false,
   error: null,
   syncPreviews: [],
   activeConflicts: [],
+  reviewQueue: [],
 
   // Load MCP config
   loadConfig: async () => {
@@ -89,6 +100,7 @@ export const useMcpStore = create<McpState>()((set, get) => ({
         sourceMode: config.sourceMode,
         servers: config.servers,
         enabledTools: config.enabledTools,
+        autoSync: config.autoSync,
         isLoading: false,
       });
       // Also load the source servers based on mode
@@ -108,6 +120,7 @@ export const useMcpStore = create<McpState>()((set, get) => ({
         sourceMode: config.sourceMode,
         servers: config.servers,
         enabledTools: config.enabledTools,
+        autoSync: config.autoSync,
         isLoading: false,
       });
       await get().loadSourceServers();
@@ -137,6 +150,31 @@ export const useMcpStore = create<McpState>()((set, get) => ({
     }
   },
 
+  // Statuses pushed by the backend watcher after an MCP config changed on disk
+  setToolStatuses: (statuses) => set({ toolStatuses: statuses }),
+
+  // Toggle syncing enabled tools whenever the source changes
+  setAutoSync: async (enabled: boolean) => {
+    try {
+      const config: McpConfig = await invoke('set_mcp_auto_sync', { enabled });
+      set({ autoSync: config.autoSync });
+    } catch (err) {
+      set({ error: String(err) });
+    }
+  },
+
+  // Load the auto-sync log and the tools waiting for review
+  loadSyncLog: async () => {
+    try {
+      const log: McpSyncLog = await invoke('get_mcp_sync_log');
+      set({ reviewQueue: log.reviewQueue });
+      return log;
+    } catch (err) {
+      set({ error: String(err) });
+      return null;
+    }
+  },
+
   // Add server (app-managed mode)
   addServer: async (server: McpServer) => {
     set({ isLoading: true, error: null });
@@ -325,6 +363,7 @@ export const useMcpStore = create<McpState>()((set, get) => ({
       const result: McpSyncResult = await invoke('sync_mcp_to_tool', {
         toolId,
         resolvedConflicts: resolvedConflicts || null,
+        backupSettings: useAppStore.getState().backupSettings,
       });
       set({ isLoading: false });
       await get().loadToolStatuses();
@@ -339,8 +378,15 @@ export const useMcpStore = create<McpState>()((set, get) => ({
   syncToAll: async () => {
     set({ isLoading: true, error: null });
     try {
-      const results: McpSyncResult[] = await invoke('sync_mcp_to_all');
-      set({ isLoading: false });
+      // Abort if a config changed since the previews were shown
+      const expectedFingerprints = Object.fromEntries(
+        get().syncPreviews.map((preview) => [preview.toolId, preview.fingerprint])
+      );
+      const results: McpSyncResult[] = await invoke('sync_mcp_to_all', {
+        expectedFingerprints,
+        backupSettings: useAppStore.getState().backupSettings,
+      });
+      set({ isLoading: false, syncPreviews: [] });
       await get().loadToolStatuses();
       return results;
     } catch (err) {
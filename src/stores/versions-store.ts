@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { ConfigVersion, VersionMetadata } from '@/types';
+import { useAppStore } from './app-store';
 
 interface VersionsState {
   versions: VersionMetadata[];
@@ -43,7 +44,16 @@ export const useVersionsStore = create<VersionsState>((set, get) => ({
   fetchVersions: async (configId) => {
     set({ isLoading: true, error: null });
     try {
-      const versions = await invoke<VersionMetadata[]>('list_versions', { configId });
+      // Passing the file links it to the config, so snapshots of writes that
+      // only know the path (MCP sync, the CLI) are listed here too
+      const configFile = useAppStore
+        .getState()
+        .toolConfigs.flatMap((toolConfig) => toolConfig.configFiles)
+        .find((file) => file.id === configId);
+      const versions = await invoke<VersionMetadata[]>('list_versions', {
+        configId,
+        path: configFile ? (configFile.sshPath ?? configFile.path) : null,
+      });
       set({ versions, isLoading: false });
     } catch (error) {
       set({ error: String(error), isLoading: false, versions: [] });
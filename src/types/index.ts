@@ -21,6 +21,35 @@ export interface CliTool {
   description?: string;
   // Suggested config files for quick setup (not enforced)
   suggestedConfigs?: SuggestedConfig[];
+  // Settings files the tool merges itself, lowest precedence first
+  layering?: ConfigLayering;
+}
+
+export type LayerScope = 'defaults' | 'user' | 'project' | 'local' | 'enterprise';
+
+export interface ConfigLayer {
+  scope: LayerScope;
+  path: string;         // Project-relative paths resolve against the project root
+  platformPaths?: { linux: string; macos: string; windows: string };
+}
+
+export interface ConfigLayering {
+  layers: ConfigLayer[];
+  mergeArrays: string[]; // Dot paths whose arrays are concatenated across layers
+}
+
+// Result of effective_config: merged settings with the layer behind each leaf
+export interface EffectiveConfig {
+  toolId: string;
+  value: unknown;
+  sources: Record<string, { scope: LayerScope; file: string }>; // keyed by JSON pointer
+  layers: {
+    scope: LayerScope;
+    path: string;
+    file: string | null;
+    loaded: boolean;
+    error: string | null;
+  }[];
 }
 
 // Suggested config for quick setup
@@ -62,6 +91,21 @@ export interface DetectedTool {
   exists: boolean;
 }
 
+// Returned by read_file / read_json_path / read_json_prefix. Pass `token`
+// back as `expectedToken` to refuse a save over an external edit.
+export interface FileContent {
+  content: string;
+  token: string;
+}
+
+// Payload of the backend 'config-changed' event
+export interface ConfigChangeEvent {
+  path: string;
+  kind: 'created' | 'modified' | 'removed';
+  hash: string;
+  mcp: boolean;
+}
+
 export interface ConfigContent {
   path: string;
   content: string;
@@ -141,6 +185,100 @@ export interface VersionMetadata {
   isDefault: boolean;
 }
 
+// Retention rules for automatic snapshots
+export interface RetentionRules {
+  keepLast: number;           // Newest auto snapshots always kept
+  keepDailyDays: number;      // Then one per day for this many days
+}
+
+// Automatic versioning policy (versions/policy.json)
+export interface VersionPolicy {
+  autoSnapshots: boolean;
+  coalesceWindowSecs: number; // Writes this close together share a snapshot
+  retention: RetentionRules;
+  overrides: Record<string, RetentionRules>; // Keyed by configId
+}
+
+// ============================================
+// Profile Bundle Types (from Rust backend)
+// ============================================
+
+export interface ExportOptions {
+  tools: string[];            // Tool ids whose config files are bundled
+  includeVersions?: boolean;  // Default true
+  includeSidebar?: boolean;   // Default true
+  includeSecrets?: boolean;   // Default false: secrets become "<redacted>"
+}
+
+export interface ProfileFile {
+  entry: string;
+  toolId: string;
+  label: string;
+  path: string;               // Home directory written as ~
+  platformPaths?: { linux: string; macos: string; windows: string };
+  format: ConfigFormat;
+  redacted?: string[];        // JSON pointers, or "line N" for unstructured files
+}
+
+export interface ProfileManifest {
+  formatVersion: number;
+  createdAt: number;
+  sourceOs: string;
+  secretsIncluded: boolean;
+  files: ProfileFile[];
+  hasMcpConfig: boolean;
+  mcpRedacted?: string[];
+  versionCount: number;
+  redactedVersions?: Record<string, string[]>; // Version id -> redacted pointers
+  hasSidebarState: boolean;
+}
+
+export type ImportAction = 'create' | 'overwrite' | 'unchanged';
+
+export interface ImportChange {
+  label: string;
+  path: string;               // Resolved for this machine
+  action: ImportAction;
+  redacted?: string[];        // Secrets with no local value to keep
+}
+
+export interface ImportPreview {
+  manifest: ProfileManifest;
+  changes: ImportChange[];
+  versionsAdded: number;
+  versionsRedacted: number;   // Left out: their secrets were redacted
+  applied: boolean;           // false for a dry run
+}
+
+// ============================================
+// Git History Types (from Rust backend)
+// ============================================
+
+export interface HistoryStatus {
+  enabled: boolean;
+  gitAvailable: boolean;
+  repoPath: string;           // Mirror repository, usable with plain git
+}
+
+// A commit in the history mirror, one per write
+export interface HistoryCommit {
+  id: string;
+  timestamp: number;
+  subject: string;
+  path: string;               // The real file, local or user@host:path
+  mirrorPath: string;         // Path inside the mirror repository
+  origin: string;             // Command that wrote it, or 'external'
+  tool?: string;
+  config?: string;
+  jsonPath?: string;
+}
+
+export interface HistoryShow {
+  commit: HistoryCommit;
+  content: string;
+  patch: string;
+}
+
 // ============================================
 // MCP Settings Sync Types
 // ============================================
@@ -165,10 +303,49 @@ export interface McpConfig {
   sourceMode: McpSourceMode;
   servers: McpServer[];
   enabledTools: string[]; // Tool IDs that are enabled for sync
+  autoSync: boolean; // Sync enabled tools when the source changes on disk
 }
 
-// Tool format type for MCP config
-export type McpToolFormat = 'standard' | 'copilot' | 'opencode';
+export type McpAutoSyncAction = 'applied' | 'queued' | 'failed';
+
+// One tool's outcome of an auto-sync run
+export interface McpSyncLogEntry {
+  timestamp: number;
+  toolId: string;
+  configPath: string;
+  action: McpAutoSyncAction;
+  message: string;
+}
+
+export interface McpSyncLog {
+  entries: McpSyncLogEntry[];
+  reviewQueue: string[]; // Tools skipped by auto-sync because of conflicts
+}
+
+export type McpProbeTransport = 'stdio' | 'http' | 'sse';
+
+export interface McpProbeTool {
+  name: string;
+  description?: string;
+}
+
+// Result of starting a server and running the MCP initialize/tools/list handshake
+export interface McpProbeResult {
+  serverName: string;
+  transport: McpProbeTransport;
+  ok: boolean;
+  latencyMs: number;
+  protocolVersion?: string;
+  serverInfoName?: string;
+  serverVersion?: string;
+  capabilities: Record<string, unknown> | null;
+  tools: McpProbeTool[];
+  stderr: string;
+  error?: string;
+}
+
+// Name of the backend format adapter that reads and writes a tool's MCP servers
+export type McpToolFormat = 'standard' | 'amp' | 'copilot' | 'opencode' | 'vscode' | 'zed' | 'continue' | 'codex';
 
 // MCP tool information with format details
 export interface McpToolInfo {
@@ -177,6 +354,15 @@ export interface McpToolInfo {
   jsonPath: string;
   format: McpToolFormat;
   name: string;
+  projectConfigPath?: string;
+  workspace?: string;
+}
+
+// A registered project root
+export interface Workspace {
+  id: string;
+  name: string;
+  path: string;
 }
 
 // Sync status for a tool
@@ -204,8 +390,12 @@ export interface McpServerConflict {
 // Merge result showing what would change
 export interface McpMergeResult {
   toolId: string;
-  added: McpServer[];     // Servers to add
-  kept: McpServer[];      // Servers already present
+  added: McpServer[];          // Servers to add
+  updated: McpServer[];        // Source edits replacing the tool's copy
+  removed: McpServer[];        // Deleted from source since last sync
+  kept: McpServer[];           // Servers already present
+  sourceUpdates: McpServer[];  // Tool-side edits flowing back to source
+  sourceRemovals: McpServer[]; // Tool-side deletions removed from source
   conflicts: McpServerConflict[];
 }
 
@@ -215,6 +405,7 @@ export interface McpSyncPreview {
   toolName: string;
   mergeResult: McpMergeResult;
   hasChanges: boolean;
+  fingerprint: string;  // Config file fingerprint at preview time
 }
 
 // Conflict resolution choice
@@ -228,6 +419,14 @@ export interface McpSyncResult {
   serversWritten: number;
 }
 
+// A project in ~/.claude.json with project-scoped MCP servers
+export interface ClaudeProject {
+  path: string;
+  name: string;
+  serverCount: number;
+  exists: boolean;
+}
+
 // Full config content preview
 export interface McpConfigPreview {
   toolId: string;
@@ -237,8 +436,47 @@ export interface McpConfigPreview {
   previewContent: string;
 }
 
-// Detected MCP config format from file import
-export type McpDetectedFormat = 'standard' | 'amp' | 'copilot' | 'opencode';
+// Where a ${secret:name} reference is used in the MCP source servers
+export interface SecretUsage {
+  secret: string;
+  serverName: string;
+  envKey: string;
+}
+
+// Secret leak scanner
+export type SecretSeverity = 'low' | 'medium' | 'high';
+
+export interface SecretRemediation {
+  envVar: string;
+  replacement: string;  // e.g. ${GITHUB_TOKEN}, or {env:GITHUB_TOKEN} for OpenCode
+}
+
+export interface SecretFinding {
+  file: string;
+  toolId: string;
+  jsonPointer: string;
+  severity: SecretSeverity;
+  rule: string;         // 'token-prefix' | 'key-name' | 'entropy'
+  description: string;
+  preview: string;      // masked value
+  remediation: SecretRemediation | null;
+}
+
+export interface SecretScanReport {
+  findings: SecretFinding[];
+  scannedFiles: string[];
+  errors: { file: string; message: string }[];
+}
+
+export interface AppliedRemediation {
+  file: string;
+  jsonPointer: string;
+  envVar: string;
+  exportLine: string;
+}
+
+// Detected MCP config format from file import: the adapter that recognized it
+export type McpDetectedFormat = McpToolFormat;
 
 // Result of importing MCP config from file
 export interface McpImportResult {
@@ -288,12 +526,19 @@ export type BackendErrorType =
   | 'PermissionDenied'
   | 'PathResolution'
   | 'JsonParse'
-  | 'JsonPathNotFound';
+  | 'JsonPathNotFound'
+  | 'ConfigParse'
+  | 'UnsupportedFormat'
+  | 'Validation'
+  | 'Transaction'
+  | 'InvalidArgument'
+  | 'Conflict';
 
 // Structured error response from backend
 export interface BackendError {
   error_type: BackendErrorType;
   message: string;
+  details?: unknown;
 }
 
 // Helper to parse backend errors from Tauri invoke
@@ -304,6 +549,7 @@ export function parseBackendError(error: unknown): BackendError | null {
       return {
         error_type: err.error_type as BackendErrorType,
         message: err.message,
+        details: err.details,
       };
     }
   }
@@ -315,10 +561,76 @@ export function isFileNotFoundError(error: BackendError | null): boolean {
   return error?.error_type === 'ConfigNotFound';
 }
 
+// Details of a 'Conflict' error: the file changed on disk since it was read
+export interface ConflictDetails {
+  path: string;
+  currentContent: string | null;
+  token: string;
+}
+
+export function isConflictError(error: BackendError | null): boolean {
+  return error?.error_type === 'Conflict';
+}
+
 // Check if error is a parse/read error (should show error banner)
 export function isFileReadError(error: BackendError | null): boolean {
   if (!error) return false;
-  return ['JsonParse', 'PermissionDenied', 'Io'].includes(error.error_type);
+  return ['JsonParse', 'ConfigParse', 'PermissionDenied', 'Io'].includes(error.error_type);
+}
+
+// ============================================
+// Schema Validation Types (from Rust backend)
+// ============================================
+
+export type DiagnosticSeverity = 'error' | 'warning';
+
+export interface ConfigDiagnostic {
+  pointer: string;
+  message: string;
+  severity: DiagnosticSeverity;
+}
+
+// Identifies the suggested config whose bundled schema applies
+export interface SchemaTarget {
+  toolId: string;
+  label: string;
+}
+
+export interface ValidationReport {
+  hasSchema: boolean;
+  diagnostics: ConfigDiagnostic[];
+}
+
+// ============================================
+// Semantic Diff Types (from Rust backend)
+// ============================================
+
+// One side of a diff_configs call
+export type DiffSource =
+  | { type: 'file'; path: string }
+  | { type: 'backup'; path: string }
+  | { type: 'remote'; path: string }
+  | { type: 'version'; configId: string; versionId: string }
+  | { type: 'content'; content: string; path?: string };
+
+export type DiffKind = 'added' | 'removed' | 'changed' | 'modified';
+
+export interface DiffNode {
+  key: string;
+  path: string; // JSON pointer
+  kind: DiffKind;
+  oldValue?: unknown;
+  newValue?: unknown;
+  children?: DiffNode[];
+}
+
+export interface ConfigDiff {
+  format: ConfigFormat;
+  identical: boolean;
+  added: number;
+  removed: number;
+  changed: number;
+  changes: DiffNode[];
 }
 
 // ============================================